@@ -54,7 +54,7 @@ use tor_keymgr::{config::ArtiKeystoreKind, ArtiNativeKeystore, KeyMgr, KeyMgrBui
 use tor_keymgr::ArtiEphemeralKeystore;
 
 #[cfg(feature = "ctor-keystore")]
-use tor_keymgr::{CTorClientKeystore, CTorServiceKeystore};
+use tor_keymgr::{CTorClientKeystore, CTorRelayKeystore, CTorServiceKeystore};
 
 use futures::lock::Mutex as AsyncMutex;
 use futures::task::SpawnExt;
@@ -292,6 +292,17 @@ impl InertTorClient {
             builder.secondary_stores().push(store);
         }
 
+        #[cfg(feature = "ctor-keystore")]
+        for config in config.storage.keystore().ctor_relay_stores() {
+            let store: Box<dyn Keystore> = Box::new(CTorRelayKeystore::from_path_and_mistrust(
+                config.path(),
+                permissions,
+                config.id().clone(),
+            )?);
+
+            builder.secondary_stores().push(store);
+        }
+
         let keymgr = builder
             .build()
             .map_err(|_| internal!("failed to build keymgr"))?;
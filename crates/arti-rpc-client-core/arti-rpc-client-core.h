@@ -356,7 +356,8 @@ typedef int ArtiRpcResponseType;
  * The peer has closed our connection; possibly because it is shutting down.
  *
  * (This error was generated by the library,
- * based on the connection being closed or reset from the peer.)
+ * based on the connection being closed in an orderly way,
+ * or failing for some reason other than a reset.)
  */
 #define ARTI_RPC_STATUS_SHUTDOWN 6
 
@@ -403,7 +404,8 @@ typedef int ArtiRpcResponseType;
 
 /**
  * All of our attempts to connect to Arti failed,
- * or we reached an explicit instruction to "abort" our connection attempts.
+ * or we reached an explicit instruction to "abort" our connection attempts,
+ * or we used up the attempts or time allowed for connecting.
  */
 #define ARTI_RPC_STATUS_ALL_CONNECT_ATTEMPTS_FAILED 13
 
@@ -421,6 +423,22 @@ typedef int ArtiRpcResponseType;
  */
 #define ARTI_RPC_STATUS_BAD_CONNECT_POINT_PATH 15
 
+/**
+ * Our connection to the peer was reset or broken abruptly,
+ * rather than being closed in an orderly way.
+ *
+ * (This error was generated by the library,
+ * based on an IO error from the connection.)
+ */
+#define ARTI_RPC_STATUS_CONNECTION_RESET 16
+
+/**
+ * An operation did not finish within the time we allowed for it.
+ *
+ * (This error was generated by the library.)
+ */
+#define ARTI_RPC_STATUS_TIMEOUT 17
+
 
 
 
@@ -482,6 +500,77 @@ ArtiRpcStatus arti_rpc_conn_builder_prepend_entry(const struct ArtiRpcConnBuilde
                                                   const char *entry,
                                                   ArtiRpcError **error_out);
 
+/**
+ * Limit the number of connect points that `builder` will try when connecting.
+ *
+ * If `max_attempts` is 0, there is no limit.
+ *
+ * If this many connect points are tried without success,
+ * [`arti_rpc_conn_builder_connect`] will give up and return
+ * `ARTI_RPC_STATUS_ALL_CONNECT_ATTEMPTS_FAILED`.
+ *
+ * On success, return `ARTI_RPC_STATUS_SUCCESS`.
+ * Otherwise return some other status code, and set
+ * `*error_out` (if provided) to a newly allocated error object.
+ *
+ * # Ownership
+ *
+ * The caller is responsible for making sure that `*error_out`,
+ * if set, is eventually freed.
+ */
+ArtiRpcStatus arti_rpc_conn_builder_set_max_connect_attempts(const struct ArtiRpcConnBuilder *builder,
+                                                             uint32_t max_attempts,
+                                                             ArtiRpcError **error_out);
+
+/**
+ * Limit the total time, in milliseconds,
+ * that `builder` will spend trying connect points when connecting.
+ *
+ * If `deadline_msec` is 0, there is no limit.
+ *
+ * This limit is checked before each connect point is tried;
+ * it does not interrupt a connection attempt that is already in progress.
+ * Once it has passed, [`arti_rpc_conn_builder_connect`] will give up
+ * and return `ARTI_RPC_STATUS_ALL_CONNECT_ATTEMPTS_FAILED`.
+ *
+ * On success, return `ARTI_RPC_STATUS_SUCCESS`.
+ * Otherwise return some other status code, and set
+ * `*error_out` (if provided) to a newly allocated error object.
+ *
+ * # Ownership
+ *
+ * The caller is responsible for making sure that `*error_out`,
+ * if set, is eventually freed.
+ */
+ArtiRpcStatus arti_rpc_conn_builder_set_connect_deadline_msec(const struct ArtiRpcConnBuilder *builder,
+                                                              uint64_t deadline_msec,
+                                                              ArtiRpcError **error_out);
+
+/**
+ * Limit the total time, in milliseconds,
+ * that [`arti_rpc_conn_builder_connect`] may take when using `builder`,
+ * including opening a connection and authenticating on it.
+ *
+ * If `timeout_msec` is 0, there is no limit.
+ *
+ * Unlike the limit set by [`arti_rpc_conn_builder_set_connect_deadline_msec`],
+ * this one interrupts a connection attempt that is already in progress.
+ * Once it has passed, [`arti_rpc_conn_builder_connect`] will give up
+ * and return `ARTI_RPC_STATUS_TIMEOUT`.
+ *
+ * On success, return `ARTI_RPC_STATUS_SUCCESS`.
+ * Otherwise return some other status code, and set
+ * `*error_out` (if provided) to a newly allocated error object.
+ *
+ * # Ownership
+ *
+ * The caller is responsible for making sure that `*error_out`,
+ * if set, is eventually freed.
+ */
+ArtiRpcStatus arti_rpc_conn_builder_set_connect_timeout_msec(const struct ArtiRpcConnBuilder *builder,
+                                                             uint64_t timeout_msec,
+                                                             ArtiRpcError **error_out);
+
 /**
  * Use `builder` to open a new RPC connection to Arti.
  *
@@ -515,6 +604,24 @@ ArtiRpcStatus arti_rpc_conn_builder_connect(const struct ArtiRpcConnBuilder *bui
  */
 const char *arti_rpc_conn_get_session_id(const ArtiRpcConn *rpc_conn);
 
+/**
+ * Given a pointer to an RPC connection, return a description of the connect point
+ * that was used to open it.
+ *
+ * This is intended for diagnostics:
+ * it tells you which entry on the connect point search path succeeded.
+ * Its format is not specified, and may change in the future.
+ *
+ * The caller should be prepared for a possible NULL return, in case
+ * the connect point is not known.
+ *
+ * # Ownership
+ *
+ * The resulting string is a reference to part of the `ArtiRpcConn`.
+ * It lives for no longer than the underlying `ArtiRpcConn` object.
+ */
+const char *arti_rpc_conn_connect_point(const ArtiRpcConn *rpc_conn);
+
 /**
  * Run an RPC request over `rpc_conn` and wait for a successful response.
  *
@@ -579,6 +686,32 @@ ArtiRpcStatus arti_rpc_conn_cancel_handle(const ArtiRpcConn *rpc_conn,
                                           const ArtiRpcHandle *handle,
                                           ArtiRpcError **error_out);
 
+/**
+ * Attempt to cancel the request on `rpc_conn` with the provided `request_id`.
+ *
+ * The `request_id` must be the JSON encoding of the request's ID:
+ * for example, `7` or `"my-request"` (including the quotation marks).
+ *
+ * Like `arti_rpc_conn_cancel_handle`, this asks Arti to cancel the request.
+ * Additionally, on success, it marks the request as cancelled locally:
+ * any responses that have been received for it are discarded,
+ * and any later (or ongoing) call to `arti_rpc_handle_wait` on its handle
+ * returns `ARTI_RPC_STATUS_REQUEST_COMPLETED`.
+ *
+ * If we are not waiting for any responses to this request
+ * (for example, because it has already been cancelled,
+ * or because its final response has already been received),
+ * return `ARTI_RPC_STATUS_REQUEST_COMPLETED`.
+ *
+ * On success, return `ARTI_RPC_STATUS_SUCCESS`.
+ *
+ * Otherwise return some other status code,
+ * and set `*error_out` (if provided) to a newly allocated error object.
+ */
+ArtiRpcStatus arti_rpc_request_cancel(const ArtiRpcConn *rpc_conn,
+                                      const char *request_id,
+                                      ArtiRpcError **error_out);
+
 /**
  * Wait until some response arrives on an arti_rpc_handle, or until an error occurs.
  *
@@ -20,7 +20,7 @@ mod connimpl;
 mod stream;
 
 use crate::util::Utf8CString;
-pub use builder::{BuilderError, ConnPtDescription, RpcConnBuilder};
+pub use builder::{BuilderError, ConnPtDescription, ConnectBudget, RpcConnBuilder};
 pub use connimpl::RpcConn;
 use serde::{de::DeserializeOwned, Deserialize};
 pub use stream::StreamError;
@@ -91,6 +91,10 @@ impl SuccessResponse {
 /// An Update Response from Arti, with information about the progress of a request.
 ///
 /// This is the complete message, including `id` and `update` fields.
+///
+/// This is also used for the `updates_done` response
+/// that tells us that a request will receive no more updates:
+/// in that case, the message has an `updates_done` field instead of an `update` field.
 //
 // Invariant: it is valid JSON and contains no NUL bytes or newlines.
 // TODO RPC: check that the newline invariant is enforced in constructors.
@@ -202,6 +206,22 @@ impl RpcConn {
         self.session.as_ref()
     }
 
+    /// Return a description of the connect point that was used to open this connection.
+    ///
+    /// This is mainly useful for diagnostics,
+    /// to tell which entry of a multi-entry search path actually succeeded.
+    ///
+    /// Returns None if this connection was not opened via [`RpcConnBuilder`].
+    pub fn connect_point(&self) -> Option<&str> {
+        self.connect_point.as_ref().map(AsRef::as_ref)
+    }
+
+    /// As [`RpcConn::connect_point`], but return a C string.
+    #[cfg(feature = "ffi")]
+    pub(crate) fn connect_point_cstr(&self) -> Option<&std::ffi::CStr> {
+        self.connect_point.as_ref().map(AsRef::as_ref)
+    }
+
     /// Run a command, and wait for success or failure.
     ///
     /// Note that this function will return `Err(.)` only if sending the command or getting a
@@ -279,6 +299,26 @@ impl RpcConn {
         }
     }
 
+    /// Cancel a request by ID, and stop waiting for its responses.
+    ///
+    /// Unlike [`cancel`](RpcConn::cancel), on success this also marks the request
+    /// as cancelled locally:
+    /// any responses that have been received for it are discarded,
+    /// and any attempt to wait for it (including one that is already in progress)
+    /// fails with [`ProtoError::RequestCompleted`].
+    ///
+    /// Returns [`ProtoError::RequestCompleted`] if we are not waiting for any responses
+    /// for this request, or if Arti reports that it has already finished.
+    /// In the latter case, the request's final response can still be received as usual.
+    pub fn cancel_request(&self, request_id: &AnyRequestId) -> Result<(), ProtoError> {
+        if !self.receiver().is_pending(request_id) {
+            return Err(ProtoError::RequestCompleted);
+        }
+        self.cancel(request_id)?;
+        self.receiver().forget_request(request_id);
+        Ok(())
+    }
+
     /// Like `execute`, but don't wait.  This lets the caller see the
     /// request ID and  maybe cancel it.
     pub fn execute_with_handle(&self, cmd: &str) -> Result<RequestHandle, ProtoError> {
@@ -383,6 +423,23 @@ pub enum ShutdownError {
     ConnectionClosed,
 }
 
+impl ShutdownError {
+    /// Return true if this error indicates that the connection was reset or broken abruptly,
+    /// rather than closed in an orderly way.
+    pub fn is_reset(&self) -> bool {
+        use io::ErrorKind as K;
+        match self {
+            ShutdownError::Read(e) | ShutdownError::Write(e) => matches!(
+                e.kind(),
+                K::ConnectionReset | K::ConnectionAborted | K::BrokenPipe
+            ),
+            ShutdownError::ProtocolViolated(_)
+            | ShutdownError::ProtocolViolationReport(_)
+            | ShutdownError::ConnectionClosed => false,
+        }
+    }
+}
+
 impl From<crate::msgs::response::DecodeResponseError> for ShutdownError {
     fn from(value: crate::msgs::response::DecodeResponseError) -> Self {
         use crate::msgs::response::DecodeResponseError::*;
@@ -441,8 +498,8 @@ pub struct ConnectFailure {
     final_desc: Option<builder::ConnPtDescription>,
     /// The final error explaining why we couldn't connect.
     ///
-    /// This is either an abort, an AllAttemptsDeclined, or an error that prevented the
-    /// search process from even beginning.
+    /// This is either an abort, an AllAttemptsDeclined, a BudgetExhausted, a Timeout,
+    /// or an error that prevented the search process from even beginning.
     #[source]
     pub(crate) final_error: ConnectError,
 }
@@ -455,7 +512,10 @@ impl std::fmt::Display for ConnectFailure {
                 f,
                 " ({} attempts failed{})",
                 self.declined.len(),
-                if matches!(self.final_error, ConnectError::AllAttemptsDeclined) {
+                if matches!(
+                    self.final_error,
+                    ConnectError::AllAttemptsDeclined | ConnectError::BudgetExhausted
+                ) {
                     ""
                 } else {
                     " before fatal error"
@@ -546,6 +606,14 @@ pub enum ConnectError {
     /// All attempted connect points were declined, and none were aborted.
     #[error("All connect points were declined (or there were none)")]
     AllAttemptsDeclined,
+    /// We gave up before trying every connect point,
+    /// since we ran out of the attempts or time allowed by our [`ConnectBudget`].
+    #[error("Exhausted our budget for connect attempts")]
+    BudgetExhausted,
+    /// We gave up because connecting took longer than the timeout
+    /// set with [`RpcConnBuilder::set_connect_timeout`](crate::RpcConnBuilder::set_connect_timeout).
+    #[error("Timed out while connecting to Arti")]
+    Timeout,
     /// A connect file or directory was given as a relative path.
     /// (Only absolute paths are supported).
     #[error("Connect file was given as a relative path.")]
@@ -597,6 +665,8 @@ impl HasClientErrorAction for ConnectError {
             E::BadMessage(_) => A::Abort,
             E::ProtoError(e) => e.client_action(),
             E::AllAttemptsDeclined => A::Abort,
+            E::BudgetExhausted => A::Abort,
+            E::Timeout => A::Abort,
             E::AuthenticationNotSupported => A::Decline,
             E::ServerAddressMismatch { .. } => A::Abort,
             E::CookieMismatch => A::Abort,
@@ -736,6 +806,50 @@ mod test {
         assert_eq!(map.get("xyz"), Some(&serde_json::Value::Number(3.into())));
     }
 
+    #[test]
+    fn cancel_request() {
+        let (conn, sock) = dummy_connected();
+
+        let fake_arti_thread = thread::spawn(move || {
+            let mut sock = BufReader::new(sock);
+            let mut read_request = || {
+                let mut s = String::new();
+                let _len = sock.read_line(&mut s).unwrap();
+                ValidatedRequest::from_string_strict(s.as_ref()).unwrap()
+            };
+            let _request = read_request();
+            let cancel = read_request();
+            // Tell the client that the cancellation succeeded...
+            let response = serde_json::json!({
+                "id": cancel.id().clone(),
+                "result": {}
+            });
+            write_val(sock.get_mut(), &response);
+            sock // prevent close
+        });
+
+        let hnd = conn
+            .execute_with_handle(r#"{"id":7,"obj":"fred","method":"arti:x-frob","params":{}}"#)
+            .unwrap();
+        let id = AnyRequestId::Number(7);
+        assert_eq!(hnd.id(), &id);
+        conn.cancel_request(&id).unwrap();
+
+        // The request is no longer pending.
+        assert!(matches!(hnd.wait(), Err(ProtoError::RequestCompleted)));
+        assert!(matches!(
+            conn.cancel_request(&id),
+            Err(ProtoError::RequestCompleted)
+        ));
+        // We never sent this one.
+        assert!(matches!(
+            conn.cancel_request(&AnyRequestId::Number(8)),
+            Err(ProtoError::RequestCompleted)
+        ));
+
+        let _sock = fake_arti_thread.join().unwrap();
+    }
+
     #[test]
     fn complex() {
         use std::sync::atomic::Ordering::SeqCst;
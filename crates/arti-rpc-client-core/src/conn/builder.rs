@@ -5,6 +5,8 @@ use std::{
     io::{self},
     path::PathBuf,
     str::FromStr as _,
+    sync::mpsc::RecvTimeoutError,
+    time::{Duration, Instant},
 };
 
 use fs_mistrust::Mistrust;
@@ -15,7 +17,9 @@ use tor_rpc_connect::{
     ClientErrorAction, HasClientErrorAction, ParsedConnectPoint,
 };
 
-use crate::{conn::ConnectError, llconn, msgs::response::UnparsedResponse, RpcConn};
+use crate::{
+    conn::ConnectError, llconn, msgs::response::UnparsedResponse, util::Utf8CString, RpcConn,
+};
 
 use super::ConnectFailure;
 
@@ -43,6 +47,44 @@ pub struct RpcConnBuilder {
     ///
     /// These entries are stored in reverse order.
     prepend_path_reversed: Vec<SearchEntry>,
+
+    /// Limits on how much work we do while searching for a connect point.
+    budget: ConnectBudget,
+
+    /// The longest time that [`RpcConnBuilder::connect`] may take, in total.
+    ///
+    /// If `None`, there is no limit.
+    connect_timeout: Option<Duration>,
+}
+
+/// Limits on the total effort [`RpcConnBuilder::connect`] spends walking its search path.
+///
+/// By default, there are no limits:
+/// every connect point on the search path is tried until one succeeds or aborts.
+///
+/// These limits are checked before each connect attempt:
+/// they do not interrupt an attempt that is already in progress.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct ConnectBudget {
+    /// The largest number of connect points to try.
+    ///
+    /// If `None`, there is no limit.
+    pub max_attempts: Option<usize>,
+    /// The longest total time to spend trying connect points,
+    /// measured from the start of the search.
+    ///
+    /// If `None`, there is no limit.
+    pub total_deadline: Option<Duration>,
+}
+
+impl ConnectBudget {
+    /// Return true if, having made `attempts` attempts
+    /// over `elapsed` time, we should not make any more.
+    fn is_exhausted(&self, attempts: usize, elapsed: Duration) -> bool {
+        self.max_attempts.is_some_and(|max| attempts >= max)
+            || self.total_deadline.is_some_and(|max| elapsed >= max)
+    }
 }
 
 /// A single entry in the search path used to find connect points.
@@ -237,6 +279,39 @@ impl RpcConnBuilder {
         });
     }
 
+    /// Return the [`ConnectBudget`] that this builder will honor when connecting.
+    pub fn connect_budget(&self) -> ConnectBudget {
+        self.budget
+    }
+
+    /// Replace the [`ConnectBudget`] that this builder will honor when connecting.
+    ///
+    /// If the budget is exhausted before any connect point succeeds or aborts,
+    /// [`connect`](RpcConnBuilder::connect) fails with [`ConnectError::BudgetExhausted`].
+    pub fn set_connect_budget(&mut self, budget: ConnectBudget) {
+        self.budget = budget;
+    }
+
+    /// Return the longest time that [`connect`](RpcConnBuilder::connect) may take, if any.
+    pub fn connect_timeout(&self) -> Option<Duration> {
+        self.connect_timeout
+    }
+
+    /// Limit the total time that [`connect`](RpcConnBuilder::connect) may take,
+    /// including opening a connection and authenticating on it.
+    ///
+    /// If `None`, there is no limit.
+    ///
+    /// Unlike the deadline of the [`ConnectBudget`],
+    /// this limit interrupts a connection attempt that is already in progress:
+    /// once it passes, `connect` fails with [`ConnectError::Timeout`].
+    /// The interrupted attempt keeps running on a background thread
+    /// until it finishes on its own,
+    /// and any connection that it opens is then closed.
+    pub fn set_connect_timeout(&mut self, timeout: Option<Duration>) {
+        self.connect_timeout = timeout;
+    }
+
     /// Prepend the application-provided [`SearchLocation`] to the path.
     fn prepend_internal(&mut self, location: SearchLocation) {
         self.prepend_path_reversed.push(SearchEntry {
@@ -284,6 +359,36 @@ impl RpcConnBuilder {
 
     /// Try to connect to an Arti process as specified by this Builder.
     pub fn connect(&self) -> Result<RpcConn, ConnectFailure> {
+        let Some(timeout) = self.connect_timeout else {
+            return self.connect_without_timeout();
+        };
+
+        // Our IO is blocking, so we run the attempt on its own thread,
+        // and stop waiting for it once the timeout has passed.
+        let (tx, rx) = std::sync::mpsc::sync_channel(1);
+        let builder = self.clone();
+        let handle = std::thread::spawn(move || {
+            // If this fails, we have timed out, and nobody is listening:
+            // dropping the result closes any connection we made.
+            let _ignore_err = tx.send(builder.connect_without_timeout());
+        });
+
+        match rx.recv_timeout(timeout) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => Err(ConnectFailure {
+                declined: vec![],
+                final_desc: None,
+                final_error: ConnectError::Timeout,
+            }),
+            Err(RecvTimeoutError::Disconnected) => match handle.join() {
+                Err(panic) => std::panic::resume_unwind(panic),
+                Ok(()) => unreachable!("connect thread exited without sending a result"),
+            },
+        }
+    }
+
+    /// As [`RpcConnBuilder::connect`], but ignore our `connect_timeout`.
+    fn connect_without_timeout(&self) -> Result<RpcConn, ConnectFailure> {
         let resolver = tor_config_path::arti_client_base_resolver();
         // TODO RPC: Make this configurable.  (Currently, you can override it with
         // the environment variable FS_MISTRUST_DISABLE_PERMISSIONS_CHECKS.)
@@ -294,13 +399,27 @@ impl RpcConnBuilder {
             final_desc: None,
             final_error: e,
         })?;
+        let started = Instant::now();
         let mut declined = Vec::new();
-        for (description, load_result) in all_entries
+        for (attempts, (description, load_result)) in all_entries
             .into_iter()
             .flat_map(|ent| ent.load(&resolver, &mistrust, &options))
+            .enumerate()
         {
+            if self.budget.is_exhausted(attempts, started.elapsed()) {
+                return Err(ConnectFailure {
+                    declined,
+                    final_desc: None,
+                    final_error: ConnectError::BudgetExhausted,
+                });
+            }
             match load_result.and_then(|e| try_connect(&e, &resolver, &mistrust)) {
-                Ok(conn) => return Ok(conn),
+                Ok(mut conn) => {
+                    // (This can only fail if the description contains a NUL,
+                    // in which case it isn't much use as a C string anyway.)
+                    conn.connect_point = Utf8CString::try_from(description.to_string()).ok();
+                    return Ok(conn);
+                }
                 Err(e) => match e.client_action() {
                     ClientErrorAction::Abort => {
                         return Err(ConnectFailure {
@@ -543,3 +662,168 @@ impl<'a> Iterator for ConnPtIterator<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    // @@ begin test lint list maintained by maint/add_warning @@
+    #![allow(clippy::bool_assert_comparison)]
+    #![allow(clippy::clone_on_copy)]
+    #![allow(clippy::dbg_macro)]
+    #![allow(clippy::mixed_attributes_style)]
+    #![allow(clippy::print_stderr)]
+    #![allow(clippy::print_stdout)]
+    #![allow(clippy::single_char_pattern)]
+    #![allow(clippy::unwrap_used)]
+    #![allow(clippy::unchecked_duration_subtraction)]
+    #![allow(clippy::useless_vec)]
+    #![allow(clippy::needless_pass_by_value)]
+    //! <!-- @@ end test lint list maintained by maint/add_warning @@ -->
+
+    use super::*;
+
+    /// Prepend `n` connect points that can never be reached to the search path of `builder`.
+    #[cfg(unix)]
+    fn prepend_unreachable(builder: &mut RpcConnBuilder, n: usize) {
+        for idx in 0..n {
+            builder.prepend_literal_entry(format!(
+                r#"
+[connect]
+socket = "unix:/this/directory/does/not/exist/arti-rpc-{idx}"
+auth = "none"
+"#
+            ));
+        }
+    }
+
+    /// Return a builder whose search path begins with `n` connect points
+    /// that can never be reached.
+    #[cfg(unix)]
+    fn unreachable_builder(n: usize) -> RpcConnBuilder {
+        let mut builder = RpcConnBuilder::new();
+        prepend_unreachable(&mut builder, n);
+        builder
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn budget_max_attempts() {
+        let mut builder = unreachable_builder(5);
+        let mut budget = ConnectBudget::default();
+        budget.max_attempts = Some(2);
+        builder.set_connect_budget(budget);
+
+        let err = builder.connect().unwrap_err();
+        assert!(matches!(err.final_error, ConnectError::BudgetExhausted));
+        assert!(err.fatal_error_origin().is_none());
+        assert_eq!(err.declined_attempt_outcomes().count(), 2);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn budget_deadline() {
+        let mut builder = unreachable_builder(100);
+        let mut budget = ConnectBudget::default();
+        budget.total_deadline = Some(Duration::ZERO);
+        builder.set_connect_budget(budget);
+
+        let start = Instant::now();
+        let err = builder.connect().unwrap_err();
+        assert!(start.elapsed() < Duration::from_secs(10));
+        assert!(matches!(err.final_error, ConnectError::BudgetExhausted));
+        assert_eq!(err.declined_attempt_outcomes().count(), 0);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn records_connect_point() {
+        use std::io::{BufRead as _, BufReader, Write as _};
+        use std::os::unix::net::UnixListener;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let socket_path = dir.path().join("rpc_socket");
+        let listener = UnixListener::bind(&socket_path).unwrap();
+
+        // A minimal server: send a banner, and accept inherent authentication.
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut writer = stream.try_clone().unwrap();
+            let mut reader = BufReader::new(stream);
+            writer.write_all(b"{\"arti_rpc\":{}}\n").unwrap();
+
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let request: serde_json::Value = serde_json::from_str(&line).unwrap();
+            assert_eq!(request["method"], "auth:authenticate");
+            let reply = serde_json::json!({
+                "id": request["id"],
+                "result": { "session": "fake-session" },
+            });
+            writeln!(writer, "{reply}").unwrap();
+        });
+
+        #[allow(clippy::disallowed_methods)] // This is just a test.
+        let working_entry = format!(
+            r#"
+[connect]
+socket = "unix:{}"
+auth = "none"
+"#,
+            socket_path.display()
+        );
+        let mut builder = RpcConnBuilder::new();
+        builder.prepend_literal_entry(working_entry.clone());
+        // These are considered before the working entry.
+        prepend_unreachable(&mut builder, 2);
+
+        let conn = builder.connect().unwrap();
+        server.join().unwrap();
+
+        assert_eq!(conn.session().unwrap().as_ref(), "fake-session");
+        let expected = format!(
+            "connect point in literal string {:?}, from application",
+            working_entry
+        );
+        assert_eq!(conn.connect_point(), Some(expected.as_str()));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn connect_timeout() {
+        use std::os::unix::net::UnixListener;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let socket_path = dir.path().join("rpc_socket");
+        let listener = UnixListener::bind(&socket_path).unwrap();
+
+        // A server that accepts our connection, but never sends a banner.
+        let (tx_done, rx_done) = std::sync::mpsc::channel::<()>();
+        let server = std::thread::spawn(move || {
+            let (_stream, _) = listener.accept().unwrap();
+            // Hold the connection open until the test is finished.
+            let _ = rx_done.recv();
+        });
+
+        #[allow(clippy::disallowed_methods)] // This is just a test.
+        let entry = format!(
+            r#"
+[connect]
+socket = "unix:{}"
+auth = "none"
+"#,
+            socket_path.display()
+        );
+        let mut builder = RpcConnBuilder::new();
+        builder.prepend_literal_entry(entry);
+        builder.set_connect_timeout(Some(Duration::from_millis(100)));
+        assert_eq!(builder.connect_timeout(), Some(Duration::from_millis(100)));
+
+        let start = Instant::now();
+        let err = builder.connect().unwrap_err();
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        assert!(start.elapsed() < Duration::from_secs(10));
+        assert!(matches!(err.final_error, ConnectError::Timeout));
+
+        drop(tx_done);
+        server.join().unwrap();
+    }
+}
@@ -19,6 +19,7 @@ use crate::{
         response::ValidatedResponse,
         AnyRequestId, ObjectId,
     },
+    util::Utf8CString,
 };
 
 use super::{ProtoError, ShutdownError};
@@ -75,8 +76,6 @@ struct ReceiverState {
     /// There is an entry in this map for every request that we have sent,
     /// unless we have received a final response for that request,
     /// or we have cancelled that request.
-    ///
-    /// (TODO: We might handle cancelling differently.)
     pending: HashMap<AnyRequestId, RequestState>,
     /// A reader that we use to receive replies from Arti.
     ///
@@ -161,6 +160,9 @@ pub struct RpcConn {
     /// If set, we are authenticated and we have negotiated a session that has
     /// this ObjectID.
     pub(super) session: Option<ObjectId>,
+
+    /// If set, a description of the connect point that we used to open this connection.
+    pub(super) connect_point: Option<Utf8CString>,
 }
 
 /// Instruction to alert some additional condvar(s) before releasing our lock and returning
@@ -199,9 +201,15 @@ impl RpcConn {
             }),
             writer: Mutex::new(writer),
             session: None,
+            connect_point: None,
         }
     }
 
+    /// Return the [`Receiver`] for this connection.
+    pub(super) fn receiver(&self) -> &Receiver {
+        &self.receiver
+    }
+
     /// Send the request in `msg` on this connection, and return a RequestHandle
     /// to wait for a reply.
     ///
@@ -260,6 +268,30 @@ impl RpcConn {
 }
 
 impl Receiver {
+    /// Return true if we are still expecting responses for the request with `id`.
+    pub(super) fn is_pending(&self, id: &AnyRequestId) -> bool {
+        let state = self.state.lock().expect("poisoned");
+        state.pending.contains_key(id)
+    }
+
+    /// Stop expecting responses for the request with `id`,
+    /// discarding any that have been queued for it.
+    ///
+    /// Any thread that is waiting on this request will be woken up,
+    /// and will get a [`ProtoError::RequestCompleted`].
+    ///
+    /// Return false if we were not expecting any responses for this request.
+    pub(super) fn forget_request(&self, id: &AnyRequestId) -> bool {
+        let mut state = self.state.lock().expect("poisoned");
+        let Some(ent) = state.pending.remove(id) else {
+            return false;
+        };
+        if let Some(cv) = &ent.waiter {
+            cv.notify_one();
+        }
+        true
+    }
+
     /// Wait until there is either a fatal error on this connection,
     /// _or_ there is a new message for the request with the provided `id`.
     /// Return that message, or a copy of the fatal error.
@@ -17,7 +17,7 @@ use util::{
 use crate::{
     conn::{AnyResponse, RequestHandle},
     util::Utf8CString,
-    ObjectId, RpcConnBuilder,
+    AnyRequestId, ObjectId, RpcConnBuilder,
 };
 
 /// A status code returned by an Arti RPC function.
@@ -189,6 +189,129 @@ pub unsafe extern "C" fn arti_rpc_conn_builder_prepend_entry(
     )
 }
 
+/// Limit the number of connect points that `builder` will try when connecting.
+///
+/// If `max_attempts` is 0, there is no limit.
+///
+/// If this many connect points are tried without success,
+/// [`arti_rpc_conn_builder_connect`] will give up and return
+/// `ARTI_RPC_STATUS_ALL_CONNECT_ATTEMPTS_FAILED`.
+///
+/// On success, return `ARTI_RPC_STATUS_SUCCESS`.
+/// Otherwise return some other status code, and set
+/// `*error_out` (if provided) to a newly allocated error object.
+///
+/// # Ownership
+///
+/// The caller is responsible for making sure that `*error_out`,
+/// if set, is eventually freed.
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn arti_rpc_conn_builder_set_max_connect_attempts(
+    builder: *const ArtiRpcConnBuilder,
+    max_attempts: u32,
+    error_out: *mut *mut ArtiRpcError,
+) -> ArtiRpcStatus {
+    ffi_body_with_err!(
+        {
+            let builder: Option<&ArtiRpcConnBuilder> [in_ptr_opt];
+            err error_out: Option<OutPtr<ArtiRpcError>>;
+        } in {
+            let builder = builder.ok_or(InvalidInput::NullPointer)?;
+            let mut b = builder.0.lock().expect("Poisoned lock");
+            let mut budget = b.connect_budget();
+            budget.max_attempts = match max_attempts {
+                0 => None,
+                n => Some(usize::try_from(n).unwrap_or(usize::MAX)),
+            };
+            b.set_connect_budget(budget);
+        }
+    )
+}
+
+/// Limit the total time, in milliseconds,
+/// that `builder` will spend trying connect points when connecting.
+///
+/// If `deadline_msec` is 0, there is no limit.
+///
+/// This limit is checked before each connect point is tried;
+/// it does not interrupt a connection attempt that is already in progress.
+/// Once it has passed, [`arti_rpc_conn_builder_connect`] will give up
+/// and return `ARTI_RPC_STATUS_ALL_CONNECT_ATTEMPTS_FAILED`.
+///
+/// On success, return `ARTI_RPC_STATUS_SUCCESS`.
+/// Otherwise return some other status code, and set
+/// `*error_out` (if provided) to a newly allocated error object.
+///
+/// # Ownership
+///
+/// The caller is responsible for making sure that `*error_out`,
+/// if set, is eventually freed.
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn arti_rpc_conn_builder_set_connect_deadline_msec(
+    builder: *const ArtiRpcConnBuilder,
+    deadline_msec: u64,
+    error_out: *mut *mut ArtiRpcError,
+) -> ArtiRpcStatus {
+    ffi_body_with_err!(
+        {
+            let builder: Option<&ArtiRpcConnBuilder> [in_ptr_opt];
+            err error_out: Option<OutPtr<ArtiRpcError>>;
+        } in {
+            let builder = builder.ok_or(InvalidInput::NullPointer)?;
+            let mut b = builder.0.lock().expect("Poisoned lock");
+            let mut budget = b.connect_budget();
+            budget.total_deadline = match deadline_msec {
+                0 => None,
+                n => Some(std::time::Duration::from_millis(n)),
+            };
+            b.set_connect_budget(budget);
+        }
+    )
+}
+
+/// Limit the total time, in milliseconds,
+/// that [`arti_rpc_conn_builder_connect`] may take when using `builder`,
+/// including opening a connection and authenticating on it.
+///
+/// If `timeout_msec` is 0, there is no limit.
+///
+/// Unlike the limit set by [`arti_rpc_conn_builder_set_connect_deadline_msec`],
+/// this one interrupts a connection attempt that is already in progress.
+/// Once it has passed, [`arti_rpc_conn_builder_connect`] will give up
+/// and return `ARTI_RPC_STATUS_TIMEOUT`.
+///
+/// On success, return `ARTI_RPC_STATUS_SUCCESS`.
+/// Otherwise return some other status code, and set
+/// `*error_out` (if provided) to a newly allocated error object.
+///
+/// # Ownership
+///
+/// The caller is responsible for making sure that `*error_out`,
+/// if set, is eventually freed.
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn arti_rpc_conn_builder_set_connect_timeout_msec(
+    builder: *const ArtiRpcConnBuilder,
+    timeout_msec: u64,
+    error_out: *mut *mut ArtiRpcError,
+) -> ArtiRpcStatus {
+    ffi_body_with_err!(
+        {
+            let builder: Option<&ArtiRpcConnBuilder> [in_ptr_opt];
+            err error_out: Option<OutPtr<ArtiRpcError>>;
+        } in {
+            let builder = builder.ok_or(InvalidInput::NullPointer)?;
+            let mut b = builder.0.lock().expect("Poisoned lock");
+            b.set_connect_timeout(match timeout_msec {
+                0 => None,
+                n => Some(std::time::Duration::from_millis(n)),
+            });
+        }
+    )
+}
+
 /// Use `builder` to open a new RPC connection to Arti.
 ///
 /// On success, return `ARTI_RPC_STATUS_SUCCESS`,
@@ -252,6 +375,39 @@ pub unsafe extern "C" fn arti_rpc_conn_get_session_id(
     }
 }
 
+/// Given a pointer to an RPC connection, return a description of the connect point
+/// that was used to open it.
+///
+/// This is intended for diagnostics:
+/// it tells you which entry on the connect point search path succeeded.
+/// Its format is not specified, and may change in the future.
+///
+/// The caller should be prepared for a possible NULL return, in case
+/// the connect point is not known.
+///
+/// # Ownership
+///
+/// The resulting string is a reference to part of the `ArtiRpcConn`.
+/// It lives for no longer than the underlying `ArtiRpcConn` object.
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn arti_rpc_conn_connect_point(
+    rpc_conn: *const ArtiRpcConn,
+) -> *const c_char {
+    ffi_body_raw! {
+        {
+            let rpc_conn: Option<&ArtiRpcConn> [in_ptr_opt];
+        } in {
+            rpc_conn.and_then(crate::RpcConn::connect_point_cstr)
+                .map(|s| s.as_ptr())
+                .unwrap_or(std::ptr::null())
+            // Safety: returned pointer is null, or semantically borrowed from `rpc_conn`.
+            // It is only null if `rpc_conn` was null or its connect point was unknown.
+            // The caller is not allowed to modify it.
+        }
+    }
+}
+
 /// Run an RPC request over `rpc_conn` and wait for a successful response.
 ///
 /// The message `msg` should be a valid RPC request in JSON format.
@@ -366,6 +522,48 @@ pub unsafe extern "C" fn arti_rpc_conn_cancel_handle(
     )
 }
 
+/// Attempt to cancel the request on `rpc_conn` with the provided `request_id`.
+///
+/// The `request_id` must be the JSON encoding of the request's ID:
+/// for example, `7` or `"my-request"` (including the quotation marks).
+///
+/// Like `arti_rpc_conn_cancel_handle`, this asks Arti to cancel the request.
+/// Additionally, on success, it marks the request as cancelled locally:
+/// any responses that have been received for it are discarded,
+/// and any later (or ongoing) call to `arti_rpc_handle_wait` on its handle
+/// returns `ARTI_RPC_STATUS_REQUEST_COMPLETED`.
+///
+/// If we are not waiting for any responses to this request
+/// (for example, because it has already been cancelled,
+/// or because its final response has already been received),
+/// return `ARTI_RPC_STATUS_REQUEST_COMPLETED`.
+///
+/// On success, return `ARTI_RPC_STATUS_SUCCESS`.
+///
+/// Otherwise return some other status code,
+/// and set `*error_out` (if provided) to a newly allocated error object.
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn arti_rpc_request_cancel(
+    rpc_conn: *const ArtiRpcConn,
+    request_id: *const c_char,
+    error_out: *mut *mut ArtiRpcError,
+) -> ArtiRpcStatus {
+    ffi_body_with_err!(
+        {
+            let rpc_conn: Option<&ArtiRpcConn> [in_ptr_opt];
+            let request_id: Option<&str> [in_str_opt];
+            err error_out: Option<OutPtr<ArtiRpcError>>;
+        } in {
+            let rpc_conn = rpc_conn.ok_or(InvalidInput::NullPointer)?;
+            let request_id = request_id.ok_or(InvalidInput::NullPointer)?;
+            let request_id: AnyRequestId =
+                serde_json::from_str(request_id).map_err(|_| InvalidInput::BadRequestId)?;
+            rpc_conn.cancel_request(&request_id)?;
+        }
+    )
+}
+
 /// A constant indicating that a message is a final result.
 ///
 /// After a result has been received, a handle will not return any more responses,
@@ -116,7 +116,8 @@ pub(crate) enum FfiStatus {
     /// The peer has closed our connection; possibly because it is shutting down.
     ///
     /// (This error was generated by the library,
-    /// based on the connection being closed or reset from the peer.)
+    /// based on the connection being closed in an orderly way,
+    /// or failing for some reason other than a reset.)
     [c"Peer has shut down"]
     Shutdown = 6,
 
@@ -158,7 +159,8 @@ pub(crate) enum FfiStatus {
     NotAuthenticated = 12,
 
     /// All of our attempts to connect to Arti failed,
-    /// or we reached an explicit instruction to "abort" our connection attempts.
+    /// or we reached an explicit instruction to "abort" our connection attempts,
+    /// or we used up the attempts or time allowed for connecting.
     [c"All attempts to connect to Arti RPC failed"]
     AllConnectAttemptsFailed = 13,
 
@@ -173,6 +175,20 @@ pub(crate) enum FfiStatus {
     /// in our connect point search path.
     [c"Invalid connect point search path"]
     BadConnectPointPath = 15,
+
+    /// Our connection to the peer was reset or broken abruptly,
+    /// rather than being closed in an orderly way.
+    ///
+    /// (This error was generated by the library,
+    /// based on an IO error from the connection.)
+    [c"Connection to peer was reset"]
+    ConnectionReset = 16,
+
+    /// An operation did not finish within the time we allowed for it.
+    ///
+    /// (This error was generated by the library.)
+    [c"Operation timed out"]
+    Timeout = 17,
 }
 }
 
@@ -289,6 +305,10 @@ pub(super) enum InvalidInput {
     /// Tried to use an invalid constant
     #[error("Provided constant was not recognized")]
     InvalidConstValue,
+
+    /// Tried to use a request ID that was not a JSON string or integer.
+    #[error("Provided request ID was not a JSON string or integer")]
+    BadRequestId,
 }
 
 impl From<void::Void> for InvalidInput {
@@ -320,7 +340,8 @@ impl IntoFfiError for crate::ConnectError {
                 F::BadConnectPointPath
             }
             E::CannotParse(_) | E::CannotResolveConnectPoint(_) => F::ConnectPointNotUsable,
-            E::AllAttemptsDeclined => F::AllConnectAttemptsFailed,
+            E::AllAttemptsDeclined | E::BudgetExhausted => F::AllConnectAttemptsFailed,
+            E::Timeout => F::Timeout,
             E::AuthenticationNotSupported => F::NotSupported,
             E::ServerAddressMismatch { .. } => F::ConnectPointNotUsable,
             E::CookieMismatch => F::ConnectPointNotUsable,
@@ -408,6 +429,7 @@ impl IntoFfiError for crate::ProtoError {
         use crate::ProtoError as E;
         use FfiStatus as F;
         match self {
+            E::Shutdown(e) if e.is_reset() => F::ConnectionReset,
             E::Shutdown(_) => F::Shutdown,
             E::InvalidRequest(_) => F::InvalidInput,
             E::RequestIdInUse => F::InvalidInput,
@@ -620,3 +642,50 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    // @@ begin test lint list maintained by maint/add_warning @@
+    #![allow(clippy::bool_assert_comparison)]
+    #![allow(clippy::clone_on_copy)]
+    #![allow(clippy::dbg_macro)]
+    #![allow(clippy::mixed_attributes_style)]
+    #![allow(clippy::print_stderr)]
+    #![allow(clippy::print_stdout)]
+    #![allow(clippy::single_char_pattern)]
+    #![allow(clippy::unwrap_used)]
+    #![allow(clippy::unchecked_duration_subtraction)]
+    #![allow(clippy::useless_vec)]
+    #![allow(clippy::needless_pass_by_value)]
+    //! <!-- @@ end test lint list maintained by maint/add_warning @@ -->
+
+    use super::*;
+    use crate::conn::ShutdownError;
+    use std::{io, sync::Arc};
+
+    fn status_of(e: ShutdownError) -> ArtiRpcStatus {
+        crate::ProtoError::Shutdown(e).status() as _
+    }
+
+    #[test]
+    fn shutdown_vs_reset() {
+        let io_err = |kind| Arc::new(io::Error::from(kind));
+
+        assert_eq!(
+            status_of(ShutdownError::ConnectionClosed),
+            FfiStatus::Shutdown as ArtiRpcStatus
+        );
+        assert_eq!(
+            status_of(ShutdownError::Read(io_err(io::ErrorKind::UnexpectedEof))),
+            FfiStatus::Shutdown as ArtiRpcStatus
+        );
+        assert_eq!(
+            status_of(ShutdownError::Read(io_err(io::ErrorKind::ConnectionReset))),
+            FfiStatus::ConnectionReset as ArtiRpcStatus
+        );
+        assert_eq!(
+            status_of(ShutdownError::Write(io_err(io::ErrorKind::BrokenPipe))),
+            FfiStatus::ConnectionReset as ArtiRpcStatus
+        );
+    }
+}
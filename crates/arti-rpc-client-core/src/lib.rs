@@ -56,7 +56,7 @@ mod util;
 mod testing;
 
 pub use conn::{
-    BuilderError, ConnPtDescription, ConnectError, ConnectFailure, ProtoError, RpcConn,
-    RpcConnBuilder, StreamError,
+    BuilderError, ConnPtDescription, ConnectBudget, ConnectError, ConnectFailure, ProtoError,
+    RpcConn, RpcConnBuilder, StreamError,
 };
 pub use msgs::{request::InvalidRequestError, response::RpcError, AnyRequestId, ObjectId};
@@ -159,6 +159,9 @@ enum ResponseBody {
     /// Arti reports an incremental update for the request.
     #[serde(rename = "update")]
     Update(JsonAnyObj),
+    /// Arti reports that the request will receive no more updates.
+    #[serde(rename = "updates_done")]
+    UpdatesDone(JsonAnyObj),
 }
 impl<'a> From<&'a ResponseBody> for ResponseKind {
     fn from(value: &'a ResponseBody) -> Self {
@@ -169,7 +172,8 @@ impl<'a> From<&'a ResponseBody> for ResponseKind {
         match value {
             RMB::Error(_) => RK::Error,
             RMB::Success(_) => RK::Success,
-            RMB::Update(_) => RK::Update,
+            // This is not a final response, so we deliver it like an update.
+            RMB::Update(_) | RMB::UpdatesDone(_) => RK::Update,
         }
     }
 }
@@ -321,6 +325,13 @@ mod test {
                     kind: RK::Update,
                 },
             ),
+            (
+                r#"{"id":"hi", "updates_done": {}}"#,
+                RM {
+                    id: "hi".to_string().into(),
+                    kind: RK::Update,
+                },
+            ),
             (
                 r#"{"id": 6, "error": {"message":"iffy wobbler", "code":999, "kinds": ["BadVibes"]}}"#,
                 RM {
@@ -362,6 +373,7 @@ mod test {
         check_err!(r#"{"result": {}}"#, E::ProtocolViolation(_));
         // Missing ID on an update.
         check_err!(r#"{"update": {}}"#, E::ProtocolViolation(_));
+        check_err!(r#"{"updates_done": {}}"#, E::ProtocolViolation(_));
         // No recognized type.
         check_err!(r#"{"id": 7, "flupdate": {}}"#, E::JsonProtocolViolation(_));
         // Couldn't parse.
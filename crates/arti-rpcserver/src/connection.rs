@@ -18,7 +18,7 @@ use futures::{
 };
 use rpc::dispatch::BoxedUpdateSink;
 use serde_json::error::Category as JsonErrorCategory;
-use tor_async_utils::{mpsc_channel_no_memquota, SinkExt as _};
+use tor_async_utils::mpsc_channel_no_memquota;
 
 use crate::{
     cancel::{self, Cancel, CancelHandle},
@@ -32,6 +32,69 @@ use crate::{
 use tor_rpcbase::templates::*;
 use tor_rpcbase::{self as rpc, RpcError};
 
+/// A sink for the updates of a single request.
+///
+/// Sends each update to the client as an `update` response.
+/// When it is closed (see [`rpc::UpdateSinkExt::finish_updates`]),
+/// it first sends an `updates_done` response,
+/// to tell the client that the next response to the request will be its final one.
+struct UpdateSender {
+    /// The channel on which we send responses to the client.
+    tx: mpsc::Sender<BoxedResponse>,
+    /// The ID of the request whose updates we're sending.
+    id: RequestId,
+    /// True if we have already sent the `updates_done` response.
+    done: bool,
+}
+
+impl Sink<Box<dyn erased_serde::Serialize + Send>> for UpdateSender {
+    type Error = rpc::SendUpdateError;
+
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        self.get_mut().tx.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn start_send(
+        self: Pin<&mut Self>,
+        update: Box<dyn erased_serde::Serialize + Send>,
+    ) -> Result<(), Self::Error> {
+        let this = self.get_mut();
+        this.tx.start_send(BoxedResponse {
+            id: Some(this.id.clone()),
+            body: ResponseBody::Update(update),
+        })?;
+        Ok(())
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.get_mut().tx)
+            .poll_flush(cx)
+            .map_err(Into::into)
+    }
+
+    fn poll_close(
+        self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        if !this.done {
+            futures::ready!(this.tx.poll_ready(cx))?;
+            this.tx.start_send(BoxedResponse {
+                id: Some(this.id.clone()),
+                body: ResponseBody::UpdatesDone(rpc::NIL),
+            })?;
+            this.done = true;
+        }
+        Pin::new(&mut this.tx).poll_close(cx).map_err(Into::into)
+    }
+}
+
 /// An open connection from an RPC client.
 ///
 /// Tracks information that persists from one request to another.
@@ -81,6 +144,17 @@ pub struct Connection {
 
     /// The authentication type that's required in order to get a session.
     require_auth: tor_rpc_connect::auth::RpcAuth,
+
+    /// The largest number of requests that we will run at once on this connection.
+    max_requests: usize,
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        if let Some(mgr) = self.mgr.upgrade() {
+            mgr.note_connection_closed();
+        }
+    }
 }
 
 /// The inner, lock-protected part of an RPC connection.
@@ -92,6 +166,12 @@ struct Inner {
     // cancels the most recent request sent with that ID.
     inflight: HashMap<RequestId, Option<CancelHandle>>,
 
+    /// The number of requests that we have launched on this connection,
+    /// and that have not yet finished.
+    ///
+    /// Unlike `inflight`, this counts requests that share an ID separately.
+    n_running: usize,
+
     /// An object map used to look up most objects by ID, and keep track of
     /// which objects are owned by this connection.
     objects: ObjMap,
@@ -107,6 +187,11 @@ struct Inner {
 /// How many updates can be pending, per connection, before they start to block?
 const UPDATE_CHAN_SIZE: usize = 128;
 
+/// By default, how many requests can be running at once on a single connection?
+///
+/// Requests beyond this limit are refused with [`TooManyRequests`].
+pub(crate) const DEFAULT_MAX_REQUESTS_PER_CONNECTION: usize = 128;
+
 /// A type-erased [`FusedStream`] yielding [`Request`]s.
 //
 // (We name this type and [`BoxedResponseSink`] below so as to keep the signature for run_loop
@@ -153,10 +238,12 @@ impl Connection {
         global_id_mac_key: MacKey,
         mgr: Weak<RpcMgr>,
         require_auth: tor_rpc_connect::auth::RpcAuth,
+        max_requests: usize,
     ) -> Arc<Self> {
         Arc::new_cyclic(|this_connection| Self {
             inner: Mutex::new(Inner {
                 inflight: HashMap::new(),
+                n_running: 0,
                 objects: ObjMap::new(),
                 this_connection: Some(Weak::clone(this_connection)),
             }),
@@ -165,6 +252,7 @@ impl Connection {
             global_id_mac_key,
             mgr,
             require_auth,
+            max_requests,
         })
     }
 
@@ -247,6 +335,21 @@ impl Connection {
         inner.inflight.insert(id, handle);
     }
 
+    /// Try to reserve room for a new request to run on this connection.
+    ///
+    /// Return None if we are already running as many requests as we allow.
+    /// Otherwise, the room stays reserved until the returned [`RequestSlot`] is dropped.
+    fn try_begin_request(self: &Arc<Self>) -> Option<RequestSlot> {
+        let mut inner = self.inner.lock().expect("lock poisoned");
+        if inner.n_running >= self.max_requests {
+            return None;
+        }
+        inner.n_running += 1;
+        Some(RequestSlot {
+            conn: Arc::clone(self),
+        })
+    }
+
     /// Try to cancel the request `id`.
     ///
     /// Return an error when `id` cannot be found, or cannot be cancelled.
@@ -349,13 +452,12 @@ impl Connection {
                     r = rx_response.next() => {
                         // The future for some request has sent a response (success,
                         // failure, or update), so we can inform the client.
-                        let update = r.expect("Somehow, tx_update got closed.");
-                        debug_assert!(! update.body.is_final());
+                        let response = r.expect("Somehow, tx_update got closed.");
                         // Calling `await` here (and below) is deliberate: we _want_
                         // to stop reading the client's requests if the client is
                         // not reading their responses (or not) reading them fast
                         // enough.
-                        response_sink.send(update).await.map_err(ConnectionError::writing)?;
+                        response_sink.send(response).await.map_err(ConnectionError::writing)?;
                         Continue
                     }
 
@@ -388,12 +490,29 @@ impl Connection {
                                 Continue
 
                             }
-                            Some(Ok(FlexibleRequest::Valid(req))) => {
-                                // We have a request. Time to launch it!
-                                let tx = tx_response.clone();
-                                let fut = self.run_method_and_deliver_response(tx, req);
-                                finished_requests.push(fut.boxed());
-                                Continue
+                            Some(Ok(FlexibleRequest::Valid(req))) => match self.try_begin_request() {
+                                None => {
+                                    // We have a request, but too many others are running.
+                                    // Refuse it; the client can try again later.
+                                    let response = BoxedResponse::from_error(
+                                        Some(req.id), TooManyRequests
+                                    );
+                                    response_sink
+                                        .send(response)
+                                        .await
+                                        .map_err(ConnectionError::writing)?;
+                                    Continue
+                                }
+                                Some(slot) => {
+                                    // We have a request. Time to launch it!
+                                    if let Ok(mgr) = self.mgr() {
+                                        mgr.note_request_dispatched();
+                                    }
+                                    let tx = tx_response.clone();
+                                    let fut = self.run_method_and_deliver_response(tx, req, slot);
+                                    finished_requests.push(fut.boxed());
+                                    Continue
+                                }
                             }
                         }
                     }
@@ -413,6 +532,7 @@ impl Connection {
         self: &Arc<Self>,
         mut tx_response: mpsc::Sender<BoxedResponse>,
         request: Request,
+        slot: RequestSlot,
     ) {
         let Request {
             id,
@@ -422,17 +542,11 @@ impl Connection {
         } = request;
 
         let update_sender: BoxedUpdateSink = if meta.updates {
-            let id_clone = id.clone();
-            let sink =
-                tx_response
-                    .clone()
-                    .with_fn(move |obj: Box<dyn erased_serde::Serialize + Send>| {
-                        Result::<BoxedResponse, _>::Ok(BoxedResponse {
-                            id: Some(id_clone.clone()),
-                            body: ResponseBody::Update(obj),
-                        })
-                    });
-            Box::pin(sink)
+            Box::pin(UpdateSender {
+                tx: tx_response.clone(),
+                id: id.clone(),
+                done: false,
+            })
         } else {
             let sink = futures::sink::drain().sink_err_into();
             Box::pin(sink)
@@ -468,6 +582,11 @@ impl Connection {
             }
             Err(_cancelled) => ResponseBody::Error(Box::new(rpc::RpcError::from(RequestCancelled))),
         };
+        debug_assert!(body.is_final());
+
+        // The request is done: release its slot before the client hears about it,
+        // so that the client can send another request as soon as it has the reply.
+        drop(slot);
 
         // Send the response.
         //
@@ -512,14 +631,30 @@ impl Connection {
         }
 
         let context: Arc<dyn rpc::Context> = self.clone() as Arc<_>;
+        let method = method.upcast_box();
+
+        if rpc::yields_stream(method.as_ref()) {
+            // We can't relay the stream ourselves: hand it to our stream handler, if we have one.
+            let handler = self.mgr()?.stream_handler().ok_or(StreamsNotSupported)?;
+            let stream = rpc::invoke_stream_method(context, obj, method, tx_updates)?.await?;
+            handler(self, stream);
+            return Ok(Box::new(rpc::NIL));
+        }
 
-        let invoke_future =
-            rpc::invoke_rpc_method(context, &obj_id, obj, method.upcast_box(), tx_updates)?;
+        let invoke_future = rpc::invoke_rpc_method(context, &obj_id, obj, method, tx_updates)?;
 
         // Note that we drop the read lock before we await this future!
         invoke_future.await
     }
 
+    /// Record a failed authentication attempt on this connection with our RpcMgr,
+    /// if it still exists.
+    pub(crate) fn note_auth_failure(&self) {
+        if let Some(mgr) = self.mgr.upgrade() {
+            mgr.note_auth_failure();
+        }
+    }
+
     /// Try to get a strong reference to the RpcMgr for this connection, and
     /// return an error if we can't.
     pub(crate) fn mgr(&self) -> Result<Arc<RpcMgr>, MgrDisappearedError> {
@@ -529,6 +664,18 @@ impl Connection {
     }
 }
 
+/// An error returned when an RPC request invokes a method that yields a data stream,
+/// but we have nothing that can receive the stream.
+#[derive(Clone, Debug, thiserror::Error)]
+#[error("This RPC connection can't open data streams")]
+struct StreamsNotSupported;
+
+impl From<StreamsNotSupported> for RpcError {
+    fn from(err: StreamsNotSupported) -> Self {
+        RpcError::new(err.to_string(), rpc::RpcErrorKind::MethodNotImpl)
+    }
+}
+
 /// An error returned when an RPC request lists some feature as required,
 /// but we don't have every such feature.
 #[derive(Clone, Debug, thiserror::Error)]
@@ -702,6 +849,34 @@ impl From<RequestCancelled> for RpcError {
     }
 }
 
+/// Room reserved for a single request running on a [`Connection`].
+///
+/// Returned by [`Connection::try_begin_request`];
+/// the room is released when this is dropped.
+struct RequestSlot {
+    /// The connection on which the request is running.
+    conn: Arc<Connection>,
+}
+
+impl Drop for RequestSlot {
+    fn drop(&mut self) {
+        let mut inner = self.conn.inner.lock().expect("lock poisoned");
+        inner.n_running = inner.n_running.saturating_sub(1);
+    }
+}
+
+/// An error given when we refuse an RPC request because
+/// its connection already has too many requests running.
+#[derive(thiserror::Error, Clone, Debug, serde::Serialize)]
+#[error("Too many requests in progress on this connection")]
+pub(crate) struct TooManyRequests;
+
+impl From<TooManyRequests> for RpcError {
+    fn from(err: TooManyRequests) -> Self {
+        RpcError::new(err.to_string(), rpc::RpcErrorKind::TooManyRequests)
+    }
+}
+
 /// An error given when we attempt to cancel an RPC request, but cannot.
 ///
 #[derive(thiserror::Error, Clone, Debug, serde::Serialize)]
@@ -752,3 +927,318 @@ impl From<CancelError> for RpcError {
         RpcError::new(err.to_string(), code)
     }
 }
+
+#[cfg(test)]
+mod test {
+    // @@ begin test lint list maintained by maint/add_warning @@
+    #![allow(clippy::bool_assert_comparison)]
+    #![allow(clippy::clone_on_copy)]
+    #![allow(clippy::dbg_macro)]
+    #![allow(clippy::mixed_attributes_style)]
+    #![allow(clippy::print_stderr)]
+    #![allow(clippy::print_stdout)]
+    #![allow(clippy::single_char_pattern)]
+    #![allow(clippy::unwrap_used)]
+    #![allow(clippy::unchecked_duration_subtraction)]
+    #![allow(clippy::useless_vec)]
+    #![allow(clippy::needless_pass_by_value)]
+    //! <!-- @@ end test lint list maintained by maint/add_warning @@ -->
+    use super::*;
+    use crate::RpcMetrics;
+    use futures_await_test::async_test;
+    use tor_rpc_connect::auth::RpcAuth;
+
+    /// A trivial session object.
+    #[derive(Deftly)]
+    #[derive_deftly(rpc::Object)]
+    struct TestSession;
+
+    /// A method that opens a data stream.
+    #[derive(Debug, serde::Deserialize, Deftly)]
+    #[derive_deftly(DynMethod)]
+    #[deftly(rpc(method_name = "x-test:open_stream"))]
+    struct OpenStream {}
+
+    impl rpc::RpcMethod for OpenStream {
+        type Output = rpc::StreamHandle;
+        type Update = rpc::NoUpdates;
+    }
+
+    /// Invoke the `x-test:open_stream` method on a connection.
+    async fn open_stream(
+        _conn: Arc<Connection>,
+        _method: Box<OpenStream>,
+        _ctx: Arc<dyn rpc::Context>,
+    ) -> Result<rpc::StreamHandle, rpc::RpcError> {
+        Ok(rpc::StreamHandle::new(futures::io::Cursor::new(
+            b"hello".to_vec(),
+        )))
+    }
+    rpc::static_rpc_invoke_fn! {
+        open_stream;
+    }
+
+    /// A method that sends a couple of updates, and finishes them before returning.
+    #[derive(Debug, serde::Deserialize, Deftly)]
+    #[derive_deftly(DynMethod)]
+    #[deftly(rpc(method_name = "x-test:count"))]
+    struct Count {}
+
+    impl rpc::RpcMethod for Count {
+        type Output = rpc::Nil;
+        type Update = u32;
+    }
+
+    /// Invoke the `x-test:count` method on a connection.
+    async fn count(
+        _conn: Arc<Connection>,
+        _method: Box<Count>,
+        _ctx: Arc<dyn rpc::Context>,
+        mut updates: rpc::UpdateSink<u32>,
+    ) -> Result<rpc::Nil, rpc::RpcError> {
+        use rpc::UpdateSinkExt as _;
+
+        updates.send(1).await?;
+        updates.send(2).await?;
+        updates.finish_updates().await?;
+        Ok(rpc::NIL)
+    }
+    rpc::static_rpc_invoke_fn! {
+        count;
+    }
+
+    #[async_test]
+    async fn request_limit() {
+        let mgr = RpcMgr::new(|_| Arc::new(TestSession) as Arc<dyn rpc::Object>).unwrap();
+        mgr.set_max_requests_per_connection(1);
+        let conn = mgr.new_connection(RpcAuth::Inherent);
+
+        // Stand in for a request that is still running.
+        let running = conn.try_begin_request().unwrap();
+
+        let (tx_requests, rx_requests) = mpsc::unbounded::<Result<_, JsonCodecError>>();
+        let (tx_responses, mut rx_responses) = mpsc::unbounded::<BoxedResponse>();
+        let run_loop = Arc::clone(&conn).run_loop(
+            Box::pin(rx_requests),
+            Box::pin(tx_responses.sink_map_err(|e| JsonCodecError::Io(IoError::other(e)))),
+        );
+        let client = async move {
+            let send = |id: u32| {
+                let request = format!(
+                    r#"{{"id":{id},"obj":"connection","method":"auth:query","params":{{}}}}"#
+                );
+                let request: FlexibleRequest = serde_json::from_str(&request).unwrap();
+                tx_requests.unbounded_send(Ok(request)).unwrap();
+            };
+
+            // We're at the limit, so the request is refused...
+            send(1);
+            let resp = rx_responses.next().await.unwrap();
+            assert_eq!(
+                serde_json::to_string(&resp).unwrap(),
+                r#"{"id":1,"error":{"message":"Too many requests in progress on this connection","code":6,"kinds":["rpc:TooManyRequests"]}}"#
+            );
+
+            // ...until the running request finishes.
+            drop(running);
+            send(2);
+            let resp = rx_responses.next().await.unwrap();
+            assert_eq!(resp.id, Some(RequestId::Int(2)));
+            assert!(matches!(resp.body, ResponseBody::Success(_)));
+
+            // A finished request gives up its slot before its reply is sent,
+            // so the client can send the next one right away.
+            send(3);
+            let resp = rx_responses.next().await.unwrap();
+            assert_eq!(resp.id, Some(RequestId::Int(3)));
+            assert!(matches!(resp.body, ResponseBody::Success(_)));
+            // Dropping `tx_requests` ends the run loop.
+        };
+        let (outcome, ()) = futures::join!(run_loop, client);
+        outcome.unwrap();
+        assert_eq!(conn.inner.lock().unwrap().n_running, 0);
+    }
+
+    #[async_test]
+    async fn stream_method() {
+        let mgr = RpcMgr::new(|_| Arc::new(TestSession) as Arc<dyn rpc::Object>).unwrap();
+        let conn = mgr.new_connection(RpcAuth::Inherent);
+        let streams = Arc::new(Mutex::new(vec![]));
+        let streams_in_handler = Arc::clone(&streams);
+        let mgr_in_client = Arc::clone(&mgr);
+
+        let (tx_requests, rx_requests) = mpsc::unbounded::<Result<_, JsonCodecError>>();
+        let (tx_responses, mut rx_responses) = mpsc::unbounded::<BoxedResponse>();
+        let run_loop = Arc::clone(&conn).run_loop(
+            Box::pin(rx_requests),
+            Box::pin(tx_responses.sink_map_err(|e| JsonCodecError::Io(IoError::other(e)))),
+        );
+        let client = async move {
+            let send = |id: u32| {
+                let request = format!(
+                    r#"{{"id":{id},"obj":"connection","method":"x-test:open_stream","params":{{}}}}"#
+                );
+                let request: FlexibleRequest = serde_json::from_str(&request).unwrap();
+                tx_requests.unbounded_send(Ok(request)).unwrap();
+            };
+
+            // Nothing can receive the stream, so the request is refused.
+            send(1);
+            let resp = rx_responses.next().await.unwrap();
+            assert_eq!(
+                serde_json::to_string(&resp).unwrap(),
+                r#"{"id":1,"error":{"message":"This RPC connection can't open data streams","code":3,"kinds":["rpc:MethodNotImpl"]}}"#
+            );
+
+            // Once there is a handler, it receives the stream,
+            // and the client gets an empty response.
+            mgr_in_client.set_stream_handler(Box::new(move |_conn, stream| {
+                streams_in_handler.lock().unwrap().push(stream);
+            }));
+            send(2);
+            let resp = rx_responses.next().await.unwrap();
+            assert_eq!(
+                serde_json::to_string(&resp).unwrap(),
+                r#"{"id":2,"result":{}}"#
+            );
+            // Dropping `tx_requests` ends the run loop.
+        };
+        let (outcome, ()) = futures::join!(run_loop, client);
+        outcome.unwrap();
+
+        let stream = streams.lock().unwrap().pop().unwrap();
+        let mut data = vec![];
+        futures::AsyncReadExt::read_to_end(&mut stream.into_stream(), &mut data)
+            .await
+            .unwrap();
+        assert_eq!(data, b"hello");
+    }
+
+    #[async_test]
+    async fn updates_done() {
+        let mgr = RpcMgr::new(|_| Arc::new(TestSession) as Arc<dyn rpc::Object>).unwrap();
+        let conn = mgr.new_connection(RpcAuth::Inherent);
+
+        let (tx_requests, rx_requests) = mpsc::unbounded::<Result<_, JsonCodecError>>();
+        let (tx_responses, mut rx_responses) = mpsc::unbounded::<BoxedResponse>();
+        let run_loop = Arc::clone(&conn).run_loop(
+            Box::pin(rx_requests),
+            Box::pin(tx_responses.sink_map_err(|e| JsonCodecError::Io(IoError::other(e)))),
+        );
+        let client = async move {
+            for request in [
+                r#"{"id":1,"obj":"connection","method":"x-test:count","meta":{"updates":true},"params":{}}"#,
+                r#"{"id":2,"obj":"connection","method":"x-test:count","params":{}}"#,
+            ] {
+                let request: FlexibleRequest = serde_json::from_str(request).unwrap();
+                tx_requests.unbounded_send(Ok(request)).unwrap();
+            }
+
+            let mut responses = vec![];
+            let mut n_final = 0;
+            while n_final < 2 {
+                let resp = rx_responses.next().await.unwrap();
+                if resp.body.is_final() {
+                    n_final += 1;
+                }
+                responses.push(serde_json::to_string(&resp).unwrap());
+            }
+            // Dropping `tx_requests` ends the run loop.
+            responses
+        };
+        let (outcome, responses) = futures::join!(run_loop, client);
+        outcome.unwrap();
+
+        let responses_to = |id: &str| {
+            responses
+                .iter()
+                .filter(|resp| resp.starts_with(&format!(r#"{{"id":{id},"#)))
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+        };
+        // The client learns when the updates are done, before the final response.
+        assert_eq!(
+            responses_to("1"),
+            vec![
+                r#"{"id":1,"update":1}"#,
+                r#"{"id":1,"update":2}"#,
+                r#"{"id":1,"updates_done":{}}"#,
+                r#"{"id":1,"result":{}}"#,
+            ]
+        );
+        // A request that doesn't accept updates gets neither the updates, nor their end.
+        assert_eq!(responses_to("2"), vec![r#"{"id":2,"result":{}}"#]);
+    }
+
+    #[test]
+    fn connection_hook() {
+        let mgr = RpcMgr::new(|_| Arc::new(TestSession) as Arc<dyn rpc::Object>).unwrap();
+        let seen = Arc::new(Mutex::new(vec![]));
+        let seen_in_hook = Arc::clone(&seen);
+        let mgr_in_hook = Arc::downgrade(&mgr);
+        mgr.set_connection_hook(Box::new(move |conn: &Arc<Connection>| {
+            // The hook may use the RpcMgr: it isn't called with the lock held.
+            let mgr = mgr_in_hook.upgrade().unwrap();
+            assert!(mgr.metrics_snapshot().connections_created > 0);
+            seen_in_hook.lock().unwrap().push(conn.connection_id);
+        }));
+
+        let conn1 = mgr.new_connection(RpcAuth::Inherent);
+        let conn2 = mgr.new_connection(RpcAuth::Inherent);
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![conn1.connection_id, conn2.connection_id]
+        );
+    }
+
+    #[async_test]
+    async fn metrics() {
+        let mgr = RpcMgr::new(|_| Arc::new(TestSession) as Arc<dyn rpc::Object>).unwrap();
+        assert_eq!(mgr.metrics_snapshot(), RpcMetrics::default());
+
+        let conn = mgr.new_connection(RpcAuth::Inherent);
+        drop(mgr.new_connection(RpcAuth::Inherent));
+        let snapshot = mgr.metrics_snapshot();
+        assert_eq!(snapshot.connections_created, 2);
+        assert_eq!(snapshot.connections_live, 1);
+
+        let requests = [
+            // This one fails, since it uses the wrong scheme.
+            r#"{"id":1,"obj":"connection","method":"auth:authenticate","params":{"scheme":"auth:cookie"}}"#,
+            r#"{"id":2,"obj":"connection","method":"auth:authenticate","params":{"scheme":"auth:inherent"}}"#,
+            r#"{"id":3,"obj":"connection","method":"auth:query","params":{}}"#,
+        ];
+        let (tx_requests, rx_requests) = mpsc::unbounded::<Result<_, JsonCodecError>>();
+        let (tx_responses, mut rx_responses) = mpsc::unbounded::<BoxedResponse>();
+        let run_loop = Arc::clone(&conn).run_loop(
+            Box::pin(rx_requests),
+            Box::pin(tx_responses.sink_map_err(|e| JsonCodecError::Io(IoError::other(e)))),
+        );
+        let client = async move {
+            for request in requests {
+                let request: FlexibleRequest = serde_json::from_str(request).unwrap();
+                tx_requests.unbounded_send(Ok(request)).unwrap();
+            }
+            for _ in 0..requests.len() {
+                let _response = rx_responses.next().await.unwrap();
+            }
+            // Dropping `tx_requests` ends the run loop.
+        };
+        let (outcome, ()) = futures::join!(run_loop, client);
+        outcome.unwrap();
+
+        let snapshot = mgr.metrics_snapshot();
+        assert_eq!(
+            snapshot,
+            RpcMetrics {
+                connections_created: 2,
+                connections_live: 1,
+                requests_dispatched: 3,
+                auth_failures: 1,
+            }
+        );
+
+        drop(conn);
+        assert_eq!(mgr.metrics_snapshot().connections_live, 0);
+    }
+}
@@ -8,22 +8,43 @@ use tor_rpcbase as rpc;
 
 mod cookie;
 mod inherent;
+mod query;
 
 /// Information about how an RPC session has been authenticated.
 ///
-/// Currently, this isn't actually used for anything, since there's only one way
-/// to authenticate a connection.  It exists so that later we can pass
-/// information to the session-creator function.
-#[derive(Clone, Debug)]
+/// This is passed to the session-creator function given to
+/// [`RpcMgr::new`](crate::RpcMgr::new).
+#[derive(Clone, Debug, Default)]
 #[non_exhaustive]
-pub struct RpcAuthentication {}
+pub struct RpcAuthentication {
+    /// A tag supplied by the client when it authenticated, if any.
+    ///
+    /// Arti does not interpret this tag: it exists so that an application
+    /// multiplexing several logical clients over RPC can associate each
+    /// session with its own bookkeeping.
+    ///
+    /// When present, it is at most [`MAX_CLIENT_TAG_LEN`] bytes long.
+    pub client_tag: Option<String>,
+}
+
+/// The largest permissible length, in bytes, for [`RpcAuthentication::client_tag`].
+pub const MAX_CLIENT_TAG_LEN: usize = 256;
+
+/// Check whether `client_tag` is acceptable as an [`RpcAuthentication::client_tag`].
+fn check_client_tag(client_tag: &Option<String>) -> Result<(), AuthenticationFailure> {
+    match client_tag {
+        Some(tag) if tag.len() > MAX_CLIENT_TAG_LEN => Err(AuthenticationFailure::ClientTagTooLong),
+        _ => Ok(()),
+    }
+}
 
 /// The authentication scheme as enumerated in the spec.
 ///
 /// Conceptually, an authentication scheme answers the question "How can the
 /// Arti process know you have permissions to use or administer it?"
-#[derive(Debug, Copy, Clone, serde::Serialize, serde::Deserialize)]
-enum AuthenticationScheme {
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, serde::Serialize, serde::Deserialize)]
+#[non_exhaustive]
+pub enum AuthenticationScheme {
     /// Inherent authority based on the ability to open the connection to this address.
     #[serde(rename = "auth:inherent")]
     Inherent,
@@ -33,6 +54,22 @@ enum AuthenticationScheme {
     Cookie,
 }
 
+/// The level of access that a session authenticated with a given scheme receives.
+///
+/// This is advertised to clients in response to `auth:query`,
+/// so that they can explain to a user what each scheme grants.
+/// It is purely informational: the session factory passed to
+/// [`RpcMgr::new`](crate::RpcMgr::new) decides what a session can actually do.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum AuthPrivilege {
+    /// The session can administer Arti, as well as use it.
+    Admin,
+    /// The session can use Arti as an application, but not administer it.
+    App,
+}
+
 /// An error during authentication.
 #[derive(Debug, Clone, thiserror::Error, serde::Serialize)]
 enum AuthenticationFailure {
@@ -48,6 +85,9 @@ enum AuthenticationFailure {
     /// RPC system is shutting down; can't authenticate
     #[error("Shutting down; can't authenticate")]
     ShuttingDown,
+    /// The client tag supplied with the authentication request was too long.
+    #[error("Client tag too long")]
+    ClientTagTooLong,
 }
 
 /// A successful response from an authenticate method.
@@ -64,7 +104,10 @@ impl From<AuthenticationFailure> for rpc::RpcError {
 
         let mut err = rpc::RpcError::new(value.to_string(), rpc::RpcErrorKind::RequestError);
         match value {
-            AF::IncorrectMethod | AF::CookieNonceReused | AF::IncorrectAuthentication => {}
+            AF::IncorrectMethod
+            | AF::CookieNonceReused
+            | AF::IncorrectAuthentication
+            | AF::ClientTagTooLong => {}
             AF::ShuttingDown => err.set_kind(EK::ArtiShuttingDown),
         }
         err
@@ -13,7 +13,7 @@ use tor_rpcbase::{self as rpc, templates::*};
 
 use crate::{Connection, RpcMgr};
 
-use super::{AuthenticateReply, AuthenticationFailure};
+use super::{check_client_tag, AuthenticateReply, AuthenticationFailure, RpcAuthentication};
 
 /// Begin authenticating on an RPC connection, using Cookie authentication.
 ///
@@ -38,6 +38,14 @@ struct CookieBegin {
     ///
     /// Used as input to the `server_mac` calculation
     client_nonce: CookieAuthNonce,
+    /// An optional tag to associate with the new session.
+    ///
+    /// As with `auth:authenticate`, Arti does not interpret this tag;
+    /// if authentication succeeds, it is passed to the session factory
+    /// as [`RpcAuthentication::client_tag`].
+    /// It may be at most [`MAX_CLIENT_TAG_LEN`](super::MAX_CLIENT_TAG_LEN) bytes long.
+    #[serde(default)]
+    client_tag: Option<String>,
 }
 impl rpc::RpcMethod for CookieBegin {
     type Output = CookieBeginReply;
@@ -87,6 +95,8 @@ struct CookieAuthInProgress {
     server_nonce: Mutex<Option<CookieAuthNonce>>,
     /// The address that we believe we're listening on.
     server_addr: String,
+    /// The tag that the client gave us in `auth:cookie_begin`, if any.
+    client_tag: Option<String>,
 }
 
 /// Finish cookie authentication, returning a new RPC Session.
@@ -138,8 +148,15 @@ async fn cookie_begin(
             })?,
             server_address.clone(),
         ),
-        _ => return Err(AuthenticationFailure::IncorrectMethod.into()),
+        _ => {
+            unauth.note_auth_failure();
+            return Err(AuthenticationFailure::IncorrectMethod.into());
+        }
     };
+    if let Err(failure) = check_client_tag(&method.client_tag) {
+        unauth.note_auth_failure();
+        return Err(failure.into());
+    }
     let mut rng = rand::rng();
 
     let server_nonce = CookieAuthNonce::new(&mut rng);
@@ -152,6 +169,7 @@ async fn cookie_begin(
         client_nonce: method.client_nonce,
         server_nonce: Mutex::new(Some(server_nonce.clone())),
         server_addr: server_addr.clone(),
+        client_tag: method.client_tag,
     });
     let cookie_auth = ctx.register_owned(auth_in_progress);
 
@@ -169,6 +187,14 @@ async fn cookie_continue(
     method: Box<CookieContinue>,
     ctx: Arc<dyn rpc::Context>,
 ) -> Result<AuthenticateReply, rpc::RpcError> {
+    // Helper: record a failed authentication attempt, and return `failure`.
+    let fail = |failure: AuthenticationFailure| -> Result<AuthenticateReply, rpc::RpcError> {
+        if let Some(mgr) = in_progress.mgr.upgrade() {
+            mgr.note_auth_failure();
+        }
+        Err(failure.into())
+    };
+
     // Make sure we haven't gotten another one of these.
     let Some(server_nonce) = in_progress
         .server_nonce
@@ -176,7 +202,7 @@ async fn cookie_continue(
         .expect("lock poisoned")
         .take()
     else {
-        return Err(AuthenticationFailure::CookieNonceReused.into());
+        return fail(AuthenticationFailure::CookieNonceReused);
     };
 
     let expected_client_mac = in_progress.cookie.client_mac(
@@ -186,14 +212,16 @@ async fn cookie_continue(
     );
 
     if expected_client_mac != method.client_mac {
-        return Err(AuthenticationFailure::IncorrectAuthentication.into());
+        return fail(AuthenticationFailure::IncorrectAuthentication);
     }
 
     let mgr = in_progress
         .mgr
         .upgrade()
         .ok_or(AuthenticationFailure::ShuttingDown)?;
-    let auth = &super::RpcAuthentication {};
+    let auth = &RpcAuthentication {
+        client_tag: in_progress.client_tag.clone(),
+    };
     let session = mgr.create_session(auth);
     let session = ctx.register_owned(session);
 
@@ -204,3 +232,92 @@ rpc::static_rpc_invoke_fn! {
     cookie_begin;
     cookie_continue;
 }
+
+#[cfg(test)]
+mod test {
+    // @@ begin test lint list maintained by maint/add_warning @@
+    #![allow(clippy::bool_assert_comparison)]
+    #![allow(clippy::clone_on_copy)]
+    #![allow(clippy::dbg_macro)]
+    #![allow(clippy::mixed_attributes_style)]
+    #![allow(clippy::print_stderr)]
+    #![allow(clippy::print_stdout)]
+    #![allow(clippy::single_char_pattern)]
+    #![allow(clippy::unwrap_used)]
+    #![allow(clippy::unchecked_duration_subtraction)]
+    #![allow(clippy::useless_vec)]
+    #![allow(clippy::needless_pass_by_value)]
+    //! <!-- @@ end test lint list maintained by maint/add_warning @@ -->
+    use super::*;
+    use futures_await_test::async_test;
+    use tor_rpc_connect::auth::{cookie::COOKIE_PREFIX, RpcCookieSource};
+
+    /// A trivial session object.
+    #[derive(Deftly)]
+    #[derive_deftly(rpc::Object)]
+    struct TestSession;
+
+    /// The address that the test server claims to listen on.
+    const SERVER_ADDR: &str = "unix:/tmp/arti-rpc-test";
+
+    /// Return a new random cookie.
+    fn test_cookie() -> Arc<Cookie> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cookie");
+        let mut contents = COOKIE_PREFIX.to_vec();
+        contents.extend(rand::random::<[u8; 32]>());
+        std::fs::write(&path, contents).unwrap();
+        let mistrust = fs_mistrust::Mistrust::new_dangerously_trust_everyone();
+        Arc::new(Cookie::load(&path, &mistrust).unwrap())
+    }
+
+    #[async_test]
+    async fn client_tag_reaches_factory() {
+        let tags = Arc::new(Mutex::new(Vec::new()));
+        let tags_ = Arc::clone(&tags);
+        let mgr = RpcMgr::new(move |auth: &RpcAuthentication| {
+            tags_.lock().unwrap().push(auth.client_tag.clone());
+            Arc::new(TestSession) as Arc<dyn rpc::Object>
+        })
+        .unwrap();
+        let cookie = test_cookie();
+        let new_connection = || {
+            mgr.new_connection(RpcAuth::Cookie {
+                secret: RpcCookieSource::Loaded(Arc::clone(&cookie)),
+                server_address: SERVER_ADDR.into(),
+            })
+        };
+
+        for client_tag in [None, Some("wombat-7".to_string())] {
+            let conn = new_connection();
+            let ctx: Arc<dyn rpc::Context> = conn.clone();
+            let client_nonce = CookieAuthNonce::new(&mut rand::rng());
+            let begin = Box::new(CookieBegin {
+                client_nonce: client_nonce.clone(),
+                client_tag: client_tag.clone(),
+            });
+            let reply = cookie_begin(conn, begin, Arc::clone(&ctx)).await.unwrap();
+
+            let in_progress = ctx
+                .lookup_object(&reply.cookie_auth)
+                .unwrap()
+                .downcast_arc::<CookieAuthInProgress>()
+                .ok()
+                .unwrap();
+            let client_mac = cookie.client_mac(&client_nonce, &reply.server_nonce, SERVER_ADDR);
+            let continue_ = Box::new(CookieContinue { client_mac });
+            cookie_continue(in_progress, continue_, ctx).await.unwrap();
+            assert_eq!(tags.lock().unwrap().last().unwrap(), &client_tag);
+        }
+
+        // An overlong tag is rejected before the client can authenticate.
+        let conn = new_connection();
+        let ctx: Arc<dyn rpc::Context> = conn.clone();
+        let begin = Box::new(CookieBegin {
+            client_nonce: CookieAuthNonce::new(&mut rand::rng()),
+            client_tag: Some("x".repeat(super::super::MAX_CLIENT_TAG_LEN + 1)),
+        });
+        assert!(cookie_begin(conn, begin, ctx).await.is_err());
+        assert_eq!(tags.lock().unwrap().len(), 2);
+    }
+}
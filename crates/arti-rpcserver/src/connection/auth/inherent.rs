@@ -2,7 +2,10 @@
 //! authorized.
 use std::sync::Arc;
 
-use super::{AuthenticateReply, AuthenticationFailure, AuthenticationScheme, RpcAuthentication};
+use super::{
+    check_client_tag, AuthenticateReply, AuthenticationFailure, AuthenticationScheme,
+    RpcAuthentication,
+};
 use crate::Connection;
 use derive_deftly::Deftly;
 use tor_rpc_connect::auth::RpcAuth;
@@ -32,6 +35,13 @@ struct Authenticate {
     ///
     /// The only supported one for now is "auth:inherent"
     scheme: AuthenticationScheme,
+    /// An optional tag to associate with the new session.
+    ///
+    /// Arti does not interpret this tag; it is passed to the session factory
+    /// as [`RpcAuthentication::client_tag`].
+    /// It may be at most [`MAX_CLIENT_TAG_LEN`](super::MAX_CLIENT_TAG_LEN) bytes long.
+    #[serde(default)]
+    client_tag: Option<String>,
 }
 
 impl rpc::RpcMethod for Authenticate {
@@ -45,15 +55,21 @@ async fn authenticate_connection(
     method: Box<Authenticate>,
     ctx: Arc<dyn rpc::Context>,
 ) -> Result<AuthenticateReply, rpc::RpcError> {
-    match (method.scheme, &unauth.require_auth) {
+    let outcome = match (method.scheme, &unauth.require_auth) {
         // For now, we only support AF_UNIX connections, and we assume that if
         // you have permission to open such a connection to us, you have
         // permission to use Arti. We will refine this later on!
-        (AuthenticationScheme::Inherent, RpcAuth::Inherent) => {}
-        (_, _) => return Err(AuthenticationFailure::IncorrectMethod.into()),
+        (AuthenticationScheme::Inherent, RpcAuth::Inherent) => check_client_tag(&method.client_tag),
+        (_, _) => Err(AuthenticationFailure::IncorrectMethod),
+    };
+    if let Err(failure) = outcome {
+        unauth.note_auth_failure();
+        return Err(failure.into());
     }
 
-    let auth = RpcAuthentication {};
+    let auth = RpcAuthentication {
+        client_tag: method.client_tag,
+    };
     let session = {
         let mgr = unauth.mgr()?;
         mgr.create_session(&auth)
@@ -64,3 +80,76 @@ async fn authenticate_connection(
 rpc::static_rpc_invoke_fn! {
     authenticate_connection;
 }
+
+#[cfg(test)]
+mod test {
+    // @@ begin test lint list maintained by maint/add_warning @@
+    #![allow(clippy::bool_assert_comparison)]
+    #![allow(clippy::clone_on_copy)]
+    #![allow(clippy::dbg_macro)]
+    #![allow(clippy::mixed_attributes_style)]
+    #![allow(clippy::print_stderr)]
+    #![allow(clippy::print_stdout)]
+    #![allow(clippy::single_char_pattern)]
+    #![allow(clippy::unwrap_used)]
+    #![allow(clippy::unchecked_duration_subtraction)]
+    #![allow(clippy::useless_vec)]
+    #![allow(clippy::needless_pass_by_value)]
+    //! <!-- @@ end test lint list maintained by maint/add_warning @@ -->
+    use super::*;
+    use crate::RpcMgr;
+    use futures_await_test::async_test;
+    use std::sync::Mutex;
+
+    /// A trivial session object.
+    #[derive(Deftly)]
+    #[derive_deftly(rpc::Object)]
+    struct TestSession;
+
+    /// Return an RpcMgr whose session factory records every client tag it sees.
+    fn mgr_recording_tags() -> (Arc<RpcMgr>, Arc<Mutex<Vec<Option<String>>>>) {
+        let tags = Arc::new(Mutex::new(Vec::new()));
+        let tags_ = Arc::clone(&tags);
+        let mgr = RpcMgr::new(move |auth: &RpcAuthentication| {
+            tags_.lock().unwrap().push(auth.client_tag.clone());
+            Arc::new(TestSession) as Arc<dyn rpc::Object>
+        })
+        .unwrap();
+        (mgr, tags)
+    }
+
+    #[async_test]
+    async fn client_tag_reaches_factory() {
+        let (mgr, tags) = mgr_recording_tags();
+
+        for client_tag in [None, Some("wombat-7".to_string())] {
+            let conn = mgr.new_connection(RpcAuth::Inherent);
+            let method = Box::new(Authenticate {
+                scheme: AuthenticationScheme::Inherent,
+                client_tag: client_tag.clone(),
+            });
+            let ctx: Arc<dyn rpc::Context> = conn.clone();
+            authenticate_connection(conn, method, ctx).await.unwrap();
+            assert_eq!(tags.lock().unwrap().last().unwrap(), &client_tag);
+        }
+
+        // An overlong tag is rejected before we create a session.
+        let conn = mgr.new_connection(RpcAuth::Inherent);
+        let method = Box::new(Authenticate {
+            scheme: AuthenticationScheme::Inherent,
+            client_tag: Some("x".repeat(super::super::MAX_CLIENT_TAG_LEN + 1)),
+        });
+        let ctx: Arc<dyn rpc::Context> = conn.clone();
+        assert!(authenticate_connection(conn, method, ctx).await.is_err());
+        assert_eq!(tags.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn parse_client_tag() {
+        let m: Authenticate = serde_json::from_str(r#"{"scheme":"auth:inherent"}"#).unwrap();
+        assert!(m.client_tag.is_none());
+        let m: Authenticate =
+            serde_json::from_str(r#"{"scheme":"auth:inherent","client_tag":"hello"}"#).unwrap();
+        assert_eq!(m.client_tag.as_deref(), Some("hello"));
+    }
+}
@@ -0,0 +1,121 @@
+//! Discovering which authentication schemes a connection supports.
+
+use std::sync::Arc;
+
+use derive_deftly::Deftly;
+use tor_rpc_connect::auth::RpcAuth;
+use tor_rpcbase::{self as rpc, templates::*};
+
+use super::{AuthPrivilege, AuthenticationScheme};
+use crate::Connection;
+
+/// Ask which authentication schemes can be used on an RPC connection.
+///
+/// Clients can invoke this method before authenticating,
+/// to learn which schemes the server will accept,
+/// and (if the server has declared it) what level of access each scheme grants.
+#[derive(Debug, serde::Deserialize, Deftly)]
+#[derive_deftly(DynMethod)]
+#[deftly(rpc(method_name = "auth:query"))]
+struct AuthQuery {}
+
+impl rpc::RpcMethod for AuthQuery {
+    type Output = SupportedAuth;
+    type Update = rpc::NoUpdates;
+}
+
+/// A response to an `auth:query` request.
+#[derive(Debug, serde::Serialize)]
+struct SupportedAuth {
+    /// The authentication schemes that this connection accepts.
+    schemes: Vec<SchemeInfo>,
+}
+
+/// Information about a single supported authentication scheme.
+#[derive(Debug, serde::Serialize)]
+struct SchemeInfo {
+    /// The name of the scheme.
+    scheme: AuthenticationScheme,
+    /// The level of access this scheme grants, if the server has said.
+    ///
+    /// Omitted if no privilege level was declared for this scheme.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    privilege: Option<AuthPrivilege>,
+}
+
+/// Invoke the `auth:query` method on a connection.
+async fn query_authentication(
+    unauth: Arc<Connection>,
+    _method: Box<AuthQuery>,
+    _ctx: Arc<dyn rpc::Context>,
+) -> Result<SupportedAuth, rpc::RpcError> {
+    let scheme = match &unauth.require_auth {
+        RpcAuth::Inherent => Some(AuthenticationScheme::Inherent),
+        RpcAuth::Cookie { .. } => Some(AuthenticationScheme::Cookie),
+        _ => None,
+    };
+    let mgr = unauth.mgr()?;
+    let schemes = scheme
+        .into_iter()
+        .map(|scheme| SchemeInfo {
+            scheme,
+            privilege: mgr.auth_privilege(scheme),
+        })
+        .collect();
+
+    Ok(SupportedAuth { schemes })
+}
+rpc::static_rpc_invoke_fn! {
+    query_authentication;
+}
+
+#[cfg(test)]
+mod test {
+    // @@ begin test lint list maintained by maint/add_warning @@
+    #![allow(clippy::bool_assert_comparison)]
+    #![allow(clippy::clone_on_copy)]
+    #![allow(clippy::dbg_macro)]
+    #![allow(clippy::mixed_attributes_style)]
+    #![allow(clippy::print_stderr)]
+    #![allow(clippy::print_stdout)]
+    #![allow(clippy::single_char_pattern)]
+    #![allow(clippy::unwrap_used)]
+    #![allow(clippy::unchecked_duration_subtraction)]
+    #![allow(clippy::useless_vec)]
+    #![allow(clippy::needless_pass_by_value)]
+    //! <!-- @@ end test lint list maintained by maint/add_warning @@ -->
+    use super::*;
+
+    #[test]
+    fn serialize_supported_auth() {
+        let reply = SupportedAuth {
+            schemes: vec![
+                SchemeInfo {
+                    scheme: AuthenticationScheme::Inherent,
+                    privilege: Some(AuthPrivilege::Admin),
+                },
+                SchemeInfo {
+                    scheme: AuthenticationScheme::Cookie,
+                    privilege: Some(AuthPrivilege::App),
+                },
+            ],
+        };
+        assert_eq!(
+            serde_json::to_string(&reply).unwrap(),
+            r#"{"schemes":[{"scheme":"auth:inherent","privilege":"admin"},{"scheme":"auth:cookie","privilege":"app"}]}"#
+        );
+
+        // Without a declared privilege, the response has the same shape as it would
+        // without privilege hints.
+        let reply = SupportedAuth {
+            schemes: vec![SchemeInfo {
+                scheme: AuthenticationScheme::Inherent,
+                privilege: None,
+            }],
+        };
+        assert_eq!(
+            serde_json::to_string(&reply).unwrap(),
+            r#"{"schemes":[{"scheme":"auth:inherent"}]}"#
+        );
+    }
+}
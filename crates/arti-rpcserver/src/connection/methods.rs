@@ -5,6 +5,7 @@ use std::sync::Arc;
 use tor_rpcbase::{self as rpc, templates::*};
 
 use super::Connection;
+use crate::mgr::RpcMgrHealth;
 
 /// Cancel a single request.
 ///
@@ -44,3 +45,92 @@ async fn connection_rpc_cancel(
 rpc::static_rpc_invoke_fn! {
     connection_rpc_cancel;
 }
+
+/// Report on the health of the RPC server.
+///
+/// The result includes how long the server has been running,
+/// how many RPC connections are open,
+/// and how many method implementations are registered.
+///
+/// Like other methods on the connection object,
+/// this can be invoked before authenticating.
+/// It reveals nothing about the client's own session or about any other connection.
+#[derive(Debug, serde::Deserialize, Deftly)]
+#[derive_deftly(DynMethod)]
+#[deftly(rpc(method_name = "rpc:health"))]
+struct RpcHealth {}
+
+impl rpc::RpcMethod for RpcHealth {
+    type Output = RpcMgrHealth;
+    type Update = rpc::NoUpdates;
+}
+
+/// Implement `RpcHealth` on a connection.
+async fn connection_rpc_health(
+    conn: Arc<Connection>,
+    _method: Box<RpcHealth>,
+    _ctx: Arc<dyn rpc::Context>,
+) -> Result<RpcMgrHealth, rpc::RpcError> {
+    Ok(conn.mgr()?.health())
+}
+
+rpc::static_rpc_invoke_fn! {
+    connection_rpc_health;
+}
+
+#[cfg(test)]
+mod test {
+    // @@ begin test lint list maintained by maint/add_warning @@
+    #![allow(clippy::bool_assert_comparison)]
+    #![allow(clippy::clone_on_copy)]
+    #![allow(clippy::dbg_macro)]
+    #![allow(clippy::mixed_attributes_style)]
+    #![allow(clippy::print_stderr)]
+    #![allow(clippy::print_stdout)]
+    #![allow(clippy::single_char_pattern)]
+    #![allow(clippy::unwrap_used)]
+    #![allow(clippy::unchecked_duration_subtraction)]
+    #![allow(clippy::useless_vec)]
+    #![allow(clippy::needless_pass_by_value)]
+    //! <!-- @@ end test lint list maintained by maint/add_warning @@ -->
+    use super::*;
+    use crate::RpcMgr;
+    use futures::SinkExt as _;
+    use futures_await_test::async_test;
+    use tor_rpc_connect::auth::RpcAuth;
+
+    /// A trivial session object.
+    #[derive(Deftly)]
+    #[derive_deftly(rpc::Object)]
+    struct TestSession;
+
+    #[async_test]
+    async fn health() {
+        let mgr = RpcMgr::new(|_| Arc::new(TestSession) as Arc<dyn rpc::Object>).unwrap();
+        let conn = mgr.new_connection(RpcAuth::Inherent);
+        let _conn2 = mgr.new_connection(RpcAuth::Inherent);
+        drop(mgr.new_connection(RpcAuth::Inherent));
+
+        // We can invoke the method through the dispatch table
+        // on a connection that has not authenticated.
+        let table = conn.dispatch_table.read().unwrap().clone();
+        let ctx: Arc<dyn rpc::Context> = conn.clone() as _;
+        let sink = Box::pin(futures::sink::drain().sink_err_into());
+        let health = table
+            .invoke_typed(Arc::clone(&conn), Box::new(RpcHealth {}), ctx, sink)
+            .unwrap()
+            .await
+            .unwrap();
+
+        // The dropped connection is not counted.
+        assert_eq!(health.n_connections, 2);
+        assert_eq!(health.n_methods, table.len());
+        let schema = table.to_schema();
+        assert!(schema
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|e| e["method"] == "rpc:health"));
+        assert!(health.uptime_secs < 60 * 60);
+    }
+}
@@ -56,8 +56,11 @@ mod objmap;
 mod session;
 mod stream;
 
-pub use connection::{auth::RpcAuthentication, Connection, ConnectionError};
-pub use mgr::RpcMgr;
+pub use connection::{
+    auth::{AuthPrivilege, AuthenticationScheme, RpcAuthentication, MAX_CLIENT_TAG_LEN},
+    Connection, ConnectionError,
+};
+pub use mgr::{RpcMetrics, RpcMgr};
 pub use session::RpcSession;
 
 /// Return a list of RPC methods that will be needed to use `arti-rpcserver` with the given runtime.
@@ -1,6 +1,9 @@
 //! Top-level `RpcMgr` to launch sessions.
 
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::time::Instant;
 
 use rand::Rng;
 use rpc::InvalidRpcIdentifier;
@@ -9,9 +12,9 @@ use tracing::warn;
 use weak_table::WeakValueHashMap;
 
 use crate::{
-    connection::{Connection, ConnectionId},
+    connection::{Connection, ConnectionId, DEFAULT_MAX_REQUESTS_PER_CONNECTION},
     globalid::{GlobalId, MacKey},
-    RpcAuthentication,
+    AuthPrivilege, AuthenticationScheme, RpcAuthentication,
 };
 
 /// A function we use to construct Session objects in response to authentication.
@@ -19,6 +22,13 @@ use crate::{
 // TODO RPC: Perhaps this should return a Result?
 type SessionFactory = Box<dyn Fn(&RpcAuthentication) -> Arc<dyn rpc::Object> + Send + Sync>;
 
+/// A function we call on every new connection; see [`RpcMgr::set_connection_hook`].
+type ConnectionHook = Arc<dyn Fn(&Arc<Connection>) + Send + Sync>;
+
+/// A function that receives the data streams opened by RPC methods;
+/// see [`RpcMgr::set_stream_handler`].
+pub(crate) type StreamHandler = Arc<dyn Fn(&Arc<Connection>, rpc::StreamHandle) + Send + Sync>;
+
 /// Shared state, configuration, and data for all RPC sessions.
 ///
 /// An RpcMgr knows how to listen for incoming RPC connections, and launch sessions based on them.
@@ -55,6 +65,56 @@ pub struct RpcMgr {
     /// Code that holds this lock must be checked
     /// to make sure that it doesn't then acquire any `Connection` lock.
     inner: Mutex<Inner>,
+
+    /// The time at which this `RpcMgr` was created.
+    ///
+    /// Used to report our uptime.
+    started: Instant,
+
+    /// Counters describing our activity, for [`RpcMgr::metrics_snapshot`].
+    ///
+    /// These are atomic, so that we can update and read them without taking any lock.
+    counters: Counters,
+}
+
+/// Counters describing an [`RpcMgr`]'s activity.
+///
+/// See [`RpcMetrics`] for the meaning of each counter.
+#[derive(Debug, Default)]
+struct Counters {
+    /// The number of connections we have ever created.
+    connections_created: AtomicU64,
+    /// The number of connections we have created, and that have since been dropped.
+    connections_closed: AtomicU64,
+    /// The number of requests we have dispatched.
+    requests_dispatched: AtomicU64,
+    /// The number of authentication attempts that have failed.
+    auth_failures: AtomicU64,
+}
+
+/// A snapshot of an [`RpcMgr`]'s activity counters,
+/// as returned by [`RpcMgr::metrics_snapshot`].
+///
+/// All of these counters, other than `connections_live`, only ever increase.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct RpcMetrics {
+    /// The number of connections ever created by the `RpcMgr`.
+    pub connections_created: u64,
+    /// The number of connections that currently exist.
+    ///
+    /// A connection exists until every reference to it has been dropped,
+    /// which may be a little after its underlying stream has closed.
+    pub connections_live: u64,
+    /// The number of requests dispatched on any connection.
+    ///
+    /// This counts every well-formed request that we started to run,
+    /// whether or not it then succeeded.
+    /// Requests that were refused because their connection had too many requests running
+    /// are not counted.
+    pub requests_dispatched: u64,
+    /// The number of authentication attempts that have failed.
+    pub auth_failures: u64,
 }
 
 /// The [`RpcMgr`]'s state. This is kept inside a lock for interior mutability.
@@ -89,6 +149,24 @@ pub(crate) struct Inner {
     /// MACing anything derived from them, which in turn makes the overhead of a
     /// HashMap negligible.
     connections: WeakValueHashMap<ConnectionId, Weak<Connection>>,
+
+    /// The privilege level we advertise for each authentication scheme.
+    ///
+    /// Schemes with no entry here are advertised without a privilege level.
+    auth_privileges: HashMap<AuthenticationScheme, AuthPrivilege>,
+
+    /// The largest number of requests that a new connection will run at once.
+    max_requests_per_connection: usize,
+
+    /// A function to call on every new connection, if any.
+    ///
+    /// We clone this out of the lock before calling it.
+    connection_hook: Option<ConnectionHook>,
+
+    /// A function to receive the data streams opened by RPC methods, if any.
+    ///
+    /// We clone this out of the lock before calling it.
+    stream_handler: Option<StreamHandler>,
 }
 
 /// An error from creating or using an RpcMgr.
@@ -98,6 +176,24 @@ pub enum RpcMgrError {
     /// At least one method had an invalid name.
     #[error("Method {1} had an invalid name")]
     InvalidMethodName(#[source] InvalidRpcIdentifier, String),
+
+    /// Two or more different method types were registered with the same name.
+    #[error("Method name {0} is used by more than one method type")]
+    DuplicateMethodName(String),
+}
+
+/// A summary of an [`RpcMgr`]'s state, as returned by the `rpc:health` method.
+///
+/// This deliberately contains nothing that would be sensitive to reveal
+/// to an unauthenticated client.
+#[derive(Clone, Debug, serde::Serialize)]
+pub(crate) struct RpcMgrHealth {
+    /// The number of whole seconds since the `RpcMgr` was created.
+    pub(crate) uptime_secs: u64,
+    /// The number of RPC connections that are currently open.
+    pub(crate) n_connections: usize,
+    /// The number of method implementations in the dispatch table.
+    pub(crate) n_methods: usize,
 }
 
 /// An [`rpc::Object`], along with its associated [`rpc::Context`].
@@ -105,24 +201,41 @@ pub enum RpcMgrError {
 /// The context can be used to invoke any special methods on the object.
 type ObjectWithContext = (Arc<dyn rpc::Context>, Arc<dyn rpc::Object>);
 
+/// Check the problems with our method names reported by [`rpc::check_method_names`]
+/// and [`rpc::find_duplicate_method_names`].
+///
+/// Warns about every invalid name, and returns an error for the first fatal problem, if any.
+fn check_method_names(
+    problems: Vec<(&str, InvalidRpcIdentifier)>,
+    duplicates: &[&str],
+) -> Result<(), RpcMgrError> {
+    // We warn about every problem.
+    for (m, err) in &problems {
+        warn!("Internal issue: Invalid RPC method name {m:?}: {err}");
+    }
+    let fatal_problem = problems
+        .into_iter()
+        // We don't treat UnrecognizedNamespace as fatal; somebody else might be extending our methods.
+        .find(|(_, err)| !matches!(err, InvalidRpcIdentifier::UnrecognizedNamespace));
+    if let Some((name, err)) = fatal_problem {
+        return Err(RpcMgrError::InvalidMethodName(err, name.to_owned()));
+    }
+    if let Some(name) = duplicates.first() {
+        return Err(RpcMgrError::DuplicateMethodName((*name).to_owned()));
+    }
+    Ok(())
+}
+
 impl RpcMgr {
     /// Create a new RpcMgr.
     pub fn new<F>(make_session: F) -> Result<Arc<Self>, RpcMgrError>
     where
         F: Fn(&RpcAuthentication) -> Arc<dyn rpc::Object> + Send + Sync + 'static,
     {
-        let problems = rpc::check_method_names([]);
-        // We warn about every problem.
-        for (m, err) in &problems {
-            warn!("Internal issue: Invalid RPC method name {m:?}: {err}");
-        }
-        let fatal_problem = problems
-            .into_iter()
-            // We don't treat UnrecognizedNamespace as fatal; somebody else might be extending our methods.
-            .find(|(_, err)| !matches!(err, InvalidRpcIdentifier::UnrecognizedNamespace));
-        if let Some((name, err)) = fatal_problem {
-            return Err(RpcMgrError::InvalidMethodName(err, name.to_owned()));
-        }
+        check_method_names(
+            rpc::check_method_names([]),
+            &rpc::find_duplicate_method_names(),
+        )?;
 
         Ok(Arc::new(RpcMgr {
             global_id_mac_key: MacKey::new(&mut rand::rng()),
@@ -130,7 +243,13 @@ impl RpcMgr {
             session_factory: Box::new(make_session),
             inner: Mutex::new(Inner {
                 connections: WeakValueHashMap::new(),
+                auth_privileges: HashMap::new(),
+                max_requests_per_connection: DEFAULT_MAX_REQUESTS_PER_CONNECTION,
+                connection_hook: None,
+                stream_handler: None,
             }),
+            started: Instant::now(),
+            counters: Counters::default(),
         }))
     }
 
@@ -170,12 +289,18 @@ impl RpcMgr {
         require_auth: tor_rpc_connect::auth::RpcAuth,
     ) -> Arc<Connection> {
         let connection_id = ConnectionId::from(rand::rng().random::<[u8; 16]>());
+        let max_requests = self
+            .inner
+            .lock()
+            .expect("poisoned lock")
+            .max_requests_per_connection;
         let connection = Connection::new(
             connection_id,
             self.dispatch_table.clone(),
             self.global_id_mac_key.clone(),
             Arc::downgrade(self),
             require_auth,
+            max_requests,
         );
 
         let mut inner = self.inner.lock().expect("poisoned lock");
@@ -187,6 +312,15 @@ impl RpcMgr {
             // realistic systems.
             "connection ID collision detected; this is phenomenally unlikely!",
         );
+        let hook = inner.connection_hook.clone();
+        // Release the lock before calling the hook, so that the hook may use this RpcMgr.
+        drop(inner);
+        self.counters
+            .connections_created
+            .fetch_add(1, Ordering::Relaxed);
+        if let Some(hook) = hook {
+            hook(&connection);
+        }
         connection
     }
 
@@ -223,4 +357,182 @@ impl RpcMgr {
     pub(crate) fn create_session(&self, auth: &RpcAuthentication) -> Arc<dyn rpc::Object> {
         (self.session_factory)(auth)
     }
+
+    /// Declare the level of access that sessions authenticated with `scheme` will receive.
+    ///
+    /// This is advertised to clients via `auth:query`.
+    /// It should match how the session factory given to [`RpcMgr::new`]
+    /// actually treats sessions from that scheme.
+    pub fn set_auth_privilege(&self, scheme: AuthenticationScheme, privilege: AuthPrivilege) {
+        let mut inner = self.inner.lock().expect("poisoned lock");
+        inner.auth_privileges.insert(scheme, privilege);
+    }
+
+    /// Set the largest number of requests that a single connection may have running at once.
+    ///
+    /// Once a connection reaches this limit, any further requests it sends
+    /// are refused with an `rpc:TooManyRequests` error until some of its requests finish.
+    /// The default is 128.
+    ///
+    /// This only affects connections created after it is called.
+    pub fn set_max_requests_per_connection(&self, max_requests: usize) {
+        let mut inner = self.inner.lock().expect("poisoned lock");
+        inner.max_requests_per_connection = max_requests;
+    }
+
+    /// Set a function to call on every new connection.
+    ///
+    /// The function is called synchronously by [`new_connection`](RpcMgr::new_connection),
+    /// once the connection has been created and registered with this `RpcMgr`,
+    /// and before the connection is returned.
+    /// It replaces any function set previously.
+    ///
+    /// This `RpcMgr`'s internal lock is released before the function is called,
+    /// so the function may call methods on this `RpcMgr`.
+    /// It should be quick, since it delays the start of the connection.
+    ///
+    /// This only affects connections created after it is called.
+    pub fn set_connection_hook(&self, hook: Box<dyn Fn(&Arc<Connection>) + Send + Sync>) {
+        let mut inner = self.inner.lock().expect("poisoned lock");
+        inner.connection_hook = Some(Arc::from(hook));
+    }
+
+    /// Set a function to receive the data streams opened by RPC methods.
+    ///
+    /// Some RPC methods [yield a data stream](rpc::yields_stream) rather than a value.
+    /// Our connections can't relay such a stream themselves,
+    /// so when such a method succeeds, its stream is passed to this function,
+    /// along with the connection that invoked the method,
+    /// and the client receives an empty successful response.
+    /// It replaces any function set previously.
+    ///
+    /// Until a function is set, requests for methods that yield a stream
+    /// are refused without running the method.
+    pub fn set_stream_handler(
+        &self,
+        handler: Box<dyn Fn(&Arc<Connection>, rpc::StreamHandle) + Send + Sync>,
+    ) {
+        let mut inner = self.inner.lock().expect("poisoned lock");
+        inner.stream_handler = Some(Arc::from(handler));
+    }
+
+    /// Return the function set with [`set_stream_handler`](RpcMgr::set_stream_handler), if any.
+    pub(crate) fn stream_handler(&self) -> Option<StreamHandler> {
+        let inner = self.inner.lock().expect("poisoned lock");
+        inner.stream_handler.clone()
+    }
+
+    /// Return the level of access declared for `scheme`, if any.
+    pub(crate) fn auth_privilege(&self, scheme: AuthenticationScheme) -> Option<AuthPrivilege> {
+        let inner = self.inner.lock().expect("poisoned lock");
+        inner.auth_privileges.get(&scheme).copied()
+    }
+
+    /// Return a summary of this manager's current state.
+    pub(crate) fn health(&self) -> RpcMgrHealth {
+        let n_connections = {
+            let mut inner = self.inner.lock().expect("poisoned lock");
+            inner.connections.remove_expired();
+            inner.connections.len()
+        };
+        // We take this lock only after releasing `inner`:
+        // we don't need to hold both at once.
+        let n_methods = self.dispatch_table.read().expect("poisoned lock").len();
+
+        RpcMgrHealth {
+            uptime_secs: self.started.elapsed().as_secs(),
+            n_connections,
+            n_methods,
+        }
+    }
+
+    /// Return a snapshot of this manager's activity counters.
+    ///
+    /// This doesn't take any lock.
+    pub fn metrics_snapshot(&self) -> RpcMetrics {
+        // These counters don't protect any other data, so relaxed loads suffice,
+        // though the snapshot isn't guaranteed to be consistent across counters.
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        let connections_created = load(&self.counters.connections_created);
+        let connections_closed = load(&self.counters.connections_closed);
+
+        RpcMetrics {
+            connections_created,
+            connections_live: connections_created.saturating_sub(connections_closed),
+            requests_dispatched: load(&self.counters.requests_dispatched),
+            auth_failures: load(&self.counters.auth_failures),
+        }
+    }
+
+    /// Record that a connection created by this manager has been dropped.
+    pub(crate) fn note_connection_closed(&self) {
+        self.counters
+            .connections_closed
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record that a request has been dispatched on one of our connections.
+    pub(crate) fn note_request_dispatched(&self) {
+        self.counters
+            .requests_dispatched
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record that an authentication attempt has failed.
+    pub(crate) fn note_auth_failure(&self) {
+        self.counters.auth_failures.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    // @@ begin test lint list maintained by maint/add_warning @@
+    #![allow(clippy::bool_assert_comparison)]
+    #![allow(clippy::clone_on_copy)]
+    #![allow(clippy::dbg_macro)]
+    #![allow(clippy::mixed_attributes_style)]
+    #![allow(clippy::print_stderr)]
+    #![allow(clippy::print_stdout)]
+    #![allow(clippy::single_char_pattern)]
+    #![allow(clippy::unwrap_used)]
+    #![allow(clippy::unchecked_duration_subtraction)]
+    #![allow(clippy::useless_vec)]
+    #![allow(clippy::needless_pass_by_value)]
+    //! <!-- @@ end test lint list maintained by maint/add_warning @@ -->
+    use super::*;
+
+    // (We can't register two methods with the same name in this crate's tests:
+    // every RpcMgr we create in them would then fail.
+    // `rpc::find_duplicate_method_names` itself is tested in tor-rpcbase.)
+    #[test]
+    fn method_name_problems() {
+        use InvalidRpcIdentifier as IRI;
+
+        assert!(check_method_names(vec![], &[]).is_ok());
+        // Methods from namespaces we don't know about are fine.
+        assert!(
+            check_method_names(vec![("x-other:frob", IRI::UnrecognizedNamespace)], &[]).is_ok()
+        );
+
+        let err = check_method_names(vec![("frob", IRI::NoNamespace)], &[]).unwrap_err();
+        assert!(
+            matches!(&err, RpcMgrError::InvalidMethodName(IRI::NoNamespace, name) if name == "frob"),
+            "{err:?}"
+        );
+
+        // Duplicate names are reported, even when all the names are valid.
+        let err = check_method_names(
+            vec![("x-other:frob", IRI::UnrecognizedNamespace)],
+            &["arti:frob", "arti:twiddle"],
+        )
+        .unwrap_err();
+        assert!(
+            matches!(&err, RpcMgrError::DuplicateMethodName(name) if name == "arti:frob"),
+            "{err:?}"
+        );
+        assert_eq!(
+            err.to_string(),
+            "Method name arti:frob is used by more than one method type"
+        );
+    }
 }
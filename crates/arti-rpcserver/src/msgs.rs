@@ -132,6 +132,10 @@ pub(crate) enum ResponseBody {
     /// progress information is acceptable.
     #[serde(rename = "update")]
     Update(Box<dyn erased_serde::Serialize + Send>),
+    /// The request will receive no more updates,
+    /// but its final response is still to come.
+    #[serde(rename = "updates_done")]
+    UpdatesDone(rpc::Nil),
 }
 
 impl ResponseBody {
@@ -140,7 +144,7 @@ impl ResponseBody {
     pub(crate) fn is_final(&self) -> bool {
         match self {
             ResponseBody::Error(_) | ResponseBody::Success(_) => true,
-            ResponseBody::Update(_) => false,
+            ResponseBody::Update(_) | ResponseBody::UpdatesDone(_) => false,
         }
     }
 }
@@ -162,6 +166,7 @@ impl std::fmt::Debug for ResponseBody {
             Self::Error(arg0) => f.debug_tuple("Error").field(arg0).finish(),
             Self::Update(arg0) => f.debug_tuple("Update").field(&json(arg0)).finish(),
             Self::Success(arg0) => f.debug_tuple("Success").field(&json(arg0)).finish(),
+            Self::UpdatesDone(_) => f.write_str("UpdatesDone"),
         }
     }
 }
@@ -316,6 +321,14 @@ mod test {
         // exact order.
         assert_eq!(s, r#"{"id":7,"result":{"hello":99,"world":"foo"}}"#);
 
+        let resp = BoxedResponse {
+            id: Some(RequestId::Int(7)),
+            body: ResponseBody::UpdatesDone(rpc::NIL),
+        };
+        let s = serde_json::to_string(&resp).unwrap();
+        // NOTE: as above.
+        assert_eq!(s, r#"{"id":7,"updates_done":{}}"#);
+
         let resp = BoxedResponse::from_error(
             None,
             rpc::RpcError::from(crate::err::RequestParseError::IdMissing),
@@ -347,5 +360,7 @@ mod test {
             world: "foo".into(),
         }));
         assert!(!response_body_update.is_final());
+
+        assert!(!ResponseBody::UpdatesDone(rpc::NIL).is_final());
     }
 }
@@ -81,9 +81,82 @@ pub struct ApplicationConfig {
     /// This has no effect on Windows.
     #[builder(default)]
     pub(crate) allow_running_as_root: bool,
+
+    /// Settings for rotating the log files written by the application.
+    #[builder(sub_builder(fn_name = "build"))]
+    #[builder_field_attr(serde(default))]
+    pub(crate) log_rotation: LogRotationConfig,
 }
 impl_standard_builder! { ApplicationConfig }
 
+impl ApplicationConfig {
+    /// Return the [`LogRotationConfig`] for this configuration.
+    pub fn log_rotation(&self) -> &LogRotationConfig {
+        &self.log_rotation
+    }
+}
+
+/// Settings for rotating an application's log files by size.
+///
+/// Arti does not act on these settings itself:
+/// an application embedding Arti reads them to configure its own file logger.
+#[derive(Debug, Clone, Builder, Eq, PartialEq)]
+#[builder(build_fn(error = "ConfigBuildError", validate = "Self::validate"))]
+#[builder(derive(Debug, Serialize, Deserialize))]
+#[builder_struct_attr(non_exhaustive)]
+#[non_exhaustive]
+pub struct LogRotationConfig {
+    /// The size, in bytes, at which a log file should be rotated.
+    ///
+    /// If this is `None`, log files are not rotated by size.
+    #[builder(default)]
+    pub(crate) max_size_bytes: Option<u64>,
+
+    /// The number of rotated log files to keep.
+    ///
+    /// If this is `None`, rotated log files are never deleted.
+    /// May only be set along with `max_size_bytes`.
+    #[builder(default)]
+    pub(crate) max_files: Option<u32>,
+
+    /// If true, rotated log files should be compressed.
+    #[builder(default)]
+    pub(crate) compress: bool,
+}
+impl_standard_builder! { LogRotationConfig }
+
+impl LogRotationConfig {
+    /// Return the size, in bytes, at which a log file should be rotated, if any.
+    pub fn max_size_bytes(&self) -> Option<u64> {
+        self.max_size_bytes
+    }
+
+    /// Return the number of rotated log files to keep, if limited.
+    pub fn max_files(&self) -> Option<u32> {
+        self.max_files
+    }
+
+    /// Return true if rotated log files should be compressed.
+    pub fn compress(&self) -> bool {
+        self.compress
+    }
+}
+
+impl LogRotationConfigBuilder {
+    /// Check that this builder will give a reasonable configuration.
+    fn validate(&self) -> Result<(), ConfigBuildError> {
+        let max_size_bytes = self.max_size_bytes.flatten();
+        let max_files = self.max_files.flatten();
+        if max_files.is_some() && max_size_bytes.is_none() {
+            return Err(ConfigBuildError::Inconsistent {
+                fields: vec!["max_files".into(), "max_size_bytes".into()],
+                problem: "max_files is set, but log files are not rotated by size".into(),
+            });
+        }
+        Ok(())
+    }
+}
+
 /// Resolves values from `$field_listen` and `$field_port` (compat) into a `Listen`
 ///
 /// For `dns` and `proxy`.
@@ -330,6 +403,24 @@ impl ArtiConfig {
     pub fn rpc(&self) -> &RpcConfig {
         &self.rpc
     }
+
+    /// Return true if this configuration asks for the RPC subsystem to run.
+    ///
+    /// This is the single switch that decides whether to start RPC:
+    /// an application embedding Arti should check it
+    /// before constructing an `RpcMgr` or binding any RPC connect points,
+    /// rather than inferring it from which listeners are configured.
+    ///
+    /// Always returns false when Arti is built without the `rpc` feature.
+    pub fn rpc_enabled(&self) -> bool {
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "rpc")] {
+                self.rpc.enabled()
+            } else {
+                false
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -524,7 +615,9 @@ mod test {
             &[
                 // Keys that are newer than the oldest-supported example, but otherwise normal.
                 "application.allow_running_as_root",
+                "application.log_rotation",
                 "bridges",
+                "circuit_timing.max_concurrent_builds",
                 "logging.time_granularity",
                 "path_rules.long_lived_ports",
                 "proxy.socks_listen",
@@ -532,6 +625,17 @@ mod test {
             ],
         );
 
+        declare_exceptions(
+            None,
+            None,
+            Recognized,
+            &[
+                // Unset by default, so the examples are not auto-testable
+                "application.log_rotation.max_size_bytes",
+                "application.log_rotation.max_files",
+            ],
+        );
+
         declare_exceptions(
             None,
             None,
@@ -653,6 +757,7 @@ mod test {
                 "storage.keystore.ctor",
                 "storage.keystore.ctor.services",
                 "storage.keystore.ctor.clients",
+                "storage.keystore.ctor.relays",
             ],
         );
 
@@ -1378,6 +1483,28 @@ example config file {which:?}, uncommented={uncommented:?}
         assert_eq!(rpc_parsed, &rpc_default);
     }
 
+    #[test]
+    fn rpc_enabled() {
+        // By default, RPC is off.
+        let cfg = ArtiConfigBuilder::default().build().unwrap();
+        assert!(!cfg.rpc_enabled());
+
+        #[cfg(feature = "rpc")]
+        {
+            assert!(!RpcConfigBuilder::default().build().unwrap().enabled());
+
+            let mut bld = ArtiConfigBuilder::default();
+            bld.rpc().enable(true);
+            let cfg = bld.build().unwrap();
+            assert!(cfg.rpc_enabled());
+            assert!(cfg.rpc().enabled());
+
+            let mut bld = ArtiConfigBuilder::default();
+            bld.rpc().enable(false);
+            assert!(!bld.build().unwrap().rpc_enabled());
+        }
+    }
+
     #[cfg(feature = "rpc")]
     #[test]
     fn rpc_full() {
@@ -1633,6 +1760,46 @@ example config file {which:?}, uncommented={uncommented:?}
         assert_eq!(&config.application, application);
     }
 
+    #[test]
+    fn log_rotation() {
+        let config = ApplicationConfig::default();
+        let rotation = config.log_rotation();
+        assert_eq!(rotation, &LogRotationConfig::default());
+        assert_eq!(rotation.max_size_bytes(), None);
+        assert_eq!(rotation.max_files(), None);
+        assert!(!rotation.compress());
+
+        let from_toml = |s: &str| -> ApplicationConfigBuilder {
+            let cfg: toml::Value = toml::from_str(s).unwrap();
+            cfg.try_into().unwrap()
+        };
+
+        let config = from_toml(
+            r#"
+            [log_rotation]
+            max_size_bytes = 10000000
+            max_files = 5
+            compress = true
+            "#,
+        )
+        .build()
+        .unwrap();
+        let rotation = config.log_rotation();
+        assert_eq!(rotation.max_size_bytes(), Some(10_000_000));
+        assert_eq!(rotation.max_files(), Some(5));
+        assert!(rotation.compress());
+
+        let err = from_toml(
+            r#"
+            [log_rotation]
+            max_files = 5
+            "#,
+        )
+        .build()
+        .unwrap_err();
+        assert!(matches!(err, ConfigBuildError::Inconsistent { .. }));
+    }
+
     #[test]
     fn articonfig_logging() {
         let config = ArtiConfig::default();
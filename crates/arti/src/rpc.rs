@@ -49,6 +49,15 @@ pub struct RpcConfig {
 }
 impl_standard_builder! { RpcConfig }
 
+impl RpcConfig {
+    /// Return true if the RPC subsystem is enabled.
+    ///
+    /// When this is false, no `RpcMgr` should be constructed and no connect points bound.
+    pub fn enabled(&self) -> bool {
+        self.enable
+    }
+}
+
 /// Type alias to enable sub_builder to work.
 type ListenDefaults = Vec<String>;
 
@@ -147,7 +156,7 @@ pub(crate) async fn launch_rpc_mgr<R: Runtime>(
     mistrust: &Mistrust,
     client: TorClient<R>,
 ) -> Result<Option<RpcProxySupport>> {
-    if !cfg.enable {
+    if !cfg.enabled() {
         return Ok(None);
     }
     let (rpc_state, rpc_state_sender) = RpcVisibleArtiState::new();
@@ -459,7 +459,14 @@ impl Mistrust {
 
     /// Return true if this `Mistrust` object has been configured to trust all
     /// users.
-    pub(crate) fn is_disabled(&self) -> bool {
+    ///
+    /// This is the case if it was built with
+    /// [`dangerously_trust_everyone`](MistrustBuilder::dangerously_trust_everyone),
+    /// or if permission checks were disabled with an environment variable.
+    ///
+    /// Code that performs permission checks of its own
+    /// should skip them when this returns true.
+    pub fn is_disabled(&self) -> bool {
         self.status.disabled()
     }
 
@@ -67,11 +67,24 @@ pub mod deps {
 ///   that can be accessed from a given element by calling `element.func()`.
 /// * `name : type { .field }` - A key whose name is `name` and type is `type`,
 ///   that can be accessed from a given element by calling `&element.field`.
+/// * `name : type { try func() }` - A key whose name is `name` and type is `type`,
+///   that can be accessed from a given element by calling `element.func()`,
+///   which returns a `Result<&type, _>`.
 /// * `name : type` - Short for as `name : type { name() }`.
 ///
 /// If a key declaration is preceded with `(Option)`, then the
 /// key is treated as optional, and accessor functions are expected to return
-/// `Option<&Type>`.
+/// `Option<&Type>` (or, with `try`, `Result<Option<&Type>, _>`).
+///
+/// A key with a `try` accessor behaves like an optional key,
+/// even if it is not declared as `(Option)`:
+/// if the accessor returns an error for some element,
+/// that element is not indexed under that key,
+/// but can still be found by its other keys.
+/// This is useful for keys that are computed, and whose computation can fail.
+/// The accessor must give the same result every time it is called on
+/// the same element; and if every key is optional or `try`,
+/// every element must have at least one key that can be derived.
 ///
 /// # Additional features
 ///
@@ -346,6 +359,21 @@ This could be more efficient in space and time.
                 .expect("Tried to add a value with no key!")
         }
 
+        /// Return the keys under which `value` would be indexed in this set.
+        ///
+        /// The result is a tuple with one entry per key, in the order
+        /// the keys were declared.  Each entry is `None` if `value` has no
+        /// value for that (optional) key.
+        ///
+        /// `value` does not need to be a member of this set.  The keys
+        /// are cloned, so the result does not borrow from `value`.
+        $vis fn keys_of(&self, value: &$V) -> ( $( Option<$KEY>, )+ ) {
+            ( $(
+                $crate::n_key_set!( @access(value, ($($($flag)+)?) $key : $KEY $({$($source)+})?) )
+                    .map(|key| key.to_owned()),
+            )+ )
+        }
+
         /// Return the number of elements in this container.
         $vis fn len(&self) -> usize {
             self.values.len()
@@ -570,6 +598,12 @@ This could be more efficient in space and time.
 { @access($ex:expr, () $key:ident : $t:ty { $func:ident () } ) } => {
     Some($ex.$func())
 };
+{ @access($ex:expr, (Option) $key:ident : $t:ty { try $func:ident () } ) } => {
+    $ex.$func().ok().flatten()
+};
+{ @access($ex:expr, () $key:ident : $t:ty { try $func:ident () } ) } => {
+    $ex.$func().ok()
+};
 }
 
 /// An error returned from an operation on an `n_key_set`.
@@ -736,6 +770,98 @@ mod test {
         }
     }
 
+    #[test]
+    fn keys_of() {
+        let mut set = WeekdaySet::new();
+        let monday = Weekday {
+            dow: 1,
+            name: "Monday",
+            lucky_number: Some(7),
+        };
+        let tuesday = Weekday {
+            dow: 2,
+            name: "Tuesday",
+            lucky_number: None,
+        };
+        assert_eq!(
+            set.keys_of(&monday),
+            (Some(1), Some(7), Some("Monday".to_string()))
+        );
+        assert_eq!(
+            set.keys_of(&tuesday),
+            (Some(2), None, Some("Tuesday".to_string()))
+        );
+
+        // Membership doesn't matter.
+        set.insert(monday);
+        let monday = set.by_idx(&1).unwrap();
+        assert_eq!(
+            set.keys_of(monday),
+            (Some(1), Some(7), Some("Monday".to_string()))
+        );
+
+        let set = Tuple2Set::<u32, u16>::new();
+        assert_eq!(set.keys_of(&(5, 6)), (Some(5), Some(6)));
+    }
+
+    struct Host {
+        name: &'static str,
+        addr: &'static str,
+        port: Result<u16, &'static str>,
+    }
+    impl Host {
+        fn port(&self) -> Result<&u16, &'static str> {
+            self.port.as_ref().map_err(|e| *e)
+        }
+    }
+    n_key_set! {
+        struct HostSet for Host {
+            name: &'static str { .name },
+            port: u16 { try port() },
+        }
+    }
+
+    #[test]
+    fn fallible_accessor() {
+        let parse = |addr: &'static str| -> Result<u16, &'static str> {
+            let (_, port) = addr.rsplit_once(':').ok_or("no port")?;
+            port.parse().map_err(|_| "bad port")
+        };
+        let host = |name, addr| Host {
+            name,
+            addr,
+            port: parse(addr),
+        };
+
+        let mut set = HostSet::new();
+        set.insert(host("www", "www.example.com:443"));
+        set.insert(host("mail", "mail.example.com"));
+        set.insert(host("dns", "ns.example.com:domain"));
+        set.check_invariants();
+        assert_eq!(set.len(), 3);
+
+        // The hosts whose port we couldn't derive can still be found by name.
+        assert_eq!(set.by_name("mail").unwrap().addr, "mail.example.com");
+        assert_eq!(set.by_name("dns").unwrap().addr, "ns.example.com:domain");
+        assert_eq!(set.by_port(&443).unwrap().name, "www");
+        assert_eq!(
+            set.keys_of(set.by_name("mail").unwrap()),
+            (Some("mail"), None)
+        );
+
+        // Hosts without a port don't conflict with each other.
+        assert!(set.insert(host("ftp", "ftp.example.com")).is_empty());
+        assert_eq!(set.len(), 4);
+        // But hosts with the same port still do.
+        let replaced = set.insert(host("web", "web.example.com:443"));
+        assert_eq!(replaced.len(), 1);
+        assert_eq!(replaced[0].name, "www");
+
+        assert!(set.remove_by_name("mail").is_some());
+        set.check_invariants();
+        assert_eq!(set.len(), 3);
+    }
+
     n_key_set! {
         struct['a] ArrayMap['a] for (String, [&'a u32;10]) {
             name: String { .0 }
@@ -2,7 +2,7 @@
 
 use crate::path::{OwnedPath, TorPath};
 use crate::timeouts::{self, Action};
-use crate::{Error, Result};
+use crate::{BuildStage, Error, Result};
 use async_trait::async_trait;
 use futures::task::SpawnExt;
 use futures::Future;
@@ -113,6 +113,7 @@ async fn create_common<RT: Runtime, CT: ChanTarget>(
         error,
         peer: None, // we don't blame the peer, because new_circ() does no networking.
         action: "initializing circuit",
+        stage: BuildStage::FirstHop,
         unique_id: None,
     })?;
 
@@ -142,6 +143,7 @@ impl Buildable for ClientCirc {
                 peer: Some(ct.to_logged()),
                 error,
                 action: "running CREATE_FAST handshake",
+                stage: BuildStage::FirstHop,
                 unique_id,
             })
     }
@@ -168,6 +170,7 @@ impl Buildable for ClientCirc {
             peer: Some(ct.to_logged()),
             error,
             action: "creating first hop",
+            stage: BuildStage::FirstHop,
             unique_id,
         })
     }
@@ -191,6 +194,7 @@ impl Buildable for ClientCirc {
             // to.
             peer: None,
             action: "extending circuit",
+            stage: BuildStage::Extension,
             unique_id: Some(self.unique_id()),
         })
     }
@@ -246,6 +246,16 @@ pub struct CircuitTiming {
     #[getter(skip)]
     pub(crate) request_loyalty: Duration,
 
+    /// The largest number of circuits that we build at once,
+    /// for all purposes combined.
+    ///
+    /// When this many circuits are being built,
+    /// any further circuits wait to be built until one of them has finished.
+    /// A value of 0 is treated as 1.
+    #[builder(default = "default_max_concurrent_builds()")]
+    #[getter(skip)]
+    pub(crate) max_concurrent_builds: usize,
+
     /// When an HS connection is attempted, we stop trying more hsdirs after this many attempts
     //
     // This parameter is honoured by tor-hsclient, not here.
@@ -334,6 +344,11 @@ fn default_request_loyalty() -> Duration {
     Duration::from_millis(50)
 }
 
+/// Return the default value for `max_concurrent_builds`.
+fn default_max_concurrent_builds() -> usize {
+    64
+}
+
 define_accessor_trait! {
     /// Configuration for a circuit manager
     ///
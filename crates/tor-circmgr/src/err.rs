@@ -120,6 +120,8 @@ pub enum Error {
     Protocol {
         /// The action that we were trying to take.
         action: &'static str,
+        /// The part of the circuit we were building when we failed.
+        stage: BuildStage,
         /// The peer that created the protocol error.
         ///
         /// This is set to None if we can't blame a single party.
@@ -151,6 +153,40 @@ pub enum Error {
     Bug(#[from] Bug),
 }
 
+/// The part of a circuit that we were building when an [`Error::Protocol`] occurred.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum BuildStage {
+    /// Setting up the circuit on a channel to the first hop (the guard),
+    /// or creating the first hop itself.
+    FirstHop,
+    /// Extending the circuit beyond its first hop.
+    Extension,
+}
+
+/// A coarse classification of the reason why we failed to build a circuit.
+///
+/// Returned by [`Error::build_failure`],
+/// to help decide whether (and how soon) to retry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CircBuildFailure {
+    /// We couldn't select a guard,
+    /// or we failed to open a channel to the guard or create the circuit's first hop,
+    /// or we decided not to use the guard after all.
+    Guard,
+    /// We failed to extend the circuit beyond its first hop.
+    Extension,
+    /// The circuit (or the request for it) took too long.
+    Timeout,
+    /// We couldn't find the relays we needed in our network directory.
+    ///
+    /// This may mean that the directory is out of date.
+    NetDirStale,
+    /// Some other problem, such as an internal error.
+    Other,
+}
+
 tor_error::define_asref_dyn_std_error!(Error);
 tor_error::define_asref_dyn_std_error!(Box<Error>);
 
@@ -318,6 +354,38 @@ impl Error {
         }
     }
 
+    /// Classify this error according to which part of circuit building failed.
+    ///
+    /// For an error that wraps several others, this classifies the most severe of them.
+    pub fn build_failure(&self) -> CircBuildFailure {
+        use CircBuildFailure as F;
+        use Error as E;
+        match self {
+            E::Guard(_) | E::GuardMgr(_) | E::GuardNotUsable(_) | E::Channel { .. } => F::Guard,
+            E::Protocol { stage, .. } => match stage {
+                BuildStage::FirstHop => F::Guard,
+                BuildStage::Extension => F::Extension,
+            },
+            E::CircTimeout(_) | E::RequestTimeout => F::Timeout,
+            E::NoRelay { .. } => F::NetDirStale,
+            E::PendingFailed(e) => e.build_failure(),
+            E::RequestFailed(errors) => errors
+                .sources()
+                .max_by_key(|e| e.severity())
+                .map(|e| e.build_failure())
+                .unwrap_or(F::Other),
+            #[cfg(all(feature = "vanguards", feature = "hs-common"))]
+            E::VanguardMgrInit(_) => F::Other,
+            E::PendingCanceled
+            | E::LostUsabilityRace(_)
+            | E::CircCanceled
+            | E::UsageMismatched(_)
+            | E::Spawn { .. }
+            | E::State(_)
+            | E::Bug(_) => F::Other,
+        }
+    }
+
     /// Return true if this error should not count against our total number of
     /// failures.
     ///
@@ -417,3 +485,82 @@ impl<'a> std::fmt::Display for OptUniqId<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    // @@ begin test lint list maintained by maint/add_warning @@
+    #![allow(clippy::bool_assert_comparison)]
+    #![allow(clippy::clone_on_copy)]
+    #![allow(clippy::dbg_macro)]
+    #![allow(clippy::mixed_attributes_style)]
+    #![allow(clippy::print_stderr)]
+    #![allow(clippy::print_stdout)]
+    #![allow(clippy::single_char_pattern)]
+    #![allow(clippy::unwrap_used)]
+    #![allow(clippy::unchecked_duration_subtraction)]
+    #![allow(clippy::useless_vec)]
+    #![allow(clippy::needless_pass_by_value)]
+    //! <!-- @@ end test lint list maintained by maint/add_warning @@ -->
+    use super::*;
+    use tor_error::internal;
+
+    /// Return a `Protocol` error for a failure while taking `action` during `stage`.
+    fn protocol_err(action: &'static str, stage: BuildStage) -> Error {
+        Error::Protocol {
+            action,
+            stage,
+            peer: None,
+            error: tor_proto::Error::CircuitClosed,
+            unique_id: None,
+        }
+    }
+
+    #[test]
+    fn build_failure() {
+        use CircBuildFailure as F;
+
+        let peer = OwnedChanTarget::builder()
+            .ed_identity([0x10; 32].into())
+            .rsa_identity([0x20; 20].into())
+            .build()
+            .unwrap();
+        let guard_errs = [
+            Error::Guard(tor_guardmgr::PickGuardError::NoCandidatesAvailable),
+            Error::Channel {
+                peer: peer.into(),
+                cause: tor_chanmgr::Error::UnusableTarget(internal!("bad guard")),
+            },
+            protocol_err("creating first hop", BuildStage::FirstHop),
+            protocol_err("running CREATE_FAST handshake", BuildStage::FirstHop),
+        ];
+        for e in &guard_errs {
+            assert_eq!(e.build_failure(), F::Guard, "{e:?}");
+        }
+
+        assert_eq!(
+            protocol_err("extending circuit", BuildStage::Extension).build_failure(),
+            F::Extension
+        );
+        assert_eq!(Error::CircTimeout(None).build_failure(), F::Timeout);
+        assert_eq!(Error::RequestTimeout.build_failure(), F::Timeout);
+        let no_relay = Error::NoRelay {
+            path_kind: "exit",
+            role: "final hop",
+            problem: "no exits".into(),
+        };
+        assert_eq!(no_relay.build_failure(), F::NetDirStale);
+        assert_eq!(Error::Bug(internal!("oops")).build_failure(), F::Other);
+        assert_eq!(Error::CircCanceled.build_failure(), F::Other);
+
+        // Wrapped errors are classified by what they wrap.
+        let pending = Error::PendingFailed(Box::new(Error::CircTimeout(None)));
+        assert_eq!(pending.build_failure(), F::Timeout);
+
+        let mut errors: RetryError<Box<Error>> = RetryError::in_attempt_to("build a circuit");
+        errors.push(Error::CircTimeout(None));
+        errors.push(protocol_err("extending circuit", BuildStage::Extension));
+        errors.push(Error::CircCanceled);
+        // The protocol error is the most severe.
+        assert_eq!(Error::RequestFailed(errors).build_failure(), F::Extension);
+    }
+}
@@ -13,7 +13,7 @@ use std::{
 use crate::{
     build::{onion_circparams_from_netparams, CircuitBuilder},
     mgr::AbstractCircBuilder,
-    timeouts, AbstractCirc, CircMgr, CircMgrInner, Error, Result,
+    timeouts, AbstractCirc, BuildStage, CircMgr, CircMgrInner, Error, Result,
 };
 use futures::{task::SpawnExt, StreamExt, TryFutureExt};
 use once_cell::sync::OnceCell;
@@ -157,6 +157,7 @@ impl<C: AbstractCirc> HsCircStem<C> {
 ///      ```
 #[derive(Copy, Clone, Debug, PartialEq, derive_more::Display)]
 #[non_exhaustive]
+#[cfg_attr(feature = "experimental-api", visibility::make(pub))]
 pub(crate) enum HsCircStemKind {
     /// A naive circuit stem.
     ///
@@ -461,6 +462,7 @@ impl<B: AbstractCircBuilder<R> + 'static, R: Runtime> HsCircPoolInner<B, R> {
             .extend_ntor(&target, &params)
             .map_err(|error| Error::Protocol {
                 action: "extending to chosen HS hop",
+                stage: BuildStage::Extension,
                 peer: None, // Either party could be to blame.
                 unique_id: Some(circ.unique_id()),
                 error,
@@ -78,6 +78,41 @@ pub(crate) struct Plan {
 
 impl MockablePlan for Plan {}
 
+impl<R: Runtime> CircuitBuilder<R> {
+    /// Select a path for a circuit with the given `usage`, without building it.
+    ///
+    /// Returns the path that we would build the circuit along,
+    /// and the usage that the circuit would support once built.
+    /// The circuit would have one hop for every hop in the path.
+    ///
+    /// Any guard that was selected for the path is told that the attempt was
+    /// abandoned, so that our guard statistics are not affected by the plan.
+    ///
+    /// This is useful for diagnostics, and for testing path selection.
+    #[cfg(any(test, feature = "experimental-api"))]
+    #[cfg_attr(feature = "experimental-api", visibility::make(pub))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "experimental-api")))]
+    pub(crate) fn plan_only(
+        &self,
+        usage: &TargetCircUsage,
+        dir: DirInfo<'_>,
+    ) -> Result<(OwnedPath, SupportedCircUsage)> {
+        let (plan, _) = mgr::AbstractCircBuilder::plan_circuit(self, usage, dir)?;
+        let Plan {
+            final_spec,
+            path,
+            guard_status,
+            ..
+        } = plan;
+
+        if let Some(guard_status) = guard_status {
+            guard_status.attempt_abandoned();
+        }
+
+        Ok((path, final_spec))
+    }
+}
+
 #[async_trait]
 impl<R: Runtime> crate::mgr::AbstractCircBuilder<R> for crate::build::CircuitBuilder<R> {
     type Circ = ClientCirc;
@@ -222,3 +257,112 @@ impl<R: Runtime> crate::mgr::AbstractCircBuilder<R> for crate::build::CircuitBui
         CircuitBuilder::update_network_parameters(self, p);
     }
 }
+
+#[cfg(test)]
+mod test {
+    // @@ begin test lint list maintained by maint/add_warning @@
+    #![allow(clippy::bool_assert_comparison)]
+    #![allow(clippy::clone_on_copy)]
+    #![allow(clippy::dbg_macro)]
+    #![allow(clippy::mixed_attributes_style)]
+    #![allow(clippy::print_stderr)]
+    #![allow(clippy::print_stdout)]
+    #![allow(clippy::single_char_pattern)]
+    #![allow(clippy::unwrap_used)]
+    #![allow(clippy::unchecked_duration_subtraction)]
+    #![allow(clippy::useless_vec)]
+    #![allow(clippy::needless_pass_by_value)]
+    //! <!-- @@ end test lint list maintained by maint/add_warning @@ -->
+    use super::*;
+    use crate::{CircMgrInner, TestConfig};
+    use tor_memquota::ArcMemoryQuotaTrackerExt as _;
+    use tor_netdir::testnet;
+    use tor_proto::memquota::ToplevelAccount;
+    use tor_rtmock::MockRuntime;
+
+    /// Create a `CircMgrInner` using a real `CircuitBuilder`, and a guard
+    /// manager that knows about `netdir`.
+    fn make_circmgr<R: Runtime>(
+        runtime: R,
+        netdir: &tor_netdir::NetDir,
+    ) -> CircMgrInner<CircuitBuilder<R>, R> {
+        let chanmgr = tor_chanmgr::ChanMgr::new(
+            runtime.clone(),
+            &Default::default(),
+            tor_chanmgr::Dormancy::Dormant,
+            &Default::default(),
+            ToplevelAccount::new_noop(),
+        );
+        let guardmgr = tor_guardmgr::GuardMgr::new(
+            runtime.clone(),
+            tor_persist::TestingStateMgr::new(),
+            &tor_guardmgr::TestConfig::default(),
+        )
+        .unwrap();
+        guardmgr.install_test_netdir(netdir);
+
+        CircMgrInner::new(
+            &TestConfig::default(),
+            tor_persist::TestingStateMgr::new(),
+            &runtime,
+            Arc::new(chanmgr),
+            &guardmgr,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn plan_without_building() {
+        MockRuntime::test_with_various(|runtime| async move {
+            let netdir = testnet::construct_netdir().unwrap_if_sufficient().unwrap();
+            let circmgr = make_circmgr(runtime.clone(), &netdir);
+            runtime.progress_until_stalled().await;
+            let n_tasks = runtime.mock_task().n_tasks();
+
+            let (path, final_spec) = circmgr
+                .builder()
+                .plan_only(&TargetCircUsage::Dir, (&netdir).into())
+                .unwrap();
+            assert_eq!(path.len(), 1);
+
+            // Nothing was launched: no task was spawned to build the circuit,
+            // and the circuit manager knows of no circuit, pending or open.
+            runtime.progress_until_stalled().await;
+            assert_eq!(runtime.mock_task().n_tasks(), n_tasks);
+            assert!(circmgr.pool_stats().is_empty());
+            assert!(matches!(final_spec, SupportedCircUsage::Dir));
+        });
+    }
+
+    #[test]
+    #[cfg(feature = "hs-common")]
+    fn plan_hs_circuit_hops() {
+        use crate::hspool::HsCircStemKind;
+
+        MockRuntime::test_with_various(|runtime| async move {
+            let netdir = testnet::construct_netdir().unwrap_if_sufficient().unwrap();
+            let circmgr = make_circmgr(runtime, &netdir);
+
+            #[cfg(feature = "vanguards")]
+            let (mode, _provider) = {
+                let vanguards = circmgr.builder().vanguardmgr();
+                let provider = vanguards.init_vanguard_sets(&netdir).await.unwrap();
+                (vanguards.mode(), provider)
+            };
+            #[cfg(not(feature = "vanguards"))]
+            let mode = tor_guardmgr::VanguardMode::Disabled;
+
+            for kind in [HsCircStemKind::Naive, HsCircStemKind::Guarded] {
+                let usage = TargetCircUsage::HsCircBase {
+                    compatible_with_target: None,
+                    kind,
+                };
+                let (path, _) = circmgr
+                    .builder()
+                    .plan_only(&usage, (&netdir).into())
+                    .unwrap();
+                assert_eq!(path.len(), kind.num_hops(mode).unwrap(), "{kind}");
+            }
+        });
+    }
+}
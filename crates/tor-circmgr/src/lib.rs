@@ -62,6 +62,7 @@ use tor_linkspec::IntoOwnedChanTarget;
 
 use futures::task::SpawnExt;
 use futures::StreamExt;
+use std::collections::BTreeMap;
 use std::sync::{Arc, Mutex, Weak};
 use std::time::{Duration, Instant};
 use tracing::{debug, info, trace, warn};
@@ -84,11 +85,17 @@ mod preemptive;
 pub mod timeouts;
 mod usage;
 
-pub use err::Error;
+pub use err::{BuildStage, CircBuildFailure, Error};
 pub use isolation::IsolationToken;
 use tor_guardmgr::fallback::FallbackList;
 pub use tor_guardmgr::{ClockSkewEvents, GuardMgrConfig, SkewEstimate};
-pub use usage::{TargetPort, TargetPorts};
+pub use usage::{CircPoolStats, TargetPort, TargetPorts, UsageCategory};
+#[cfg(feature = "experimental-api")]
+#[cfg_attr(docsrs, doc(cfg(feature = "experimental-api")))]
+pub use {
+    path::OwnedPath,
+    usage::{ExitPolicy, SupportedCircUsage, TargetCircUsage},
+};
 
 pub use config::{
     CircMgrConfig, CircuitTiming, CircuitTimingBuilder, PathConfig, PathConfigBuilder,
@@ -98,6 +105,7 @@ pub use config::{
 use crate::isolation::StreamIsolation;
 use crate::mgr::CircProvenance;
 use crate::preemptive::PreemptiveCircuitPredictor;
+#[cfg(not(feature = "experimental-api"))]
 use usage::TargetCircUsage;
 
 use safelog::sensitive as sv;
@@ -354,6 +362,28 @@ impl<R: Runtime> CircMgr<R> {
         self.0.estimate_timeout(timeout_action)
     }
 
+    /// Return the number of open circuits held by this manager, grouped by
+    /// the [`UsageCategory`] they can be used for.
+    ///
+    /// Only categories with at least one circuit are listed.
+    /// The result is sorted by category.
+    pub fn pool_status(&self) -> Vec<(UsageCategory, usize)> {
+        self.0.pool_status()
+    }
+
+    /// Return the number of idle, in-use, and pending circuits held by this manager,
+    /// grouped by the [`UsageCategory`] they can be used for.
+    ///
+    /// An open circuit is in use once it has been handed out for a request,
+    /// and idle until then.
+    /// A pending circuit is counted under the category of the usage
+    /// it is currently expected to support.
+    ///
+    /// Only categories with at least one circuit are listed.
+    pub fn pool_stats(&self) -> BTreeMap<UsageCategory, CircPoolStats> {
+        self.0.pool_stats()
+    }
+
     /// Return a reference to the associated CircuitBuilder that this CircMgr
     /// will use to create its circuits.
     #[cfg(feature = "experimental-api")]
@@ -971,7 +1001,15 @@ impl<B: AbstractCircBuilder<R> + 'static, R: Runtime> CircMgrInner<B, R> {
             compatible_with_target: planned_target.map(IntoOwnedChanTarget::to_owned),
             kind,
         };
-        let (_, client_circ) = self.mgr.launch_unmanaged(&usage, dir.into()).await?;
+        #[cfg_attr(not(feature = "vanguards"), allow(unused_variables))]
+        let (supported, client_circ) = self.mgr.launch_unmanaged(&usage, dir.into()).await?;
+
+        #[cfg(feature = "vanguards")]
+        match supported.vanguard_mode() {
+            Some(mode) => debug!("Built onion service circuit with {mode} vanguards"),
+            None => debug!("Built onion service circuit without vanguards"),
+        }
+
         Ok(client_circ)
     }
 
@@ -1040,6 +1078,16 @@ impl<B: AbstractCircBuilder<R> + 'static, R: Runtime> CircMgrInner<B, R> {
         let _ = self.mgr.take_circ(circ_id);
     }
 
+    /// Internal implementation for [`CircMgr::pool_stats`].
+    pub(crate) fn pool_stats(&self) -> BTreeMap<UsageCategory, CircPoolStats> {
+        self.mgr.pool_stats()
+    }
+
+    /// Internal implementation for [`CircMgr::pool_status`].
+    pub(crate) fn pool_status(&self) -> Vec<(UsageCategory, usize)> {
+        self.mgr.pool_status()
+    }
+
     /// Return a stream of events about our estimated clock skew; these events
     /// are `None` when we don't have enough information to make an estimate,
     /// and `Some(`[`SkewEstimate`]`)` otherwise.
@@ -22,7 +22,7 @@
 //    - Error reported by restrict_mut?
 
 use crate::config::CircuitTiming;
-use crate::usage::{SupportedCircUsage, TargetCircUsage};
+use crate::usage::{CircPoolStats, SupportedCircUsage, TargetCircUsage, UsageCategory};
 use crate::{timeouts, DirInfo, Error, PathConfig, Result};
 
 use retry_error::RetryError;
@@ -42,7 +42,7 @@ use futures::future::{FutureExt, Shared};
 use futures::stream::{FuturesUnordered, StreamExt};
 use futures::task::SpawnExt;
 use oneshot_fused_workaround as oneshot;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Debug;
 use std::hash::Hash;
 use std::panic::AssertUnwindSafe;
@@ -51,6 +51,7 @@ use std::time::{Duration, Instant};
 use tracing::{debug, warn};
 use weak_table::PtrWeakHashSet;
 
+mod limit;
 mod streams;
 
 /// Description of how we got a circuit.
@@ -732,6 +733,11 @@ pub(crate) struct AbstractCircMgr<B: AbstractCircBuilder<R>, R: Runtime> {
     ///
     /// Derived from the network parameters.
     unused_timing: sync::Mutex<UnusedTimings>,
+
+    /// The limit on how many circuits we build at once.
+    ///
+    /// See [`CircuitTiming::max_concurrent_builds`].
+    build_limiter: limit::BuildLimiter,
 }
 
 /// An action to take in order to satisfy a request for a circuit.
@@ -758,6 +764,7 @@ impl<B: AbstractCircBuilder<R> + 'static, R: Runtime> AbstractCircMgr<B, R> {
             circs,
             circuit_timing: circuit_timing.into(),
             unused_timing: sync::Mutex::new(unused_timing),
+            build_limiter: limit::BuildLimiter::default(),
         }
     }
 
@@ -1308,7 +1315,7 @@ impl<B: AbstractCircBuilder<R> + 'static, R: Runtime> AbstractCircMgr<B, R> {
         plan: <B as AbstractCircBuilder<R>>::Plan,
         pending: Arc<PendingEntry<B, R>>,
     ) -> (Option<SupportedCircUsage>, PendResult<B, R>) {
-        let outcome = self.builder.build_circuit(plan).await;
+        let outcome = self.build_limited(plan).await;
 
         match outcome {
             Err(e) => (None, Err(e)),
@@ -1370,7 +1377,21 @@ impl<B: AbstractCircBuilder<R> + 'static, R: Runtime> AbstractCircMgr<B, R> {
         dir: DirInfo<'_>,
     ) -> Result<(SupportedCircUsage, Arc<B::Circ>)> {
         let (_, plan) = self.plan_by_usage(dir, usage)?;
-        self.builder.build_circuit(plan.plan).await
+        self.build_limited(plan.plan).await
+    }
+
+    /// Build a circuit according to `plan`,
+    /// once we are building few enough other circuits.
+    ///
+    /// If we are already building [`max_concurrent_builds`](CircuitTiming::max_concurrent_builds)
+    /// circuits, we wait in line until one of them has finished.
+    async fn build_limited(
+        &self,
+        plan: <B as AbstractCircBuilder<R>>::Plan,
+    ) -> Result<(SupportedCircUsage, Arc<B::Circ>)> {
+        let max = self.circuit_timing().max_concurrent_builds;
+        let _permit = self.build_limiter.acquire(max).await;
+        self.builder.build_circuit(plan).await
     }
 
     /// Remove the circuit with a given `id` from this manager.
@@ -1429,6 +1450,40 @@ impl<B: AbstractCircBuilder<R> + 'static, R: Runtime> AbstractCircMgr<B, R> {
         list.open_circs.len()
     }
 
+    /// Return the number of open circuits held by this circuit manager,
+    /// grouped by usage category, and sorted by category.
+    pub(crate) fn pool_status(&self) -> Vec<(UsageCategory, usize)> {
+        let list = self.circs.lock().expect("poisoned lock");
+        let mut counts = BTreeMap::new();
+        for ent in list.open_circs.values() {
+            *counts.entry(ent.spec.category()).or_insert(0) += 1;
+        }
+        counts.into_iter().collect()
+    }
+
+    /// Return the number of idle, in-use, and pending circuits tracked by this circuit manager,
+    /// grouped by usage category.
+    pub(crate) fn pool_stats(&self) -> BTreeMap<UsageCategory, CircPoolStats> {
+        let list = self.circs.lock().expect("poisoned lock");
+        let mut stats: BTreeMap<_, CircPoolStats> = BTreeMap::new();
+        for ent in list.open_circs.values() {
+            let counts = stats.entry(ent.spec.category()).or_default();
+            match ent.expiration {
+                ExpirationInfo::Unused { .. } => counts.idle += 1,
+                ExpirationInfo::Dirty { .. } => counts.in_use += 1,
+            }
+        }
+        for pending in list.pending_circs.iter() {
+            let category = pending
+                .tentative_assignment
+                .lock()
+                .expect("poisoned lock")
+                .category();
+            stats.entry(category).or_default().pending += 1;
+        }
+        stats
+    }
+
     /// Return the number of pending circuits tracked by this circuit manager.
     #[cfg(test)]
     pub(crate) fn n_pending_circs(&self) -> usize {
@@ -1674,6 +1729,81 @@ mod test {
         });
     }
 
+    #[test]
+    fn pool_status() {
+        MockRuntime::test_with_various(|rt| async move {
+            #[allow(deprecated)] // TODO #1885
+            let rt = MockSleepRuntime::new(rt);
+
+            let builder = make_builder(&rt);
+            let mgr = Arc::new(AbstractCircMgr::new(
+                builder,
+                rt.clone(),
+                CircuitTiming::default(),
+            ));
+
+            assert!(mgr.pool_status().is_empty());
+
+            let webports = TargetCircUsage::new_from_ipv4_ports(&[80, 443]);
+            let dnsport = TargetCircUsage::new_from_ipv4_ports(&[53]);
+            let c1 = rt.wait_for(mgr.get_or_launch(&webports, di())).await;
+            let c1 = c1.unwrap().0;
+            assert_eq!(mgr.pool_status(), vec![(UsageCategory::Exit, 1)]);
+
+            let c2 = rt.wait_for(mgr.get_or_launch(&dnsport, di())).await;
+            let c2 = c2.unwrap().0;
+            assert_eq!(mgr.pool_status(), vec![(UsageCategory::Exit, 2)]);
+
+            // Circuits that are taken from the pool are no longer counted.
+            assert!(mgr.take_circ(&c1.id()).is_some());
+            assert_eq!(mgr.pool_status(), vec![(UsageCategory::Exit, 1)]);
+            assert!(mgr.take_circ(&c2.id()).is_some());
+            assert!(mgr.pool_status().is_empty());
+        });
+    }
+
+    #[test]
+    fn pool_stats() {
+        MockRuntime::test_with_various(|rt| async move {
+            #[allow(deprecated)] // TODO #1885
+            let rt = MockSleepRuntime::new(rt);
+
+            let builder = make_builder(&rt);
+            let mgr = Arc::new(AbstractCircMgr::new(
+                builder,
+                rt.clone(),
+                CircuitTiming::default(),
+            ));
+            let exit_stats = || {
+                let stats = mgr.pool_stats();
+                assert!(stats.keys().all(|c| *c == UsageCategory::Exit));
+                stats
+                    .get(&UsageCategory::Exit)
+                    .map(|s| (s.idle, s.in_use, s.pending))
+                    .unwrap_or_default()
+            };
+
+            assert!(mgr.pool_stats().is_empty());
+
+            // A circuit that was requested is in use.
+            let webports = TargetCircUsage::new_from_ipv4_ports(&[80, 443]);
+            let c1 = rt.wait_for(mgr.get_or_launch(&webports, di())).await;
+            let c1 = c1.unwrap().0;
+            assert_eq!(exit_stats(), (0, 1, 0));
+
+            // A circuit that was launched without a request is pending, and then idle.
+            let dnsport = TargetCircUsage::new_from_ipv4_ports(&[53]);
+            let pending = mgr.launch_by_usage(&dnsport, di()).unwrap();
+            assert_eq!(exit_stats(), (0, 1, 1));
+            rt.wait_for(pending).await.unwrap().unwrap();
+            assert_eq!(exit_stats(), (1, 1, 0));
+
+            // Circuits that are taken from the pool are no longer counted.
+            assert!(mgr.take_circ(&c1.id()).is_some());
+            assert_eq!(exit_stats(), (1, 0, 0));
+        });
+    }
+
     #[test]
     fn request_timeout() {
         MockRuntime::test_with_various(|rt| async move {
@@ -2070,6 +2200,46 @@ mod test {
         });
     }
 
+    #[test]
+    fn build_ceiling() {
+        MockRuntime::test_with_various(|rt| async move {
+            use crate::config::CircuitTimingBuilder;
+            #[allow(deprecated)] // TODO #1885
+            let rt = MockSleepRuntime::new(rt);
+            let builder = make_builder(&rt);
+
+            let circuit_timing = CircuitTimingBuilder::default()
+                .max_concurrent_builds(2)
+                .build()
+                .unwrap();
+
+            let mgr = Arc::new(AbstractCircMgr::new(builder, rt.clone(), circuit_timing));
+
+            // Ask for circuits to five different ports at once.
+            // None of these circuits can be shared between the requests.
+            let usages: Vec<_> = [21, 22, 23, 25, 53]
+                .into_iter()
+                .map(|port| TargetCircUsage::new_from_ipv4_ports(&[port]))
+                .collect();
+            let circs = rt
+                .wait_for(futures::future::join_all(
+                    usages.iter().map(|usage| mgr.get_or_launch(usage, di())),
+                ))
+                .await;
+
+            // Every request got its circuit...
+            assert!(circs.iter().all(Result::is_ok));
+            assert_eq!(mgr.n_circs(), 5);
+            // ...but we never built more than two at once.
+            let max_building = mgr
+                .peek_builder()
+                .max_building
+                .load(std::sync::atomic::Ordering::SeqCst);
+            assert_eq!(max_building, 2);
+            assert_eq!(mgr.build_limiter.in_flight(), 0);
+        });
+    }
+
     /// Returns three exit policies; one that permits nothing, one that permits ports 80
     /// and 443 only, and one that permits all ports.
     fn get_exit_policies() -> (ExitPolicy, ExitPolicy, ExitPolicy) {
@@ -0,0 +1,84 @@
+//! A limit on the total number of circuits that we build at once.
+
+use oneshot_fused_workaround as oneshot;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// A limit on the number of circuits that an
+/// [`AbstractCircMgr`](super::AbstractCircMgr) builds at once, for all usages combined.
+///
+/// Every circuit build must hold a [`BuildPermit`] while it is running.
+/// When no permit is available, callers of [`acquire`](BuildLimiter::acquire)
+/// wait in line until one is released.
+#[derive(Debug, Default)]
+pub(super) struct BuildLimiter {
+    /// The state of this limiter.
+    inner: Mutex<Inner>,
+}
+
+/// The mutable state of a [`BuildLimiter`].
+#[derive(Debug, Default)]
+struct Inner {
+    /// The number of permits currently held.
+    in_flight: usize,
+    /// Senders to wake the tasks that are waiting for a permit, oldest first.
+    waiting: VecDeque<oneshot::Sender<()>>,
+}
+
+/// Permission to build one circuit, from a [`BuildLimiter`].
+///
+/// The permit is released when this is dropped.
+#[derive(Debug)]
+pub(super) struct BuildPermit<'a> {
+    /// The limiter that gave out this permit.
+    limiter: &'a BuildLimiter,
+}
+
+impl BuildLimiter {
+    /// Wait until fewer than `max` permits are held, and then take one.
+    ///
+    /// A `max` of 0 is treated as 1.
+    ///
+    /// `max` is only checked against the number of permits held when we try to take one:
+    /// if it is lowered, any permits already held above the new limit remain valid.
+    pub(super) async fn acquire(&self, max: usize) -> BuildPermit<'_> {
+        let max = std::cmp::max(1, max);
+        loop {
+            let wakeup = {
+                let mut inner = self.inner.lock().expect("poisoned lock");
+                if inner.in_flight < max {
+                    inner.in_flight += 1;
+                    return BuildPermit { limiter: self };
+                }
+                let (tx, rx) = oneshot::channel();
+                inner.waiting.push_back(tx);
+                rx
+            };
+            // We are woken when a permit is released; then we check again,
+            // since some other task may have taken it first.
+            // (An error here means the limiter was dropped, which can't happen while we
+            // borrow it, so we just check again.)
+            let _ = wakeup.await;
+        }
+    }
+
+    /// Return the number of permits currently held.
+    #[cfg(test)]
+    pub(super) fn in_flight(&self) -> usize {
+        self.inner.lock().expect("poisoned lock").in_flight
+    }
+}
+
+impl Drop for BuildPermit<'_> {
+    fn drop(&mut self) {
+        let mut inner = self.limiter.inner.lock().expect("poisoned lock");
+        inner.in_flight -= 1;
+        // Wake the oldest task that is still waiting.
+        // (Sending fails if the waiting task has given up.)
+        while let Some(waiter) = inner.waiting.pop_front() {
+            if waiter.send(()).is_ok() {
+                break;
+            }
+        }
+    }
+}
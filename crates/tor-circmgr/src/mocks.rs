@@ -86,6 +86,10 @@ pub(crate) struct FakeBuilder<RT: Runtime> {
     #[cfg(feature = "vanguards")]
     vanguardmgr: Arc<VanguardMgr<RT>>,
     pub(crate) script: sync::Mutex<Vec<(TargetCircUsage, FakeOp)>>,
+    /// The number of circuits currently being built.
+    building: AtomicUsize,
+    /// The largest number of circuits that we have been building at once.
+    pub(crate) max_building: AtomicUsize,
 }
 
 #[derive(Debug, Clone)]
@@ -144,7 +148,10 @@ impl<RT: Runtime> AbstractCircBuilder<RT> for FakeBuilder<RT> {
                 all_relays_stable: *require_stability,
             },
             #[cfg(feature = "hs-common")]
-            TargetCircUsage::HsCircBase { .. } => SupportedCircUsage::HsOnly,
+            TargetCircUsage::HsCircBase { .. } => SupportedCircUsage::HsOnly {
+                #[cfg(feature = "vanguards")]
+                vanguard_mode: tor_guardmgr::VanguardMode::Disabled,
+            },
             _ => unimplemented!(),
         };
         let plan = FakePlan {
@@ -156,10 +163,13 @@ impl<RT: Runtime> AbstractCircBuilder<RT> for FakeBuilder<RT> {
 
     async fn build_circuit(&self, plan: FakePlan) -> Result<(SupportedCircUsage, Arc<FakeCirc>)> {
         let op = plan.op;
+        let building = self.building.fetch_add(1, atomic::Ordering::SeqCst) + 1;
+        self.max_building
+            .fetch_max(building, atomic::Ordering::SeqCst);
         let sl = self.runtime.sleep(FAKE_CIRC_DELAY);
         self.runtime.allow_one_advance(FAKE_CIRC_DELAY);
         sl.await;
-        match op {
+        let outcome = match op {
             FakeOp::Succeed => Ok((plan.spec, Arc::new(FakeCirc { id: FakeId::next() }))),
             FakeOp::WrongSpec(s) => Ok((s, Arc::new(FakeCirc { id: FakeId::next() }))),
             FakeOp::Fail => Err(Error::CircTimeout(None)),
@@ -177,7 +187,9 @@ impl<RT: Runtime> AbstractCircBuilder<RT> for FakeBuilder<RT> {
                 unreachable!()
             }
             FakeOp::NoPlan => unreachable!(),
-        }
+        };
+        self.building.fetch_sub(1, atomic::Ordering::SeqCst);
+        outcome
     }
 
     fn learning_timeouts(&self) -> bool {
@@ -238,6 +250,8 @@ impl<RT: Runtime> FakeBuilder<RT> {
                     .expect("Create VanguardMgr"),
             ),
             script: sync::Mutex::new(vec![]),
+            building: AtomicUsize::new(0),
+            max_building: AtomicUsize::new(0),
         }
     }
 
@@ -229,6 +229,7 @@ impl<'a> TorPath<'a> {
 
 /// A path composed entirely of owned components.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "experimental-api", visibility::make(pub))]
 pub(crate) enum OwnedPath {
     /// A path where we only know how to make circuits via CREATE_FAST.
     ChannelOnly(OwnedChanTarget),
@@ -140,6 +140,8 @@ impl HsPathBuilder {
     ///
     /// If vanguards are disabled, this has the same behavior as
     /// [pick_path](HsPathBuilder::pick_path).
+    ///
+    /// Also returns the [`VanguardMode`] that was used to pick the path.
     #[cfg(feature = "vanguards")]
     #[cfg_attr(not(feature = "vanguards"), allow(unused))]
     pub(crate) fn pick_path_with_vanguards<'a, R: Rng, RT: Runtime>(
@@ -150,10 +152,11 @@ impl HsPathBuilder {
         vanguards: &VanguardMgr<RT>,
         config: &PathConfig,
         now: SystemTime,
-    ) -> Result<(TorPath<'a>, GuardMonitor, GuardUsable)> {
+    ) -> Result<(TorPath<'a>, GuardMonitor, GuardUsable, VanguardMode)> {
         let mode = vanguards.mode();
         if mode == VanguardMode::Disabled {
-            return pick_path(self, rng, netdir, guards, config, now);
+            let (path, mon, usable) = pick_path(self, rng, netdir, guards, config, now)?;
+            return Ok((path, mon, usable, mode));
         }
 
         let vanguard_path_builder = VanguardHsPathBuilder {
@@ -161,7 +164,9 @@ impl HsPathBuilder {
             compatible_with: self.compatible_with.clone(),
         };
 
-        vanguard_path_builder.pick_path(rng, netdir, guards, vanguards)
+        let (path, mon, usable) =
+            vanguard_path_builder.pick_path(rng, netdir, guards, vanguards)?;
+        Ok((path, mon, usable, mode))
     }
 }
 
@@ -39,7 +39,7 @@ pub(crate) type CountryCode = Void;
 use tor_linkspec::OwnedChanTarget;
 
 #[cfg(all(feature = "vanguards", feature = "hs-common"))]
-use tor_guardmgr::vanguards::VanguardMgr;
+use tor_guardmgr::{vanguards::VanguardMgr, VanguardMode};
 
 use crate::isolation::{IsolationHelper, StreamIsolation};
 use crate::mgr::{AbstractCirc, OpenEntry, RestrictionFailed};
@@ -49,6 +49,7 @@ pub use tor_relay_selection::TargetPort;
 
 /// An exit policy, as supported by the last hop of a circuit.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "experimental-api", visibility::make(pub))]
 pub(crate) struct ExitPolicy {
     /// Permitted IPv4 ports.
     v4: Arc<PortPolicy>,
@@ -129,9 +130,10 @@ impl ExitPolicy {
 
 /// The purpose for which a circuit is being created.
 ///
-/// This type should stay internal to the circmgr crate for now: we'll probably
-/// want to refactor it a lot.
+/// This type is only public with the `experimental-api` feature:
+/// we'll probably want to refactor it a lot.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "experimental-api", visibility::make(pub))]
 pub(crate) enum TargetCircUsage {
     /// Use for BEGINDIR-based non-anonymous directory connections
     Dir,
@@ -194,9 +196,10 @@ pub(crate) enum TargetCircUsage {
 
 /// The purposes for which a circuit is usable.
 ///
-/// This type should stay internal to the circmgr crate for now: we'll probably
-/// want to refactor it a lot.
+/// This type is only public with the `experimental-api` feature:
+/// we'll probably want to refactor it a lot.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "experimental-api", visibility::make(pub))]
 pub(crate) enum SupportedCircUsage {
     /// Usable for BEGINDIR-based non-anonymous directory connections
     Dir,
@@ -220,13 +223,69 @@ pub(crate) enum SupportedCircUsage {
     /// (It should never be given to the circuit manager; the
     /// `HsPool` code will handle it instead.)
     #[cfg(feature = "hs-common")]
-    HsOnly,
+    HsOnly {
+        /// The kind of vanguards that were used when picking this circuit's path.
+        ///
+        /// This is [`VanguardMode::Disabled`] if no vanguards were used.
+        #[cfg(feature = "vanguards")]
+        vanguard_mode: VanguardMode,
+    },
     /// Use only for BEGINDIR-based non-anonymous directory connections
     /// to a particular target (which may not be in the netdir).
     #[cfg(feature = "specific-relay")]
     DirSpecificTarget(OwnedChanTarget),
 }
 
+/// A coarse description of what a circuit can be used for.
+///
+/// Unlike the (internal) description of a circuit's supported usage,
+/// this does not include any port, isolation, or target information:
+/// it is meant for reporting, as in [`CircMgr::pool_status`](crate::CircMgr::pool_status).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub enum UsageCategory {
+    /// Usable for BEGINDIR-based non-anonymous directory connections.
+    Dir,
+    /// Usable to exit to some set of ports.
+    Exit,
+    /// Not suitable for any usage.
+    NoUsage,
+    /// Usable only for onion-service-related operations.
+    #[cfg(feature = "hs-common")]
+    HsOnly,
+    /// Usable only for directory connections to a particular target.
+    #[cfg(feature = "specific-relay")]
+    DirSpecificTarget,
+}
+
+/// The number of circuits of a single [`UsageCategory`] held by a circuit manager,
+/// as returned by [`CircMgr::pool_stats`](crate::CircMgr::pool_stats).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct CircPoolStats {
+    /// The number of open circuits that have never been used.
+    pub idle: usize,
+    /// The number of open circuits that have been used for at least one request.
+    pub in_use: usize,
+    /// The number of circuits that are still being built.
+    pub pending: usize,
+}
+
+impl Display for UsageCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            UsageCategory::Dir => "dir",
+            UsageCategory::Exit => "exit",
+            UsageCategory::NoUsage => "no-usage",
+            #[cfg(feature = "hs-common")]
+            UsageCategory::HsOnly => "hs-only",
+            #[cfg(feature = "specific-relay")]
+            UsageCategory::DirSpecificTarget => "dir-specific-target",
+        };
+        write!(f, "{}", s)
+    }
+}
+
 impl TargetCircUsage {
     /// Construct path for a given circuit purpose; return it and the
     /// usage that it _actually_ supports.
@@ -361,14 +420,15 @@ impl TargetCircUsage {
                 let path_builder = HsPathBuilder::new(compatible_with_target.clone(), *kind);
                 cfg_if::cfg_if! {
                     if #[cfg(all(feature = "vanguards", feature = "hs-common"))] {
-                        let (path, mon, usable) = path_builder
+                        let (path, mon, usable, vanguard_mode) = path_builder
                             .pick_path_with_vanguards::<_, RT>(rng, netdir, guards, vanguards, config, now)?;
+                        let usage = SupportedCircUsage::HsOnly { vanguard_mode };
                     } else {
                         let (path, mon, usable) = path_builder
                             .pick_path::<_, RT>(rng, netdir, guards, config, now)?;
+                        let usage = SupportedCircUsage::HsOnly {};
                     }
                 };
-                let usage = SupportedCircUsage::HsOnly;
                 Ok((path, usage, Some(mon), Some(usable)))
             }
         }
@@ -398,6 +458,19 @@ fn owned_targets_equivalent(a: &OwnedChanTarget, b: &OwnedChanTarget) -> bool {
 }
 
 impl SupportedCircUsage {
+    /// Return the [`UsageCategory`] of this spec.
+    pub(crate) fn category(&self) -> UsageCategory {
+        match self {
+            SupportedCircUsage::Dir => UsageCategory::Dir,
+            SupportedCircUsage::Exit { .. } => UsageCategory::Exit,
+            SupportedCircUsage::NoUsage => UsageCategory::NoUsage,
+            #[cfg(feature = "hs-common")]
+            SupportedCircUsage::HsOnly { .. } => UsageCategory::HsOnly,
+            #[cfg(feature = "specific-relay")]
+            SupportedCircUsage::DirSpecificTarget(_) => UsageCategory::DirSpecificTarget,
+        }
+    }
+
     /// Return true if this spec permits the usage described by `other`.
     ///
     /// If this function returns `true`, then it is okay to use a circuit
@@ -564,7 +637,24 @@ impl SupportedCircUsage {
             SCU::Exit { .. } => CU::UserTraffic,
             SCU::NoUsage => CU::UselessCircuit,
             #[cfg(feature = "hs-common")]
-            SCU::HsOnly => CU::UserTraffic,
+            SCU::HsOnly { .. } => CU::UserTraffic,
+        }
+    }
+
+    /// Return the kind of vanguards used by this circuit, if it used any.
+    ///
+    /// Only onion service circuits can use vanguards:
+    /// [`VanguardMode::Lite`] means the circuit used an L2 vanguard,
+    /// and [`VanguardMode::Full`] means it used L2 and L3 vanguards.
+    #[cfg(all(feature = "vanguards", feature = "hs-common"))]
+    pub(crate) fn vanguard_mode(&self) -> Option<VanguardMode> {
+        match self {
+            SupportedCircUsage::HsOnly { vanguard_mode }
+                if *vanguard_mode != VanguardMode::Disabled =>
+            {
+                Some(*vanguard_mode)
+            }
+            _ => None,
         }
     }
 }
@@ -1013,6 +1103,45 @@ pub(crate) mod test {
         });
     }
 
+    #[test]
+    #[cfg(all(feature = "vanguards", feature = "hs-common"))]
+    fn buildpath_vanguard_mode() {
+        use tor_rtmock::MockRuntime;
+
+        MockRuntime::test_with_various(|rt| async move {
+            let mut rng = testing_rng();
+            let netdir = testnet::construct_netdir().unwrap_if_sufficient().unwrap();
+            let di = (&netdir).into();
+            let config = crate::PathConfig::default();
+            let guards = tor_guardmgr::GuardMgr::new(
+                rt.clone(),
+                TestingStateMgr::new(),
+                &TestConfig::default(),
+            )
+            .unwrap();
+            guards.install_test_netdir(&netdir);
+            let vanguards = VanguardMgr::new_testing(&rt, VanguardMode::Lite).unwrap();
+            let _provider = vanguards.init_vanguard_sets(&netdir).await.unwrap();
+            let now = SystemTime::now();
+
+            // A directory circuit never uses vanguards.
+            let (_, u_dir, _, _) = TargetCircUsage::Dir
+                .build_path(&mut rng, di, &guards, &vanguards, &config, now)
+                .unwrap();
+            assert_eq!(u_dir.vanguard_mode(), None);
+
+            // An onion service circuit uses the configured vanguards.
+            let hs_usage = TargetCircUsage::HsCircBase {
+                compatible_with_target: None,
+                kind: HsCircStemKind::Naive,
+            };
+            let (_, u_hs, _, _) = hs_usage
+                .build_path(&mut rng, di, &guards, &vanguards, &config, now)
+                .unwrap();
+            assert_eq!(u_hs.vanguard_mode(), Some(VanguardMode::Lite));
+        });
+    }
+
     #[test]
     fn build_testing_noexit() {
         // Here we'll try to build paths for testing circuits on a network
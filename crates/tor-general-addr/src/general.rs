@@ -11,7 +11,7 @@
 //! Generally, it's better to use these types unless you know
 //! that the minor performance overhead here will matter in practice.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use crate::unix;
@@ -43,8 +43,30 @@ use std::os::linux::net::SocketAddrExt as _;
 ///    Examples: `unix:/path/to/socket`, `inet:127.0.0.1:9999`,
 ///    `inet:[::1]:9999`.
 ///
+///    As a convenience, after the `inet:` schema (only),
+///    we also accept an IPv6 address and port _without_ the square brackets,
+///    as in `inet:1:2:3:4:5:6:7:8:9999`,
+///    provided that the result is unambiguous.
+///    Input like `inet:::1:9999` is rejected,
+///    since `::1:9999` is itself a valid IPv6 address:
+///    write `inet:[::1]:9999` instead.
+///    We never produce this bracketless form.
+///
 ///    The "unnamed" AF_UNIX address is represented as `unix:`.
 ///
+///    When parsing, we also accept a URL-like form,
+///    in which the schema is followed by `://` rather than a single colon,
+///    as in `unix:///path/to/socket` or `inet://127.0.0.1:9999`.
+///    In this form, `tcp` is accepted as a synonym for `inet`,
+///    and the path of a `unix` address must be absolute:
+///    `unix://relative/path` is rejected.
+///    We only produce this form for pathnames that themselves begin with `//`:
+///    the pathname `//tmp/sock` is represented as `unix:////tmp/sock`,
+///    since `unix://tmp/sock` would be read as a relative URL-like path.
+///
+///    Note that `unix:///path` therefore names `/path`, not `///path`.
+///    (POSIX treats those as the same file.)
+///
 /// 2. A _unqualified_ representation,
 ///    consisting of a `net::SocketAddr` address represented as a string.
 ///
@@ -97,6 +119,19 @@ use std::os::linux::net::SocketAddrExt as _;
 ///
 /// TODO: We should try to get Rust's stdlib specify these formats, so we don't have to.
 /// There is an open PR at <https://github.com/rust-lang/rust/pull/131790>.
+///
+/// ## Conversions
+///
+/// A `SocketAddr` can be built with `From` from a [`std::net::SocketAddr`],
+/// or (on Unix) from a [`std::os::unix::net::SocketAddr`]:
+/// [`unix::SocketAddr`] is the same type as the latter there,
+/// so code using the standard library doesn't need to name it.
+///
+/// Going the other way, `TryFrom<SocketAddr>` is implemented for both address types.
+/// The conversion fails with a [`derive_more::TryIntoError`]
+/// if the address is of the other kind:
+/// for example, when converting an `inet:` address into a Unix address.
+/// The original `SocketAddr` can be recovered from the error's `input` field.
 #[derive(Clone, Debug, derive_more::From, derive_more::TryInto)]
 #[non_exhaustive]
 pub enum SocketAddr {
@@ -131,9 +166,14 @@ impl SocketAddr {
                 if sa.is_unnamed() {
                     Some("unix:".to_string())
                 } else {
-                    sa.as_pathname()
-                        .and_then(Path::to_str)
-                        .map(|p| format!("unix:{}", p))
+                    sa.as_pathname().and_then(Path::to_str).map(|p| {
+                        if p.starts_with("//") {
+                            // Otherwise, this would be parsed as a URL-like address.
+                            format!("unix://{}", p)
+                        } else {
+                            format!("unix:{}", p)
+                        }
+                    })
                 }
             }
         }
@@ -147,6 +187,140 @@ impl SocketAddr {
             SocketAddr::Unix(socket_addr) => socket_addr.as_pathname(),
         }
     }
+
+    /// Return true if this address and `other` belong to the same address family.
+    ///
+    /// Two inet addresses are in the same family only if they are
+    /// both IPv4 or both IPv6 addresses.
+    /// Any two AF_UNIX addresses are in the same family,
+    /// whether they are pathname, abstract, or unnamed addresses.
+    pub fn same_family(&self, other: &SocketAddr) -> bool {
+        use SocketAddr::*;
+        match (self, other) {
+            (Inet(a), Inet(b)) => a.is_ipv4() == b.is_ipv4(),
+            (Unix(_), Unix(_)) => true,
+            (Inet(_), Unix(_)) | (Unix(_), Inet(_)) => false,
+        }
+    }
+
+    /// Return true if a connection to `other` might reach the endpoint at this address.
+    ///
+    /// This is more lenient than `==`,
+    /// and is meant for detecting loops:
+    /// for example, when we are asked to connect to an address
+    /// that is really one of our own listeners.
+    ///
+    /// For inet addresses, the ports must be equal.
+    /// If either address is a wildcard (`0.0.0.0` or `[::]`),
+    /// it matches any address of the same family:
+    /// a listener on `0.0.0.0:9150` accepts connections to `127.0.0.1:9150`.
+    /// We don't check whether the other address actually belongs to this host.
+    /// Otherwise, the IP addresses must be equal.
+    /// We don't treat an IPv6 wildcard as matching IPv4 addresses,
+    /// since whether it does so depends on the platform and the socket options.
+    ///
+    /// For AF_UNIX addresses with a path, we compare the canonical forms of the paths,
+    /// following symbolic links.
+    /// (This accesses the filesystem.
+    /// If a path can't be canonicalized, for example because its directory doesn't exist,
+    /// we use it as it is.)
+    /// Abstract addresses match if their names are equal.
+    /// An unnamed address never matches anything.
+    ///
+    /// Addresses of different types never match.
+    pub fn refers_to_same_endpoint(&self, other: &SocketAddr) -> bool {
+        use SocketAddr::*;
+        match (self, other) {
+            (Inet(a), Inet(b)) => {
+                a.port() == b.port()
+                    && a.is_ipv4() == b.is_ipv4()
+                    && (a.ip() == b.ip() || a.ip().is_unspecified() || b.ip().is_unspecified())
+            }
+            (Unix(a), Unix(b)) => {
+                if let (Some(a), Some(b)) = (a.as_pathname(), b.as_pathname()) {
+                    return canonical_socket_path(a) == canonical_socket_path(b);
+                }
+                !a.is_unnamed() && self == other
+            }
+            (Inet(_), Unix(_)) | (Unix(_), Inet(_)) => false,
+        }
+    }
+
+    /// Construct an AF_UNIX address for the socket at `path`.
+    ///
+    /// Returns [`AddrParseError::InvalidAfUnixAddress`] if `path` can't be used
+    /// as the address of an AF_UNIX socket
+    /// (for example, because it is too long, or contains a NUL byte),
+    /// or if this platform doesn't support AF_UNIX sockets.
+    pub fn unix_pathname(path: impl AsRef<Path>) -> Result<SocketAddr, AddrParseError> {
+        Ok(unix::SocketAddr::from_pathname(path)?.into())
+    }
+
+    /// Construct an AF_UNIX address in the abstract namespace, with the given `name`.
+    ///
+    /// The name is used as-is: it should not include the leading NUL byte.
+    ///
+    /// Returns [`AddrParseError::InvalidAfUnixAddress`] if `name` is too long.
+    ///
+    /// Only available on Linux and Android, which are the only platforms
+    /// that support abstract AF_UNIX addresses.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn unix_abstract(name: &[u8]) -> Result<SocketAddr, AddrParseError> {
+        Ok(unix::SocketAddr::from_abstract_name(name)?.into())
+    }
+
+    /// Parse a comma-separated list of addresses.
+    ///
+    /// Whitespace around each entry is ignored,
+    /// and each entry is parsed as with [`FromStr`](std::str::FromStr).
+    /// A string containing only whitespace yields an empty list.
+    ///
+    /// Note that there is no way to escape a comma,
+    /// so this cannot be used for `unix:` addresses whose paths contain one.
+    ///
+    /// On failure, returns [`AddrParseError::InvalidListEntry`],
+    /// which reports the position and text of the first entry that could not be parsed.
+    pub fn parse_list(s: &str) -> Result<Vec<SocketAddr>, AddrParseError> {
+        if s.trim().is_empty() {
+            return Ok(vec![]);
+        }
+        s.split(',')
+            .map(str::trim)
+            .enumerate()
+            .map(|(index, entry)| {
+                entry
+                    .parse()
+                    .map_err(|error| AddrParseError::InvalidListEntry {
+                        index,
+                        entry: entry.to_string(),
+                        error: Box::new(error),
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Return the canonical form of `path`, the path of an AF_UNIX socket, if we can find it.
+///
+/// The socket itself may not exist yet, so if we can't canonicalize `path`,
+/// we try canonicalizing its parent directory instead.
+/// If that fails too, we return `path` unchanged.
+fn canonical_socket_path(path: &Path) -> PathBuf {
+    if let Ok(canonical) = path.canonicalize() {
+        return canonical;
+    }
+    if let (Some(parent), Some(name)) = (path.parent(), path.file_name()) {
+        // An empty parent means that `path` is relative to the current directory.
+        let parent = if parent.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            parent
+        };
+        if let Ok(parent) = parent.canonicalize() {
+            return parent.join(name);
+        }
+    }
+    path.to_owned()
 }
 
 /// Lossy display for a [`SocketAddr`].
@@ -178,13 +352,19 @@ impl std::str::FromStr for SocketAddr {
     type Err = AddrParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(AddrParseError::Empty);
+        }
         if s.starts_with(|c: char| (c.is_ascii_digit() || c == '[')) {
             // This looks like an inet address, and cannot be a qualified address.
             Ok(s.parse::<net::SocketAddr>()?.into())
         } else if let Some((schema, remainder)) = s.split_once(':') {
+            if let Some(remainder) = remainder.strip_prefix("//") {
+                return parse_url_remainder(schema, remainder);
+            }
             match schema {
                 "unix" => Ok(unix::SocketAddr::from_pathname(remainder)?.into()),
-                "inet" => Ok(remainder.parse::<net::SocketAddr>()?.into()),
+                "inet" => Ok(parse_inet_remainder(remainder)?.into()),
                 _ => Err(AddrParseError::UnrecognizedSchema(schema.to_string())),
             }
         } else {
@@ -193,10 +373,52 @@ impl std::str::FromStr for SocketAddr {
     }
 }
 
+/// Parse the part of a URL-like address that follows the `schema://`.
+fn parse_url_remainder(schema: &str, s: &str) -> Result<SocketAddr, AddrParseError> {
+    match schema {
+        "unix" => {
+            if !s.starts_with('/') {
+                return Err(AddrParseError::RelativeUnixUrl(s.to_string()));
+            }
+            Ok(unix::SocketAddr::from_pathname(s)?.into())
+        }
+        "inet" | "tcp" => Ok(parse_inet_remainder(s)?.into()),
+        _ => Err(AddrParseError::UnrecognizedSchema(schema.to_string())),
+    }
+}
+
+/// Parse the part of a qualified `inet:` address that follows the schema.
+///
+/// In addition to the usual `net::SocketAddr` formats,
+/// accept an IPv6 address and port with no square brackets,
+/// so long as the last colon can't also be read as part of the address.
+fn parse_inet_remainder(s: &str) -> Result<net::SocketAddr, AddrParseError> {
+    let err = match s.parse::<net::SocketAddr>() {
+        Ok(addr) => return Ok(addr),
+        Err(e) => e,
+    };
+    if s.starts_with('[') || s.matches(':').count() < 2 {
+        return Err(err.into());
+    }
+    let Some((host, port)) = s.rsplit_once(':') else {
+        return Err(err.into());
+    };
+    match (host.parse::<net::Ipv6Addr>(), port.parse::<u16>()) {
+        (Ok(_), Ok(_)) if s.parse::<net::Ipv6Addr>().is_ok() => {
+            Err(AddrParseError::AmbiguousInetAddress(s.to_string()))
+        }
+        (Ok(ip), Ok(port)) => Ok(net::SocketAddrV6::new(ip, port, 0, 0).into()),
+        _ => Err(err.into()),
+    }
+}
+
 /// An error encountered while attempting to parse a [`SocketAddr`]
 #[derive(Clone, Debug, thiserror::Error)]
 #[non_exhaustive]
 pub enum AddrParseError {
+    /// Tried to parse an empty string.
+    #[error("Address was empty")]
+    Empty,
     /// Tried to parse an address with an unrecognized schema.
     #[error("Address schema {0:?} unrecognized")]
     UnrecognizedSchema(String),
@@ -209,6 +431,24 @@ pub enum AddrParseError {
     /// Tried to parse an address as a inet address, but failed.
     #[error("Invalid internet address")]
     InvalidInetAddress(#[from] std::net::AddrParseError),
+    /// Tried to parse an IPv6 address and port without square brackets,
+    /// but could not tell where the address ended and the port began.
+    #[error("Ambiguous internet address {0:?}: put the IPv6 address in square brackets")]
+    AmbiguousInetAddress(String),
+    /// Tried to parse a URL-like `unix://` address whose path was not absolute.
+    #[error("AF_UNIX address {0:?} has a relative path: use unix:///absolute/path")]
+    RelativeUnixUrl(String),
+    /// Tried to parse a list of addresses, but one of the entries was invalid.
+    #[error("Invalid address {entry:?} at position {index} in list")]
+    InvalidListEntry {
+        /// The (zero-based) position of the invalid entry within the list.
+        index: usize,
+        /// The text of the invalid entry.
+        entry: String,
+        /// The reason the entry could not be parsed.
+        #[source]
+        error: Box<AddrParseError>,
+    },
 }
 
 impl From<IoError> for AddrParseError {
@@ -301,6 +541,41 @@ impl<'a> arbitrary::Arbitrary<'a> for SocketAddr {
     }
 }
 
+/// A [`SocketAddr`] that can be represented as a string, along with that string.
+///
+/// The [`Arbitrary`](arbitrary::Arbitrary) implementation for this type
+/// only generates addresses for which [`SocketAddr::try_to_string`] returns `Some`,
+/// and which are recovered exactly by parsing that string.
+/// (For example, it never generates an IPv6 address with a nonzero flowinfo,
+/// since there is no way to encode one.)
+///
+/// This is meant for fuzzing: a fuzz target can assert that
+/// parsing `string` gives back `addr`.
+#[cfg(feature = "arbitrary")]
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct RepresentableAddr {
+    /// The address.
+    pub addr: SocketAddr,
+    /// The string representation of `addr`, as returned by [`SocketAddr::try_to_string`].
+    pub string: String,
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for RepresentableAddr {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut addr: SocketAddr = u.arbitrary()?;
+        // There is no encoding for flowinfo, so we have to clear it.
+        if let SocketAddr::Inet(net::SocketAddr::V6(v6)) = &mut addr {
+            v6.set_flowinfo(0);
+        }
+        let string = addr
+            .try_to_string()
+            .ok_or(arbitrary::Error::IncorrectFormat)?;
+        Ok(RepresentableAddr { addr, string })
+    }
+}
+
 #[cfg(test)]
 mod test {
     // @@ begin test lint list maintained by maint/add_warning @@
@@ -438,8 +713,107 @@ mod test {
         );
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn std_unix_roundtrip() {
+        let std_addr = unix::SocketAddr::from_pathname("/some/path").unwrap();
+        let ga = general::SocketAddr::from(std_addr);
+        assert_eq!(
+            ga,
+            general::SocketAddr::from_str("unix:/some/path").unwrap()
+        );
+
+        let std_addr: unix::SocketAddr = ga.try_into().unwrap();
+        assert_eq!(
+            std_addr.as_pathname(),
+            Some(std::path::Path::new("/some/path"))
+        );
+
+        // An inet address can't become a unix address; we get it back in the error.
+        let ga = from_inet("127.0.0.1:9999");
+        let err = unix::SocketAddr::try_from(ga.clone()).unwrap_err();
+        assert_eq!(err.input, ga);
+        let _: net::SocketAddr = err.input.try_into().unwrap();
+    }
+
+    #[test]
+    fn same_family() {
+        let v4a = from_inet("127.0.0.1:9999");
+        let v4b = from_inet("192.0.2.7:80");
+        let v6a = from_inet("[::1]:9999");
+        let v6b = from_inet("[2001:db8::7]:80");
+        assert!(v4a.same_family(&v4a));
+        assert!(v4a.same_family(&v4b));
+        assert!(v6a.same_family(&v6b));
+        assert!(!v4a.same_family(&v6a));
+        assert!(!v6b.same_family(&v4b));
+
+        #[cfg(unix)]
+        {
+            let path = from_pathname("/some/path");
+            let unnamed = from_pathname("");
+            assert!(path.same_family(&unnamed));
+            assert!(unnamed.same_family(&path));
+            assert!(!path.same_family(&v4a));
+            assert!(!v6a.same_family(&unnamed));
+
+            #[cfg(any(target_os = "android", target_os = "linux"))]
+            {
+                let abstract_ = general::SocketAddr::unix_abstract(b"hello").unwrap();
+                assert!(abstract_.same_family(&path));
+                assert!(!abstract_.same_family(&v4a));
+            }
+        }
+    }
+
+    #[test]
+    fn bracketless_ipv6() {
+        assert_eq!(
+            from_inet("[1:2:3:4:5:6:7:8]:9150"),
+            general::SocketAddr::from_str("inet:1:2:3:4:5:6:7:8:9150").unwrap()
+        );
+        assert_eq!(
+            from_inet("[fe80::1:2:3:4:5:6]:443"),
+            general::SocketAddr::from_str("inet:fe80::1:2:3:4:5:6:443").unwrap()
+        );
+        // The bracketed form keeps working.
+        assert_eq!(
+            from_inet("[::1]:9150"),
+            general::SocketAddr::from_str("inet:[::1]:9150").unwrap()
+        );
+
+        // "::1:9150" is itself an IPv6 address, so we can't tell what was meant.
+        assert_matches!(
+            "inet:::1:9150".parse::<general::SocketAddr>(),
+            Err(AddrParseError::AmbiguousInetAddress(a)) if a == "::1:9150"
+        );
+        assert_matches!(
+            "inet:fe80::1:9150".parse::<general::SocketAddr>(),
+            Err(AddrParseError::AmbiguousInetAddress(_))
+        );
+        // Not a valid port.
+        assert_matches!(
+            "inet:1:2:3:4:5:6:7:8:99999".parse::<general::SocketAddr>(),
+            Err(AddrParseError::InvalidInetAddress(_))
+        );
+        // No port at all.
+        assert_matches!(
+            "inet:1:2:3:4:5:6:7:8".parse::<general::SocketAddr>(),
+            Err(AddrParseError::InvalidInetAddress(_))
+        );
+        // The unqualified form still requires brackets.
+        assert_matches!(
+            "1:2:3:4:5:6:7:8:9150".parse::<general::SocketAddr>(),
+            Err(AddrParseError::InvalidInetAddress(_))
+        );
+    }
+
     #[test]
     fn parse_err_schemata() {
+        assert_matches!(
+            "".parse::<general::SocketAddr>(),
+            Err(AddrParseError::Empty)
+        );
         assert_matches!(
             "fred".parse::<general::SocketAddr>(),
             Err(AddrParseError::NoSchema)
@@ -454,6 +828,129 @@ mod test {
         );
     }
 
+    #[test]
+    fn parse_url() {
+        assert_eq!(
+            from_inet("127.0.0.1:9150"),
+            general::SocketAddr::from_str("tcp://127.0.0.1:9150").unwrap()
+        );
+        assert_eq!(
+            from_inet("127.0.0.1:9150"),
+            general::SocketAddr::from_str("inet://127.0.0.1:9150").unwrap()
+        );
+        assert_eq!(
+            from_inet("[::1]:9150"),
+            general::SocketAddr::from_str("tcp://[::1]:9150").unwrap()
+        );
+        #[cfg(unix)]
+        assert_eq!(
+            from_pathname("/var/run/arti.sock"),
+            general::SocketAddr::from_str("unix:///var/run/arti.sock").unwrap()
+        );
+
+        #[cfg(unix)]
+        assert_eq!(
+            from_pathname("/abs"),
+            general::SocketAddr::from_str("unix:///abs").unwrap()
+        );
+
+        assert_matches!(
+            "unix://relative".parse::<general::SocketAddr>(),
+            Err(AddrParseError::RelativeUnixUrl(p)) if p == "relative"
+        );
+        assert_matches!(
+            "unix://".parse::<general::SocketAddr>(),
+            Err(AddrParseError::RelativeUnixUrl(p)) if p.is_empty()
+        );
+        assert_matches!(
+            "tcp://fred:9150".parse::<general::SocketAddr>(),
+            Err(AddrParseError::InvalidInetAddress(_))
+        );
+        // "tcp" is only accepted in the URL-like form.
+        assert_matches!(
+            "tcp:127.0.0.1:9150".parse::<general::SocketAddr>(),
+            Err(AddrParseError::UnrecognizedSchema(f)) if f == "tcp"
+        );
+        assert_matches!(
+            "fred://hello".parse::<general::SocketAddr>(),
+            Err(AddrParseError::UnrecognizedSchema(f)) if f == "fred"
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn url_like_pathname_roundtrip() {
+        for (path, expected) in [
+            ("/x", "unix:/x"),
+            ("//x", "unix:////x"),
+            ("///x", "unix://///x"),
+            ("//tmp/sock", "unix:////tmp/sock"),
+        ] {
+            let addr = from_pathname(path);
+            let s = addr.try_to_string().unwrap();
+            assert_eq!(s, expected);
+            assert_eq!(general::SocketAddr::from_str(&s).unwrap(), addr);
+        }
+    }
+
+    #[test]
+    fn parse_list() {
+        assert!(general::SocketAddr::parse_list("").unwrap().is_empty());
+        assert!(general::SocketAddr::parse_list("  ").unwrap().is_empty());
+
+        let addrs = general::SocketAddr::parse_list("127.0.0.1:9050").unwrap();
+        assert_eq!(addrs, vec![from_inet("127.0.0.1:9050")]);
+
+        let addrs =
+            general::SocketAddr::parse_list(" inet:[::1]:9050 ,127.0.0.1:9150,inet:10.0.0.1:80 ")
+                .unwrap();
+        assert_eq!(
+            addrs,
+            vec![
+                from_inet("[::1]:9050"),
+                from_inet("127.0.0.1:9150"),
+                from_inet("10.0.0.1:80"),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn parse_list_mixed() {
+        let addrs =
+            general::SocketAddr::parse_list("unix:/tmp/socket, 127.0.0.1:9050, unix:rel/sock")
+                .unwrap();
+        assert_eq!(
+            addrs,
+            vec![
+                from_pathname("/tmp/socket"),
+                from_inet("127.0.0.1:9050"),
+                from_pathname("rel/sock"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_list_err() {
+        let err = general::SocketAddr::parse_list("127.0.0.1:9050, fred:hello").unwrap_err();
+        assert_matches!(
+            &err,
+            AddrParseError::InvalidListEntry { index: 1, entry, error }
+                if entry == "fred:hello"
+                && matches!(**error, AddrParseError::UnrecognizedSchema(ref f) if f == "fred")
+        );
+        assert_eq!(
+            err.to_string(),
+            r#"Invalid address "fred:hello" at position 1 in list"#
+        );
+
+        assert_matches!(
+            general::SocketAddr::parse_list("127.0.0.1:9050,,127.0.0.1:9150"),
+            Err(AddrParseError::InvalidListEntry { index: 1, entry, error })
+                if entry.is_empty() && matches!(*error, AddrParseError::Empty)
+        );
+    }
+
     #[test]
     #[cfg(unix)]
     fn display_unix_weird() {
@@ -469,6 +966,133 @@ mod test {
         assert_eq!(a2.display_lossy().to_string(), "unix:");
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn unix_pathname() {
+        assert_eq!(
+            general::SocketAddr::unix_pathname("/some/path").unwrap(),
+            from_pathname("/some/path")
+        );
+        assert_matches!(
+            general::SocketAddr::unix_pathname("/some/\0/path"),
+            Err(AddrParseError::InvalidAfUnixAddress(_))
+        );
+        assert_matches!(
+            general::SocketAddr::unix_pathname("x".repeat(1000)),
+            Err(AddrParseError::InvalidAfUnixAddress(_))
+        );
+    }
+
+    #[test]
+    fn same_endpoint_inet() {
+        let listener = from_inet("0.0.0.0:9150");
+        assert!(listener.refers_to_same_endpoint(&from_inet("127.0.0.1:9150")));
+        assert!(from_inet("127.0.0.1:9150").refers_to_same_endpoint(&listener));
+        assert!(listener.refers_to_same_endpoint(&from_inet("192.0.2.7:9150")));
+        assert!(!listener.refers_to_same_endpoint(&from_inet("127.0.0.1:9151")));
+        assert!(!listener.refers_to_same_endpoint(&from_inet("[::1]:9150")));
+
+        let listener = from_inet("[::]:9150");
+        assert!(listener.refers_to_same_endpoint(&from_inet("[::1]:9150")));
+        assert!(!listener.refers_to_same_endpoint(&from_inet("127.0.0.1:9150")));
+
+        let listener = from_inet("127.0.0.1:9150");
+        assert!(listener.refers_to_same_endpoint(&from_inet("127.0.0.1:9150")));
+        assert!(!listener.refers_to_same_endpoint(&from_inet("127.0.0.2:9150")));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn same_endpoint_unix() {
+        let dir = std::env::temp_dir();
+        let a = from_pathname(dir.join("arti.sock"));
+        let b = from_pathname(
+            dir.join("..")
+                .join(dir.file_name().unwrap())
+                .join("arti.sock"),
+        );
+        assert_ne!(a, b);
+        assert!(a.refers_to_same_endpoint(&b));
+        assert!(!a.refers_to_same_endpoint(&from_pathname(dir.join("other.sock"))));
+        assert!(!a.refers_to_same_endpoint(&from_inet("127.0.0.1:9150")));
+
+        let unnamed = from_pathname("");
+        assert!(!unnamed.refers_to_same_endpoint(&unnamed));
+    }
+
+    #[test]
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn unix_abstract() {
+        #[cfg(target_os = "android")]
+        use std::os::android::net::SocketAddrExt as _;
+        #[cfg(target_os = "linux")]
+        use std::os::linux::net::SocketAddrExt as _;
+
+        let a = general::SocketAddr::unix_abstract(b"hello\0world").unwrap();
+        assert_eq!(
+            a,
+            unix::SocketAddr::from_abstract_name(b"hello\0world")
+                .unwrap()
+                .into()
+        );
+        assert_ne!(a, general::SocketAddr::unix_abstract(b"hello").unwrap());
+        assert_ne!(a, from_pathname("hello"));
+        assert!(a.as_pathname().is_none());
+
+        assert_matches!(
+            general::SocketAddr::unix_abstract(&[b'x'; 1000]),
+            Err(AddrParseError::InvalidAfUnixAddress(_))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "arbitrary")]
+    fn representable_roundtrip() {
+        use arbitrary::{Arbitrary as _, Unstructured};
+
+        // A simple deterministic source of bytes for `Unstructured`.
+        let mut state: u64 = 0x5eed_0fad_d7e5;
+        let mut next_byte = move || {
+            // xorshift64
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state >> 24) as u8
+        };
+
+        let (mut n_inet, mut n_unix) = (0, 0);
+        for _ in 0..2000 {
+            let len = usize::from(next_byte()) % 64;
+            let bytes: Vec<u8> = (0..len).map(|_| next_byte()).collect();
+            let Ok(general::RepresentableAddr { addr, string }) =
+                general::RepresentableAddr::arbitrary(&mut Unstructured::new(&bytes))
+            else {
+                continue;
+            };
+
+            assert_eq!(addr.try_to_string().as_ref(), Some(&string));
+            assert_eq!(general::SocketAddr::from_str(&string).unwrap(), addr);
+            match addr {
+                general::SocketAddr::Inet(_) => n_inet += 1,
+                general::SocketAddr::Unix(_) => n_unix += 1,
+            }
+        }
+
+        assert!(n_inet > 0);
+        if cfg!(unix) {
+            assert!(n_unix > 0);
+        }
+    }
+
+    #[test]
+    #[cfg(not(unix))]
+    fn unix_pathname_no_unix() {
+        assert_matches!(
+            general::SocketAddr::unix_pathname("/any/path"),
+            Err(AddrParseError::InvalidAfUnixAddress(_))
+        );
+    }
+
     #[test]
     #[cfg(not(unix))]
     fn parse_err_no_unix() {
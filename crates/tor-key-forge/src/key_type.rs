@@ -275,6 +275,31 @@ impl KeyType {
             _ => Err(internal!("invalid keypair data").into()),
         }
     }
+
+    /// Whether keys of this type include private key material.
+    pub fn is_private(&self) -> bool {
+        match self {
+            KeyType::Ed25519Keypair
+            | KeyType::X25519StaticKeypair
+            | KeyType::Ed25519ExpandedKeypair => true,
+            KeyType::Ed25519PublicKey | KeyType::X25519PublicKey => false,
+        }
+    }
+}
+
+impl KeystoreItemType {
+    /// Whether items of this type include private key material.
+    ///
+    /// Certificates are never private.
+    /// Items of an unrecognized type are assumed to be private,
+    /// since we cannot tell what they contain.
+    pub fn is_private(&self) -> bool {
+        match self {
+            KeystoreItemType::Key(key_type) => key_type.is_private(),
+            KeystoreItemType::Cert(_) => false,
+            KeystoreItemType::Unknown { .. } => true,
+        }
+    }
 }
 
 declare_item_type! {
@@ -5,7 +5,7 @@ use std::str::FromStr;
 use derive_deftly::{define_derive_deftly, Deftly};
 use derive_more::{Deref, Display, Into};
 use serde::{Deserialize, Serialize};
-use tor_persist::slug::{self, BadSlug};
+use tor_persist::slug::{self, BadSlug, Slug};
 
 use crate::{ArtiPathRange, ArtiPathSyntaxError, KeySpecifierComponent};
 
@@ -72,6 +72,16 @@ define_derive_deftly! {
 /// implementation.
 /// The denotators **must** come after all the other fields.
 /// Denotator strings are validated in the same way as [`Slug`](tor-persist::slug::Slug)s.
+/// In particular, a denotator can't contain a [`DENOTATOR_SEP`]:
+/// there is no escaping mechanism, and every [`DENOTATOR_SEP`]
+/// in the last component starts a new denotator.
+///
+/// (We don't escape separators within denotators,
+/// since every denotator is the [`Slug`](tor-persist::slug::Slug)
+/// of a [`KeySpecifierComponent`], and a `Slug` can never contain a [`DENOTATOR_SEP`].
+/// A component whose value is arbitrary data,
+/// which might contain characters that aren't allowed in a `Slug`,
+/// must encode it into a `Slug` itself, for example as lowercase hex.)
 ///
 /// For example, the last component of the path `"foo/bar/bax+denotator_example+1"`
 /// is `"bax+denotator_example+1"`.
@@ -153,6 +163,98 @@ impl ArtiPath {
         self.0.get(range.0.clone())
     }
 
+    /// Return the part of this `ArtiPath` that precedes its denotators.
+    ///
+    /// This is the substring up to (but not including) the first [`DENOTATOR_SEP`],
+    /// or the whole path if it has no denotators.
+    ///
+    /// ### Example
+    /// ```
+    /// # use tor_keymgr::{ArtiPath, ArtiPathSyntaxError};
+    /// # fn demo() -> Result<(), ArtiPathSyntaxError> {
+    /// let path = ArtiPath::new("hss/allium-cepa/ks_hs_blind_id+2_1".into())?;
+    /// assert_eq!(path.without_denotator(), "hss/allium-cepa/ks_hs_blind_id");
+    ///
+    /// let path = ArtiPath::new("hss/allium-cepa/ks_hs_id".into())?;
+    /// assert_eq!(path.without_denotator(), "hss/allium-cepa/ks_hs_id");
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # demo().unwrap();
+    /// ```
+    pub fn without_denotator(&self) -> &str {
+        self.0
+            .split_once(DENOTATOR_SEP)
+            .map_or(self.0.as_str(), |(main_part, _)| main_part)
+    }
+
+    /// Return true if this `ArtiPath` and `other` are the same, ignoring their denotators.
+    ///
+    /// This can be used to group the different instances of a key
+    /// (for example, the keys of an onion service for each time period)
+    /// by the role they play.
+    ///
+    /// See [`ArtiPath::without_denotator`].
+    pub fn same_role(&self, other: &ArtiPath) -> bool {
+        self.without_denotator() == other.without_denotator()
+    }
+
+    /// Create an `ArtiPath` from its path components, and an optional denotator.
+    ///
+    /// The `components` are joined with `/`.
+    /// If a `denotator` is specified, it is appended to the last component,
+    /// following a [`DENOTATOR_SEP`] character.
+    ///
+    /// Since each component is already known to be a valid [`Slug`],
+    /// only the `denotator` is validated.
+    ///
+    /// Returns an error if there are no `components`,
+    /// or if the `denotator` is not a valid [`Slug`]
+    /// (in particular, it can't contain a [`DENOTATOR_SEP`]:
+    /// the path could then not be parsed back into the same denotator).
+    ///
+    /// ### Example
+    /// ```
+    /// # use tor_keymgr::{ArtiPath, ArtiPathSyntaxError};
+    /// # use tor_persist::slug::Slug;
+    /// # fn demo() -> Result<(), ArtiPathSyntaxError> {
+    /// let components = ["hss", "allium-cepa", "ks_hs_blind_id"]
+    ///     .into_iter()
+    ///     .map(|c| Slug::new(c.into()))
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    ///
+    /// let path = ArtiPath::from_components(components, Some("2_1"))?;
+    /// assert_eq!(path.as_str(), "hss/allium-cepa/ks_hs_blind_id+2_1");
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # demo().unwrap();
+    /// ```
+    pub fn from_components(
+        components: impl IntoIterator<Item = Slug>,
+        denotator: Option<&str>,
+    ) -> Result<ArtiPath, ArtiPathSyntaxError> {
+        let mut path = String::new();
+        for component in components {
+            if !path.is_empty() {
+                path.push(PATH_SEP);
+            }
+            path.push_str(component.as_str());
+        }
+
+        if path.is_empty() {
+            return Err(BadSlug::EmptySlugNotAllowed.into());
+        }
+
+        if let Some(denotator) = denotator {
+            let () = slug::check_syntax(denotator)?;
+            path.push(DENOTATOR_SEP);
+            path.push_str(denotator);
+        }
+
+        Ok(ArtiPath(path))
+    }
+
     /// Create an `ArtiPath` from an `ArtiPath` and a list of denotators.
     ///
     /// If `cert_denotators` is empty, returns the specified `path` as-is.
@@ -424,6 +526,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn arti_path_with_sep_in_denotator() {
+        // There is no way to escape a separator within a denotator
+        // (see the docs of ArtiPath): a doubled separator is just an empty denotator.
+        const INVALID: &[&str] = &[
+            "foo/bar/qux+a++b",
+            "foo/bar/qux+++a",
+            "foo/bar/qux+a+++b",
+            "foo/bar/qux+a++",
+        ];
+
+        for path in INVALID {
+            assert_err(
+                path,
+                ArtiPathSyntaxError::Slug(BadSlug::EmptySlugNotAllowed),
+            );
+        }
+    }
+
+    #[test]
+    fn from_components() {
+        fn slugs(components: &[&str]) -> Vec<Slug> {
+            components
+                .iter()
+                .map(|c| Slug::new(c.to_string()).unwrap())
+                .collect()
+        }
+
+        assert_eq!(
+            ArtiPath::from_components(vec![], None).unwrap_err(),
+            ArtiPathSyntaxError::Slug(BadSlug::EmptySlugNotAllowed)
+        );
+        assert_eq!(
+            ArtiPath::from_components(vec![], Some("foo")).unwrap_err(),
+            ArtiPathSyntaxError::Slug(BadSlug::EmptySlugNotAllowed)
+        );
+
+        let path = ArtiPath::from_components(slugs(&["foo"]), None).unwrap();
+        assert_eq!(path.as_str(), "foo");
+
+        let path = ArtiPath::from_components(slugs(&["foo", "bar", "baz"]), None).unwrap();
+        assert_eq!(path, ArtiPath::new("foo/bar/baz".into()).unwrap());
+
+        let path = ArtiPath::from_components(slugs(&["foo", "bar"]), Some("d")).unwrap();
+        assert_eq!(path, ArtiPath::new("foo/bar+d".into()).unwrap());
+
+        // The denotator must be a valid slug.
+        // In particular, it can't contain a separator:
+        // "foo+a+b" would be parsed as a path with two denotators.
+        assert_eq!(
+            ArtiPath::from_components(slugs(&["foo"]), Some("a+b")).unwrap_err(),
+            ArtiPathSyntaxError::Slug(BadSlug::BadCharacter('+'))
+        );
+        assert_eq!(
+            ArtiPath::from_components(slugs(&["foo"]), Some("")).unwrap_err(),
+            ArtiPathSyntaxError::Slug(BadSlug::EmptySlugNotAllowed)
+        );
+        assert_eq!(
+            ArtiPath::from_components(slugs(&["foo"]), Some("a/b")).unwrap_err(),
+            ArtiPathSyntaxError::Slug(BadSlug::BadCharacter('/'))
+        );
+    }
+
     #[test]
     fn substring() {
         const KEY_PATH: &str = "hello";
@@ -438,4 +603,27 @@ mod tests {
         assert_eq!(path.substring(&(0..KEY_PATH.len() + 1).into()), None);
         assert_eq!(path.substring(&(0..0).into()).unwrap(), "");
     }
+
+    #[test]
+    fn same_role() {
+        let path = |p: &str| ArtiPath::new(p.into()).unwrap();
+
+        let desc1 = path("hss/allium-cepa/ks_hs_blind_id+2_1");
+        let desc2 = path("hss/allium-cepa/ks_hs_blind_id+3_1");
+        let id = path("hss/allium-cepa/ks_hs_id");
+        let other_svc = path("hss/leek/ks_hs_blind_id+2_1");
+
+        assert_eq!(desc1.without_denotator(), "hss/allium-cepa/ks_hs_blind_id");
+        assert_eq!(id.without_denotator(), id.as_str());
+        assert_eq!(path("foo+a+b+c").without_denotator(), "foo");
+
+        assert!(desc1.same_role(&desc2));
+        assert!(desc1.same_role(&desc1));
+        assert!(id.same_role(&id));
+        assert!(!desc1.same_role(&id));
+        assert!(!desc1.same_role(&other_svc));
+        // A path without denotators doesn't have the same role as one with denotators
+        // whose prefix merely starts with it.
+        assert!(!path("hss/allium-cepa/ks_hs_blind").same_role(&desc1));
+    }
 }
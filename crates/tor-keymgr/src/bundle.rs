@@ -0,0 +1,111 @@
+//! Key bundles, for moving keys between keystores.
+
+use std::path::PathBuf;
+use std::result::Result as StdResult;
+
+use tor_bytes::Writer as _;
+use tor_error::{into_bad_api_usage, ErrorReport as _};
+use tor_key_forge::{EncodableItem, KeystoreItemType};
+
+use crate::keystore::arti::{decode_item, encode_item};
+use crate::{ArtiPath, KeySpecifier, Result};
+
+/// A key bundle: a set of keystore items, encoded so they can be moved to another keystore.
+///
+/// A key bundle is a sequence of entries, each of which consists of
+///   * a 2-byte big-endian length, followed by the UTF-8 name of the entry,
+///     of the form `<ArtiPath>.<extension>`, where `<extension>` is the
+///     [`arti_extension`](KeystoreItemType::arti_extension) of the type of the item
+///     (as in the names of the files of the [`ArtiNativeKeystore`](crate::ArtiNativeKeystore)),
+///     and
+///   * a 4-byte big-endian length, followed by the item,
+///     encoded like in the files of the `ArtiNativeKeystore`:
+///     keys are encoded in OpenSSH format, and certificates in their usual binary form.
+///
+/// Items are added to a bundle with [`push`](KeyBundle::push),
+/// or with [`KeyMgr::export`](crate::KeyMgr::export).
+/// [`KeyMgr::validate_import`](crate::KeyMgr::validate_import)
+/// checks what importing a bundle would do.
+#[derive(Clone, Debug, Default)]
+pub struct KeyBundle {
+    /// The encoded entries of the bundle.
+    bytes: Vec<u8>,
+}
+
+impl KeyBundle {
+    /// Create an empty key bundle.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `item` to this bundle, under the [`ArtiPath`] of `key_spec`.
+    ///
+    /// Returns an error if `key_spec` has no `ArtiPath`, or if `item` can't be encoded.
+    pub fn push(&mut self, key_spec: &dyn KeySpecifier, item: &dyn EncodableItem) -> Result<()> {
+        let path = key_spec.arti_path().map_err(into_bad_api_usage!(
+            "cannot export an item without an ArtiPath"
+        ))?;
+        let item = item.as_keystore_item()?;
+        let name = format!("{path}.{}", item.item_type()?.arti_extension());
+        let item = encode_item(item)?;
+
+        // Encode the entry separately, so that a failure doesn't leave a partial entry behind.
+        let mut entry = vec![];
+        let mut w = entry.write_nested_u16len();
+        w.write_all(name.as_bytes());
+        w.finish()
+            .map_err(into_bad_api_usage!("entry name too long for a key bundle"))?;
+        let mut w = entry.write_nested_u32len();
+        w.write_all(&item);
+        w.finish()
+            .map_err(into_bad_api_usage!("item too large for a key bundle"))?;
+
+        self.bytes.extend(entry);
+        Ok(())
+    }
+
+    /// Return the encoded bundle.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Consume this bundle, and return its encoding.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Read a [`KeyBundle`] entry from `r`, returning its name and its encoded item.
+pub(crate) fn read_entry<'a>(
+    r: &mut tor_bytes::Reader<'a>,
+) -> tor_bytes::Result<(&'a [u8], &'a [u8])> {
+    let name_len = r.take_u16()?;
+    let name = r.take(name_len.into())?;
+    let item_len = r.take_u32()?;
+    let item = r.take(usize::try_from(item_len).unwrap_or(usize::MAX))?;
+    Ok((name, item))
+}
+
+/// Parse a [`KeyBundle`] entry, returning the [`ArtiPath`] and type of its item.
+///
+/// Returns a description of the problem if the name of the entry is malformed,
+/// if the type of its item is unknown, or if its item can't be decoded.
+pub(crate) fn parse_entry(
+    name: &[u8],
+    item: &[u8],
+) -> StdResult<(ArtiPath, KeystoreItemType), String> {
+    let malformed_name = || format!("malformed entry name {:?}", String::from_utf8_lossy(name));
+
+    let name = std::str::from_utf8(name).map_err(|_| malformed_name())?;
+    let (path, extension) = name.rsplit_once('.').ok_or_else(malformed_name)?;
+    let path = ArtiPath::new(path.into()).map_err(|_| malformed_name())?;
+    let item_type = KeystoreItemType::from(extension);
+    if matches!(item_type, KeystoreItemType::Unknown { .. }) {
+        return Err(format!("unknown item type in entry {name:?}"));
+    }
+
+    decode_item(item.to_vec(), &item_type, PathBuf::from(name))
+        .map_err(|e| format!("invalid item in entry {name:?}: {}", e.report()))?;
+
+    Ok((path, item_type))
+}
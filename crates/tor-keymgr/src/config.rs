@@ -76,6 +76,11 @@ pub struct CTorKeystoreConfig {
     #[builder(default, sub_builder(fn_name = "build"))]
     #[builder_field_attr(serde(default))]
     clients: CTorClientKeystoreConfigList,
+
+    /// C Tor relay keystores.
+    #[builder(default, sub_builder(fn_name = "build"))]
+    #[builder_field_attr(serde(default))]
+    relays: CTorRelayKeystoreConfigList,
 }
 
 /// Primary [`ArtiNativeKeystore`](crate::ArtiNativeKeystore) configuration
@@ -275,6 +280,59 @@ fn build_ctor_client_store_config(
     Ok(ctor_stores)
 }
 
+/// C Tor [`CTorRelayKeystore`](crate::CTorRelayKeystore) configuration
+#[derive(Debug, Clone, Builder, Eq, PartialEq, Serialize, Deserialize, Getters)]
+#[builder(derive(Serialize, Deserialize, Debug))]
+#[builder(build_fn(error = "ConfigBuildError"))]
+#[non_exhaustive]
+#[builder_struct_attr(non_exhaustive)]
+pub struct CTorRelayKeystoreConfig {
+    /// The identifier of this keystore.
+    ///
+    /// Each keystore **must** have a unique identifier.
+    /// It is an error to configure multiple keystores with the same [`KeystoreId`].
+    id: KeystoreId,
+
+    /// The root directory of this keystore.
+    ///
+    /// This should be set to the `KeyDirectory` of your relay
+    /// (by default, `DataDirectory/keys`).
+    /// Arti will read the ed25519 identity and signing keys,
+    /// and the ntor onion key, from this path.
+    ///
+    /// The RSA keys can't be read, and other files will be ignored.
+    path: PathBuf,
+}
+
+/// The serialized format of a [`CTorRelayKeystoreConfigListBuilder`]:
+pub type CTorRelayKeystoreConfigList = Vec<CTorRelayKeystoreConfig>;
+
+define_list_builder_helper! {
+    pub struct CTorRelayKeystoreConfigListBuilder {
+        stores: [CTorRelayKeystoreConfigBuilder],
+    }
+    built: CTorRelayKeystoreConfigList = build_ctor_relay_store_config(stores)?;
+    default = vec![];
+}
+
+/// Helper for building and validating a [`CTorRelayKeystoreConfigList`].
+///
+/// Returns an error if the [`KeystoreId`]s of the `CTorRelayKeystoreConfig`s are not unique.
+fn build_ctor_relay_store_config(
+    ctor_stores: Vec<CTorRelayKeystoreConfig>,
+) -> Result<CTorRelayKeystoreConfigList, ConfigBuildError> {
+    use itertools::Itertools as _;
+
+    if !ctor_stores.iter().map(|s| &s.id).all_unique() {
+        return Err(ConfigBuildError::Inconsistent {
+            fields: ["id"].map(Into::into).into_iter().collect(),
+            problem: "the C Tor keystores do not have unique IDs".into(),
+        });
+    }
+
+    Ok(ctor_stores)
+}
+
 impl ArtiKeystoreConfig {
     /// Whether the keystore is enabled.
     pub fn is_enabled(&self) -> bool {
@@ -310,6 +368,11 @@ impl ArtiKeystoreConfig {
     pub fn ctor_client_stores(&self) -> impl Iterator<Item = &CTorClientKeystoreConfig> {
         self.ctor.clients.iter()
     }
+
+    /// The ctor relay keystore configs
+    pub fn ctor_relay_stores(&self) -> impl Iterator<Item = &CTorRelayKeystoreConfig> {
+        self.ctor.relays.iter()
+    }
 }
 
 impl_standard_builder! { ArtiKeystoreConfig }
@@ -386,6 +449,16 @@ impl CTorKeystoreConfigBuilder {
             return Err(no_compile_time_support("C Tor client keystores"));
         }
 
+        if self
+            .relays
+            .stores
+            .as_ref()
+            .map(|s| !s.is_empty())
+            .unwrap_or_default()
+        {
+            return Err(no_compile_time_support("C Tor relay keystores"));
+        }
+
         Ok(())
     }
 
@@ -398,10 +471,12 @@ impl CTorKeystoreConfigBuilder {
         let Self {
             ref services,
             ref clients,
+            ref relays,
         } = self;
         let mut ctor_store_ids = chain![
             services.stores.iter().flatten().map(|s| &s.id),
-            clients.stores.iter().flatten().map(|s| &s.id)
+            clients.stores.iter().flatten().map(|s| &s.id),
+            relays.stores.iter().flatten().map(|s| &s.id)
         ];
 
         // This is also validated by the KeyMgrBuilder (but it's a good idea to catch this sort of
@@ -471,6 +546,14 @@ mod test {
         b
     }
 
+    /// Helper for creating [`CTorRelayKeystoreConfigBuilders`].
+    fn relay_config_builder(id: &str, path: &str) -> CTorRelayKeystoreConfigBuilder {
+        let mut b = CTorRelayKeystoreConfigBuilder::default();
+        b.id(KeystoreId::from_str(id).unwrap());
+        b.path(PathBuf::from(path));
+        b
+    }
+
     #[test]
     #[cfg(all(feature = "ctor-keystore", feature = "keymgr"))]
     fn invalid_config() {
@@ -520,6 +603,26 @@ mod test {
             Inconsistent,
             "Multiple C Tor service keystores for service with nickname pungent"
         );
+
+        let mut builder = ArtiKeystoreConfigBuilder::default();
+        // Push a client and a relay with the same ID:
+        builder
+            .ctor()
+            .clients()
+            .access()
+            .push(client_config_builder("foo", "/var/lib/foo"));
+        builder
+            .ctor()
+            .relays()
+            .access()
+            .push(relay_config_builder("foo", "/var/lib/tor/keys"));
+        let err = builder.build().unwrap_err();
+
+        assert_config_error!(
+            err,
+            Inconsistent,
+            "the C Tor keystores do not have unique IDs"
+        );
     }
 
     #[test]
@@ -548,6 +651,20 @@ mod test {
             NoCompileTimeSupport,
             "C Tor service keystores configured but ctor-keystore feature not enabled"
         );
+
+        let mut builder = ArtiKeystoreConfigBuilder::default();
+        builder
+            .ctor()
+            .relays()
+            .access()
+            .push(relay_config_builder("foo", "/var/lib/tor/keys"));
+        let err = builder.build().unwrap_err();
+
+        assert_config_error!(
+            err,
+            NoCompileTimeSupport,
+            "C Tor relay keystores configured but ctor-keystore feature not enabled"
+        );
     }
 
     #[test]
@@ -578,9 +695,15 @@ mod test {
             .clients()
             .access()
             .push(client_config_builder("bar", "/var/lib/bar"));
+        builder
+            .ctor()
+            .relays()
+            .access()
+            .push(relay_config_builder("baz", "/var/lib/tor/keys"));
 
         let res = builder.build();
         assert!(res.is_ok(), "{:?}", res);
+        assert_eq!(res.unwrap().ctor_relay_stores().count(), 1);
     }
 
     #[test]
@@ -3,13 +3,14 @@
 use tor_error::HasKind;
 
 use dyn_clone::DynClone;
+use tor_key_forge::KeystoreItemType;
 use tor_persist::slug::BadSlug;
 
 use std::error::Error as StdError;
 use std::fmt;
 use std::sync::Arc;
 
-use crate::KeyPathError;
+use crate::{KeyPath, KeyPathError, KeystoreId};
 
 /// An Error type for this crate.
 #[derive(thiserror::Error, Debug, Clone)]
@@ -34,6 +35,26 @@ pub enum Error {
     #[error("Key already exists")]
     KeyAlreadyExists,
 
+    /// An error returned when the [`KeyMgr`](crate::KeyMgr) is asked to store an item
+    /// in a keystore that does not [support](crate::Keystore::supports) items of its type.
+    #[error("Keystore {keystore} cannot store items of type {item_type:?}")]
+    UnsupportedItemType {
+        /// The keystore we tried to write to.
+        keystore: KeystoreId,
+        /// The type of the item.
+        item_type: KeystoreItemType,
+    },
+
+    /// An error returned when a [`Keystore`](crate::Keystore)
+    /// is asked to perform an operation it doesn't support.
+    #[error("Keystore {keystore} does not support {action}")]
+    NotSupported {
+        /// The keystore that doesn't support the operation.
+        keystore: KeystoreId,
+        /// The operation that isn't supported.
+        action: &'static str,
+    },
+
     /// Error coming from the tor-key-forgecrate
     #[error("{0}")]
     KeyForge(#[from] tor_key_forge::Error),
@@ -62,6 +83,8 @@ impl HasKind for Error {
             E::Keystore(e) => e.kind(),
             E::Corruption(_) => EK::KeystoreCorrupted,
             E::KeyAlreadyExists => EK::BadApiUsage, // TODO: not strictly right
+            E::UnsupportedItemType { .. } => EK::BadApiUsage,
+            E::NotSupported { .. } => EK::BadApiUsage,
             E::KeyForge(_) => EK::BadApiUsage,
             E::InvalidCert(_) => EK::BadApiUsage, // TODO: not strictly right
             E::Bug(e) => e.kind(),
@@ -89,6 +112,28 @@ pub enum ArtiPathSyntaxError {
     Bug(#[from] tor_error::Bug),
 }
 
+impl ArtiPathSyntaxError {
+    /// Convert this error into a [`ConfigBuildError`](tor_config::ConfigBuildError)
+    /// about the configuration option `field`.
+    ///
+    /// Use this when an [`ArtiPath`](crate::ArtiPath), or one of its components
+    /// (such as a nickname), was taken from the configuration.
+    pub fn into_config_error(self, field: impl Into<String>) -> tor_config::ConfigBuildError {
+        let problem = match self {
+            ArtiPathSyntaxError::Slug(BadSlug::EmptySlugNotAllowed) => {
+                "key path has an empty component".to_string()
+            }
+            ArtiPathSyntaxError::Slug(e) => format!("not usable in a key path: {e}"),
+            ArtiPathSyntaxError::Bug(e) => format!("could not be validated: {e}"),
+        };
+
+        tor_config::ConfigBuildError::Invalid {
+            field: field.into(),
+            problem,
+        }
+    }
+}
+
 /// An error caused by keystore corruption.
 #[derive(thiserror::Error, Debug, Clone)]
 #[error("Keystore corruption")]
@@ -109,6 +154,13 @@ pub enum KeystoreCorruptionError {
     /// Missing signing key for certificate.
     #[error("Missing signing key for certificate")]
     MissingSigningKey,
+
+    /// The contents of a keystore entry don't match its stored checksum.
+    #[error("Checksum mismatch for {path}")]
+    ChecksumMismatch {
+        /// The path of the corrupt entry.
+        path: KeyPath,
+    },
 }
 
 /// An error that happens when we encounter an unknown key type.
@@ -153,6 +205,43 @@ mod tests {
         }
     }
 
+    #[test]
+    fn arti_path_config_error() {
+        use crate::ArtiPath;
+
+        let config_err = |path: &str| {
+            ArtiPath::new(path.into())
+                .unwrap_err()
+                .into_config_error("nickname")
+                .to_string()
+        };
+
+        let msg = config_err("allium cepa");
+        assert!(msg.contains("nickname"), "{msg}");
+        assert!(
+            msg.contains("character ' ' (U+0020) is not allowed"),
+            "{msg}"
+        );
+
+        // An attempt at path traversal.
+        let msg = config_err("..");
+        assert!(msg.contains("nickname"), "{msg}");
+        assert!(
+            msg.contains("character '.' (U+002E) is not allowed as the first character"),
+            "{msg}"
+        );
+
+        let msg = config_err("allium//cepa");
+        assert!(msg.contains("nickname"), "{msg}");
+        assert!(msg.contains("empty component"), "{msg}");
+
+        let msg = ArtiPathSyntaxError::from(tor_error::internal!("oops"))
+            .into_config_error("nickname")
+            .to_string();
+        assert!(msg.contains("nickname"), "{msg}");
+        assert!(msg.contains("could not be validated"), "{msg}");
+    }
+
     #[test]
     fn error_source() {
         let e: Error = (Arc::new(TestError(TestErrorSource)) as Arc<dyn KeystoreError>).into();
@@ -291,6 +291,65 @@ pub trait KeyPathInfoExtractor: Send + Sync {
     fn describe(&self, path: &KeyPath) -> StdResult<KeyPathInfo, KeyPathError>;
 }
 
+/// A set of [`KeySpecifier`] types that share the same `prefix` and `role`.
+///
+/// Returned by [`find_colliding_key_specifiers`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct KeySpecifierCollision {
+    /// The `prefix` of the specifiers.
+    pub prefix: &'static str,
+    /// The `role` of the specifiers.
+    pub role: &'static str,
+    /// The names of the specifier types, sorted.
+    pub type_names: Vec<&'static str>,
+}
+
+/// Return every `prefix` and `role` pair used by more than one [`KeySpecifier`] type.
+///
+/// This only knows about the types that derive `KeySpecifier`
+/// with a toplevel `#[deftly(role = "...")]`.
+///
+/// Two such types with the same `prefix` and `role` can produce `ArtiPath`s
+/// that collide with, or shadow, each other,
+/// so this should always return an empty list.
+/// [`KeyMgrBuilder::build`](crate::KeyMgrBuilder::build) logs a warning
+/// for each collision it finds.
+///
+/// The result is sorted by `prefix`, and then by `role`.
+pub fn find_colliding_key_specifiers() -> Vec<KeySpecifierCollision> {
+    use derive::KeySpecifierRegistration;
+
+    let mut types: BTreeMap<(&'static str, &'static str), Vec<&'static str>> = BTreeMap::new();
+    for reg in inventory::iter::<KeySpecifierRegistration>() {
+        types
+            .entry((reg.prefix, reg.role))
+            .or_default()
+            .push(reg.type_name);
+    }
+
+    types
+        .into_iter()
+        .filter(|(_, type_names)| type_names.len() > 1)
+        .map(|((prefix, role), mut type_names)| {
+            type_names.sort_unstable();
+            KeySpecifierCollision {
+                prefix,
+                role,
+                type_names,
+            }
+        })
+        .collect()
+}
+
+/// Return true if `prefix` is the `prefix` of some known [`KeySpecifier`] type.
+///
+/// Like [`find_colliding_key_specifiers`], this only knows about the types that
+/// derive `KeySpecifier` with a toplevel `#[deftly(role = "...")]`.
+pub(crate) fn is_known_key_specifier_prefix(prefix: &str) -> bool {
+    inventory::iter::<derive::KeySpecifierRegistration>().any(|reg| reg.prefix == prefix)
+}
+
 /// Register a [`KeyPathInfoExtractor`] for use with [`KeyMgr`](crate::KeyMgr).
 #[macro_export]
 macro_rules! register_key_info_extractor {
@@ -327,6 +386,27 @@ pub enum KeyPathPattern {
     CTor(CTorPath),
 }
 
+impl KeyPathPattern {
+    /// Return a pattern that matches every [`ArtiPath`].
+    ///
+    /// With this pattern, [`KeyMgr::list_matching`](crate::KeyMgr::list_matching)
+    /// returns every entry of the Arti keystores.
+    ///
+    /// Like any [`KeyPathPattern::Arti`] pattern, this never matches a [`CTorPath`].
+    pub fn all() -> Self {
+        Self::Arti("**".into())
+    }
+
+    /// Return a pattern that matches nothing.
+    ///
+    /// Unlike [`all`](KeyPathPattern::all), which matches every `ArtiPath`,
+    /// this doesn't match any [`KeyPath`].
+    pub fn empty() -> Self {
+        // No ArtiPath is empty.
+        Self::Arti(String::new())
+    }
+}
+
 /// The path of a key in the C Tor key store.
 #[derive(Clone, Debug, PartialEq, Eq, Hash, derive_more::Display)] //
 #[non_exhaustive]
@@ -347,6 +427,9 @@ pub enum CTorPath {
         /// The relative path of this key.
         path: CTorServicePath,
     },
+    /// A relay key path.
+    #[display("{_0}")]
+    Relay(CTorRelayPath),
 }
 
 /// The relative path in a C Tor key store.
@@ -361,6 +444,36 @@ pub enum CTorServicePath {
     PrivateKey,
 }
 
+/// The path of a relay key, relative to C Tor's `KeyDirectory`.
+///
+/// By default, C Tor's `KeyDirectory` is `DataDirectory/keys`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, derive_more::Display)] //
+#[non_exhaustive]
+pub enum CTorRelayPath {
+    /// C Tor's `KeyDirectory/ed25519_master_id_public_key`.
+    #[display("ed25519_master_id_public_key")]
+    Ed25519MasterIdPublicKey,
+    /// C Tor's `KeyDirectory/ed25519_master_id_secret_key`.
+    #[display("ed25519_master_id_secret_key")]
+    Ed25519MasterIdSecretKey,
+    /// C Tor's `KeyDirectory/ed25519_signing_secret_key`.
+    #[display("ed25519_signing_secret_key")]
+    Ed25519SigningSecretKey,
+    /// C Tor's `KeyDirectory/secret_onion_key_ntor`.
+    #[display("secret_onion_key_ntor")]
+    SecretOnionKeyNtor,
+    /// C Tor's `KeyDirectory/secret_id_key` (the RSA identity key).
+    ///
+    /// Arti can't read RSA keys.
+    #[display("secret_id_key")]
+    SecretIdKey,
+    /// C Tor's `KeyDirectory/secret_onion_key` (the RSA onion key).
+    ///
+    /// Arti can't read RSA keys.
+    #[display("secret_onion_key")]
+    SecretOnionKey,
+}
+
 impl CTorPath {
     /// Create a CTorPath that represents a service key.
     pub fn service(nickname: HsNickname, path: CTorServicePath) -> Self {
@@ -371,6 +484,11 @@ impl CTorPath {
     pub fn client(hsid: HsId) -> Self {
         Self::ClientHsDescEncKey(hsid)
     }
+
+    /// Create a CTorPath that represents a relay key.
+    pub fn relay(path: CTorRelayPath) -> Self {
+        Self::Relay(path)
+    }
 }
 
 /// The "specifier" of a key, which identifies an instance of a key.
@@ -435,6 +553,19 @@ pub enum ArtiPathUnavailableError {
     /// implementation.
     #[error("ArtiPath unavailable")]
     ArtiPathUnavailable,
+
+    /// A field of a derived [`KeySpecifier`] cannot be used as part of an [`ArtiPath`].
+    ///
+    /// This happens if, for example, the field's `Display` impl
+    /// produces a string containing a `/`.
+    #[error("Field {field:?} cannot be represented as an ArtiPath component")]
+    InvalidField {
+        /// The name of the offending field.
+        field: &'static str,
+        /// The error we got when trying to represent it.
+        #[source]
+        error: tor_error::Bug,
+    },
 }
 
 impl KeySpecifier for ArtiPath {
@@ -485,6 +616,11 @@ impl KeySpecifier for KeyPath {
     }
 }
 
+/// The largest time period length, in minutes, we accept when decoding a [`TimePeriod`].
+///
+/// This is the maximum value of the `hsdir_interval` consensus parameter.
+const TP_MAX_LENGTH_MINUTES: u32 = 14400;
+
 impl KeySpecifierComponent for TimePeriod {
     fn to_slug(&self) -> Result<Slug, Bug> {
         Slug::new(format!(
@@ -510,14 +646,23 @@ impl KeySpecifierComponent for TimePeriod {
             .collect_tuple()
             .ok_or_else(|| err_ctx("invalid number of subcomponents"))?;
 
-        let length = len.parse().map_err(|_| err_ctx("invalid length"))?;
+        let length: u32 = len.parse().map_err(|_| err_ctx("invalid length"))?;
         let interval_num = interval
             .parse()
             .map_err(|_| err_ctx("invalid interval_num"))?;
-        let offset_in_sec = offset
+        let offset_in_sec: u32 = offset
             .parse()
             .map_err(|_| err_ctx("invalid offset_in_sec"))?;
 
+        // Reject values that can't have come from a real time period:
+        // they probably indicate keystore corruption.
+        if length == 0 || length > TP_MAX_LENGTH_MINUTES {
+            return Err(err_ctx("length out of range"));
+        }
+        if u64::from(offset_in_sec) > u64::from(length) * 60 {
+            return Err(err_ctx("offset_in_sec exceeds the period length"));
+        }
+
         Ok(TimePeriod::from_parts(length, interval_num, offset_in_sec))
     }
 
@@ -594,6 +739,87 @@ impl KeySpecifierComponent for HsId {
     }
 }
 
+/// The [`Slug`] representation of a `Vec` of [`KeySpecifierComponent`]s
+/// starts with this prefix.
+///
+/// (We need a prefix since `Slug`s can't be empty,
+/// and we need to be able to represent an empty `Vec`.)
+const LIST_PREFIX: &str = "list";
+
+/// The character that precedes each element
+/// in the [`Slug`] representation of a `Vec` of [`KeySpecifierComponent`]s.
+const LIST_SEP: char = '-';
+
+/// A list of components is represented as [`LIST_PREFIX`],
+/// followed by each element's slug, preceded by [`LIST_SEP`].
+/// For example, `vec![1, 2, 3]` is represented as `list-1-2-3`,
+/// and an empty `Vec` as `list`.
+///
+/// Since `-` separates the elements, the slug of each element must not contain `-`.
+/// (We don't use `_`, since some components, like [`TimePeriod`], contain `_`.)
+/// [`to_slug`](KeySpecifierComponent::to_slug) returns an error for lists containing such elements.
+///
+/// [`from_slug`](KeySpecifierComponent::from_slug) only accepts
+/// the canonical representation of each element:
+/// it rejects any element that is not re-encoded exactly as it was found.
+impl<T: KeySpecifierComponent> KeySpecifierComponent for Vec<T> {
+    fn to_slug(&self) -> Result<Slug, Bug> {
+        let mut s = LIST_PREFIX.to_string();
+        for elem in self {
+            let elem = elem.to_slug()?;
+            if elem.as_str().contains(LIST_SEP) {
+                return Err(internal!(
+                    "list element {elem:?} contains the list separator {LIST_SEP:?}"
+                ));
+            }
+            s.push(LIST_SEP);
+            s.push_str(elem.as_str());
+        }
+        Slug::new(s).map_err(into_internal!("list formatting went wrong"))
+    }
+
+    fn from_slug(s: &Slug) -> StdResult<Self, InvalidKeyPathComponentValue>
+    where
+        Self: Sized,
+    {
+        let err_ctx = |e: &str| InvalidKeyPathComponentValue::Slug(e.to_string());
+        let elems = s
+            .as_str()
+            .strip_prefix(LIST_PREFIX)
+            .ok_or_else(|| err_ctx("missing list prefix"))?;
+        if elems.is_empty() {
+            return Ok(vec![]);
+        }
+        let elems = elems
+            .strip_prefix(LIST_SEP)
+            .ok_or_else(|| err_ctx("missing list separator"))?;
+
+        elems
+            .split(LIST_SEP)
+            .map(|elem| {
+                let elem =
+                    Slug::new(elem.to_string()).map_err(|_| err_ctx("invalid list element"))?;
+                let value = T::from_slug(&elem)?;
+                if value.to_slug()? != elem {
+                    return Err(err_ctx("list element does not round-trip"));
+                }
+                Ok(value)
+            })
+            .collect()
+    }
+
+    fn fmt_pretty(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[")?;
+        for (i, elem) in self.iter().enumerate() {
+            if i != 0 {
+                write!(f, ", ")?;
+            }
+            elem.fmt_pretty(f)?;
+        }
+        write!(f, "]")
+    }
+}
+
 /// Wrapper for `KeySpecifierComponent` that `Displays` via `fmt_pretty`
 struct KeySpecifierComponentPrettyHelper<'c>(&'c dyn KeySpecifierComponent);
 
@@ -680,6 +906,32 @@ mod test {
         );
     }
 
+    #[test]
+    fn list_component() {
+        for (list, slug, pretty) in [
+            (vec![], "list", "[]"),
+            (vec![7_usize], "list-7", "[7]"),
+            (vec![1, 20, 300], "list-1-20-300", "[1, 20, 300]"),
+        ] {
+            let encoded = list.to_slug().unwrap();
+            assert_eq!(encoded.as_str(), slug);
+            assert_eq!(Vec::<usize>::from_slug(&encoded).unwrap(), list);
+            assert_eq!(KeySpecifierComponentPrettyHelper(&list).to_string(), pretty);
+        }
+
+        // Elements can't contain the separator.
+        assert!(vec!["a-b".to_string()].to_slug().is_err());
+        // But they can contain underscores.
+        let tps = vec![test_time_period(), test_time_period()];
+        let encoded = tps.to_slug().unwrap();
+        assert_eq!(Vec::<TimePeriod>::from_slug(&encoded).unwrap(), tps);
+
+        for bad in ["lst-1", "list1", "list-", "list--1", "list-x", "list-01"] {
+            let bad = Slug::new(bad.to_string()).unwrap();
+            assert!(Vec::<usize>::from_slug(&bad).is_err(), "{bad}");
+        }
+    }
+
     #[test]
     fn serde() {
         // TODO: clone-and-hack with tor_hsservice::::nickname::test::serde
@@ -704,6 +956,42 @@ mod test {
         );
     }
 
+    #[test]
+    fn all_and_empty_patterns() {
+        let paths = [
+            "encabulator",
+            "encabulator/marzlevane",
+            "encabulator/logarithmic/prefabulating/fan+hello",
+            "hss/allium-cepa/ks_hs_id",
+        ];
+        for path in paths {
+            let path = KeyPath::Arti(ArtiPath::new(path.into()).unwrap());
+            assert!(path.matches(&KeyPathPattern::all()), "{path}");
+            assert!(!path.matches(&KeyPathPattern::empty()), "{path}");
+        }
+    }
+
+    #[test]
+    fn colliding_key_specifiers() {
+        const TEST_SPECIFIER: &str = "tor_keymgr::key_specifier::test::TestSpecifier";
+
+        let mut collisions = find_colliding_key_specifiers()
+            .into_iter()
+            .map(|c| ((c.prefix, c.role), c.type_names))
+            .collect::<Vec<_>>();
+        collisions.sort();
+
+        // The specifiers defined by the tests in this module
+        // reuse the same couple of prefix/role pairs.
+        assert_eq!(
+            collisions,
+            vec![
+                (("encabulator", "fan"), vec![TEST_SPECIFIER; 2]),
+                (("encabulator", "marzlevane"), vec![TEST_SPECIFIER; 5]),
+            ]
+        );
+    }
+
     #[test]
     fn define_key_specifier_with_fields_and_denotator() {
         let tp = test_time_period();
@@ -760,6 +1048,43 @@ KeyPathInfo {
         );
     }
 
+    #[test]
+    fn define_key_specifier_invalid_field() {
+        #[derive(Deftly, Debug, PartialEq)]
+        #[derive_deftly(KeySpecifier)]
+        #[deftly(prefix = "encabulator")]
+        #[deftly(role = "marzlevane")]
+        #[deftly(summary = "test key")]
+        struct TestSpecifier {
+            kind: String,
+            #[deftly(denotator)]
+            count: String,
+        }
+
+        let key_spec = TestSpecifier {
+            kind: "hydro/coptic".into(),
+            count: "6".into(),
+        };
+        assert!(matches!(
+            key_spec.arti_path(),
+            Err(ArtiPathUnavailableError::InvalidField { field: "kind", .. })
+        ));
+
+        let key_spec = TestSpecifier {
+            kind: "hydrocoptic".into(),
+            count: "6/7".into(),
+        };
+        let err = key_spec.arti_path().unwrap_err();
+        assert!(matches!(
+            err,
+            ArtiPathUnavailableError::InvalidField { field: "count", .. }
+        ));
+        assert_eq!(
+            err.to_string(),
+            r#"Field "count" cannot be represented as an ArtiPath component"#
+        );
+    }
+
     #[test]
     fn define_key_specifier_no_fields() {
         #[derive(Deftly, Debug, PartialEq)]
@@ -801,6 +1126,38 @@ KeyPathInfo {
         );
     }
 
+    #[test]
+    fn define_key_specifier_denotator_sep() {
+        #[derive(Deftly, Debug, PartialEq)]
+        #[derive_deftly(KeySpecifier)]
+        #[deftly(prefix = "encabulator")]
+        #[deftly(role = "marzlevane")]
+        #[deftly(summary = "test key")]
+        struct TestSpecifier {
+            #[deftly(denotator)]
+            kind: String,
+            #[deftly(denotator)]
+            count: usize,
+        }
+
+        let key_spec = TestSpecifier {
+            kind: "side-fumbling".into(),
+            count: 6,
+        };
+        check_key_specifier(&key_spec, "encabulator/marzlevane+side-fumbling+6");
+
+        // A denotator containing a separator can't be represented,
+        // since it would be parsed back as two denotators.
+        let key_spec = TestSpecifier {
+            kind: "side+fumbling".into(),
+            count: 6,
+        };
+        assert!(key_spec.arti_path().is_err());
+        let path = ArtiPath::new("encabulator/marzlevane+side+fumbling+6".into()).unwrap();
+        assert!(TestSpecifier::try_from(&KeyPath::Arti(path)).is_err());
+        assert!(ArtiPath::new("encabulator/marzlevane+side++fumbling+6".into()).is_err());
+    }
+
     #[test]
     fn define_key_specifier_with_fields() {
         #[derive(Deftly, Debug, PartialEq)]
@@ -955,6 +1312,80 @@ KeyPathInfo {
         check_key_specifier(&TestSpecifier { x: 1, z: true }, "prefix/1/fixed/true/role");
     }
 
+    /// Represent an `Ed25519Identity` as lowercase hex,
+    /// since its `Display` impl uses base64, which isn't path-safe.
+    mod hex_identity {
+        use super::*;
+        use tor_llcrypto::pk::ed25519::Ed25519Identity;
+
+        pub(super) fn to_slug(id: &Ed25519Identity) -> StdResult<Slug, Bug> {
+            let hex = id.as_bytes().iter().map(|b| format!("{b:02x}")).collect();
+            Slug::new(hex).map_err(into_internal!("hex is not a valid slug?!"))
+        }
+
+        pub(super) fn from_slug(
+            s: &Slug,
+        ) -> StdResult<Ed25519Identity, InvalidKeyPathComponentValue> {
+            let s = s.as_str();
+            let bytes = (0..s.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+                .collect::<Option<Vec<u8>>>();
+            bytes
+                .and_then(|bytes| Ed25519Identity::from_bytes(&bytes))
+                .ok_or_else(|| InvalidKeyPathComponentValue::Slug("not a hex identity".into()))
+        }
+    }
+
+    #[test]
+    fn define_key_specifier_component_with() {
+        use tor_llcrypto::pk::ed25519::Ed25519Identity;
+
+        #[derive(Deftly, Debug, Eq, PartialEq)]
+        #[derive_deftly(KeySpecifier)]
+        #[deftly(prefix = "relay")]
+        #[deftly(role = "role")]
+        #[deftly(summary = "test key")]
+        struct TestSpecifier {
+            #[deftly(component(with = "hex_identity"))]
+            id: Ed25519Identity,
+            #[deftly(denotator)]
+            #[deftly(component(with = "hex_identity"))]
+            other: Ed25519Identity,
+        }
+
+        let spec = TestSpecifier {
+            id: [0xab; 32].into(),
+            other: [0x01; 32].into(),
+        };
+        let id_hex = "ab".repeat(32);
+        let other_hex = "01".repeat(32);
+        let path = format!("relay/{id_hex}/role+{other_hex}");
+        check_key_specifier(&spec, &path);
+
+        let pat = TestSpecifierPattern {
+            id: Some([0xab; 32].into()),
+            other: None,
+        };
+        assert_eq!(
+            pat.arti_pattern().unwrap(),
+            KeyPathPattern::Arti(format!("relay/{id_hex}/role+*"))
+        );
+
+        let info = TestSpecifierInfoExtractor
+            .describe(&KeyPath::Arti(ArtiPath::new(path).unwrap()))
+            .unwrap();
+        assert_eq!(info.extra_info().get("id"), Some(&id_hex));
+        assert_eq!(info.extra_info().get("other"), Some(&other_hex));
+
+        // A path whose component can't be parsed by `from_slug` is rejected.
+        let bad = KeyPath::Arti(ArtiPath::new(format!("relay/xyz/role+{other_hex}")).unwrap());
+        assert!(matches!(
+            TestSpecifier::try_from(&bad),
+            Err(KeyPathError::InvalidKeyPathComponentValue { key, .. }) if key == "id"
+        ));
+    }
+
     #[test]
     fn encode_time_period() {
         let period = TimePeriod::from_parts(1, 2, 3);
@@ -965,6 +1396,20 @@ KeyPathInfo {
 
         assert!(TimePeriod::from_slug(&Slug::new("invalid_tp".to_string()).unwrap()).is_err());
         assert!(TimePeriod::from_slug(&Slug::new("2_1_3_4".to_string()).unwrap()).is_err());
+
+        let decode = |s: &str| TimePeriod::from_slug(&Slug::new(s.to_string()).unwrap());
+        // Zero length.
+        assert!(decode("0_5_0").is_err());
+        assert!(decode("5_0_0").is_err());
+        // Absurdly long.
+        assert!(decode("5_14401_0").is_err());
+        assert!(decode("5_4294967295_0").is_err());
+        // Offset longer than the period.
+        assert!(decode("2_1_61").is_err());
+        assert!(decode("18519_1440_86401").is_err());
+        // Boundary values are fine.
+        assert!(decode("2_1_60").is_ok());
+        assert!(decode("18519_14400_43200").is_ok());
     }
 
     #[test]
@@ -20,6 +20,23 @@ use crate::DENOTATOR_SEP;
 pub use crate::KeyPathInfoBuilder;
 pub use tor_error::{internal, into_internal, Bug};
 
+/// A record of the static `prefix` and `role` of a [`KeySpecifier`] type.
+///
+/// One of these is submitted to `inventory` for each type
+/// that derives `KeySpecifier` with a toplevel `#[deftly(role = "...")]`.
+/// Used by [`find_colliding_key_specifiers`](crate::find_colliding_key_specifiers).
+#[derive(Debug)]
+#[allow(clippy::exhaustive_structs)]
+pub struct KeySpecifierRegistration {
+    /// The `prefix` of the specifier.
+    pub prefix: &'static str,
+    /// The `role` of the specifier, as it appears in its `ArtiPath`.
+    pub role: &'static str,
+    /// The name of the specifier type.
+    pub type_name: &'static str,
+}
+inventory::collect!(KeySpecifierRegistration);
+
 /// Trait for (only) formatting as a [`KeySpecifierComponent`]
 ///
 /// Like the formatting part of `KeySpecifierComponent`
@@ -55,6 +72,50 @@ impl<'s> RawKeySpecifierComponent for &'s str {
     }
 }
 
+/// A field whose representation is given by a `#[deftly(component(with = "..."))]` module
+///
+/// The field's type need not implement [`KeySpecifierComponent`]:
+/// instead, the macro-generated code wraps a reference to the field in one of these,
+/// along with the `to_slug` and `from_slug` functions from the module.
+///
+/// `V` is the reference to the field:
+///   * `&T`, when building an `ArtiPath` from a specifier,
+///     or describing it (via the `KeySpecifierComponent` impl);
+///   * `&Option<T>`, when building a pattern;
+///   * `&mut Option<T>`, when parsing a `KeyPath`.
+#[allow(clippy::exhaustive_structs)] // Not part of public API
+pub struct ComponentWith<V, T> {
+    /// The field
+    pub value: V,
+    /// Return the [`Slug`] representation of a value of the field's type
+    pub to_slug: fn(&T) -> Result<Slug, Bug>,
+    /// Parse a value of the field's type from its [`Slug`] representation
+    pub from_slug: fn(&Slug) -> Result<T, InvalidKeyPathComponentValue>,
+}
+
+impl<'v, T> KeySpecifierComponent for ComponentWith<&'v T, T> {
+    fn to_slug(&self) -> Result<Slug, Bug> {
+        (self.to_slug)(self.value)
+    }
+    fn from_slug(_: &Slug) -> Result<Self, InvalidKeyPathComponentValue> {
+        // We only implement KeySpecifierComponent so that we can describe the field;
+        // parsing goes via the RawKeySpecifierComponentParser impl, below.
+        Err(internal!("tried to parse a ComponentWith as a KeySpecifierComponent").into())
+    }
+    fn fmt_pretty(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // We have no other human-readable representation of the value.
+        Display::fmt(&self.to_slug().map_err(|_| fmt::Error)?, f)
+    }
+}
+impl<'v, T> RawKeySpecifierComponent for ComponentWith<&'v Option<T>, T> {
+    fn append_to(&self, s: &mut String) -> Result<(), Bug> {
+        match self.value {
+            Some(v) => (self.to_slug)(v)?.as_str().append_to(s),
+            None => "*".append_to(s),
+        }
+    }
+}
+
 /// Make a string like `pc/pc/pc/lc_lc_lc`
 fn arti_path_string_from_components(
     path_comps: &[&dyn RawKeySpecifierComponent],
@@ -102,6 +163,20 @@ pub fn arti_path_from_components(
         .map_err(into_internal!("bad ArtiPath from good components"))?)
 }
 
+/// Check that `comp`, the value of the field `field`, can be used in an `ArtiPath`
+///
+/// The `KeySpecifier` macro's `arti_path()` impls call this for each field
+/// before calling [`arti_path_from_components`],
+/// so that a field with a bad representation is reported by name,
+/// rather than as an anonymous internal error.
+pub fn check_arti_path_field(
+    field: &'static str,
+    comp: &dyn RawKeySpecifierComponent,
+) -> Result<(), ArtiPathUnavailableError> {
+    comp.append_to(&mut String::new())
+        .map_err(|error| ArtiPathUnavailableError::InvalidField { field, error })
+}
+
 /// Make a `KeyPathPattern::Arti` like `pc/pc/pc/lc_lc_lc`
 pub fn arti_pattern_from_components(
     path_comps: &[&dyn RawKeySpecifierComponent],
@@ -153,6 +228,16 @@ impl<T: KeySpecifierComponent> RawKeySpecifierComponentParser for Option<T> {
         RCPR::ParsedField
     }
 }
+impl<'v, T> RawKeySpecifierComponentParser for ComponentWith<&'v mut Option<T>, T> {
+    fn parse(&mut self, comp: &Slug) -> RawComponentParseResult {
+        let v = match (self.from_slug)(comp) {
+            Ok(v) => v,
+            Err(e) => return RCPR::Invalid(e),
+        };
+        *self.value = Some(v);
+        RCPR::ParsedField
+    }
+}
 impl<'s> RawKeySpecifierComponentParser for &'s str {
     fn parse(&mut self, comp: &Slug) -> RawComponentParseResult {
         if comp.as_str() == *self {
@@ -323,7 +408,8 @@ define_derive_deftly! {
     /// from the string representation of its path fields, taken in declaration order,
     /// followed by the encoding of its denotators, also taken in the order they were declared.
     /// As such, all path fields, must implement [`KeySpecifierComponent`].
-    /// and all denotators must implement [`KeySpecifierComponent`].
+    /// and all denotators must implement [`KeySpecifierComponent`]
+    /// (unless they have a `#[deftly(component(with = "..."))]` attribute; see below).
     /// The denotators are separated from the rest of the path, and from each other,
     /// by `+` characters.
     ///
@@ -360,6 +446,12 @@ define_derive_deftly! {
     ///    Must be a literal string.
     ///    This or the field-level `#[deftly(role)]` must be specified.
     ///
+    ///    No two specifier types may have the same `prefix` and `role`,
+    ///    even if their other fields differ:
+    ///    their `ArtiPath`s could otherwise collide, or shadow each other.
+    ///    Use [`find_colliding_key_specifiers`](crate::find_colliding_key_specifiers)
+    ///    to check for this.
+    ///
     ///  * **`[adhoc(role)]` (field):
     ///    Specifies that the role is determined at runtime.
     ///    The field type must implement [`KeyDenotator`].
@@ -386,6 +478,17 @@ define_derive_deftly! {
     ///    (Can be even used before a denotator component,
     ///    to add a final fixed path component.)
     ///
+    ///  * **`#[deftly(component(with = "module"))]`** (field):
+    ///    Represent this field using the functions of the module `module`,
+    ///    rather than its [`KeySpecifierComponent`] implementation
+    ///    (the field's type then need not implement `KeySpecifierComponent`).
+    ///    Like serde's `#[serde(with = "module")]`,
+    ///    `module` must provide the functions
+    ///    `fn to_slug(&FieldType) -> Result<Slug, Bug>` and
+    ///    `fn from_slug(&Slug) -> Result<FieldType, InvalidKeyPathComponentValue>`,
+    ///    with the same meaning as the corresponding [`KeySpecifierComponent`] methods.
+    ///    In the [`KeyPathInfo`], the value of the field is shown as its `Slug`.
+    ///
     ///  * **`#[deftly(key_specifier = "type")]`** (field):
     ///    If this is the specifier for a public key, the specifier for
     ///    the corresponding keypair type.
@@ -481,7 +584,22 @@ define_derive_deftly! {
         }}
     }}
 
-    ${define DO_FIELD { &self.$fname, }}
+    // A reference to the field `$fname` of `$FIELD_OF`,
+    // wrapped in a `ComponentWith` if it has `#[deftly(component(with = ...))]`.
+    ${define FIELD_REF {
+        ${if fmeta(component(with)) {
+            &ComponentWith {
+                value: &$FIELD_OF.$fname,
+                to_slug: ${fmeta(component(with)) as token_stream}::to_slug,
+                from_slug: ${fmeta(component(with)) as token_stream}::from_slug,
+            }
+        } else {
+            &$FIELD_OF.$fname
+        }}
+    }}
+    ${define FIELD_OF { self }}
+
+    ${define DO_FIELD { $FIELD_REF, }}
     ${define DO_LITERAL { &$LIT, }}
     ${define DO_ROLE_FIELD { $DO_FIELD }}
     ${define DO_ROLE_LITERAL { $DO_LITERAL }}
@@ -494,6 +612,13 @@ define_derive_deftly! {
         ) -> std::result::Result<$crate::ArtiPath, $crate::ArtiPathUnavailableError> {
             use $crate::key_specifier_derive::*;
 
+            {
+                ${define DO_FIELD { check_arti_path_field(stringify!($fname), $FIELD_REF)?; }}
+                ${define DO_LITERAL {}}
+                $ARTI_PATH_COMPONENTS
+                $ARTI_LEAF_COMPONENTS
+            }
+
             arti_path_from_components(
                 &[ $ARTI_PATH_COMPONENTS ],
                 &[ $ARTI_LEAF_COMPONENTS ],
@@ -555,6 +680,8 @@ define_derive_deftly! {
             #[allow(unused_variables)] // Unused if no fields
             let spec = $ttype::try_from(path)?;
 
+            ${define FIELD_OF { spec }}
+
             // none of this cares about non-role literals
             // all the others three be explicitly defined each time
             ${define DO_LITERAL {}}
@@ -572,14 +699,14 @@ define_derive_deftly! {
 
                 // role
                 ${define DO_FIELD {}}
-                ${define DO_ROLE_FIELD { &spec.$fname, }}
+                ${define DO_ROLE_FIELD { $FIELD_REF, }}
                 ${define DO_ROLE_LITERAL { &$LIT, }}
                 $ARTI_LEAF_COMPONENTS
 
                 &NON_ROLE_FIELD_KEYS,
 
                 &[
-                    ${define DO_FIELD { &spec.$fname, }}
+                    ${define DO_FIELD { $FIELD_REF, }}
                     ${define DO_ROLE_FIELD {}}
                     ${define DO_ROLE_LITERAL {}}
                     $ARTI_PATH_COMPONENTS
@@ -609,7 +736,17 @@ define_derive_deftly! {
             let mut builder =
                 <$<$tname Pattern>::<$tgens> as $crate::KeySpecifierPattern>::new_any();
 
-            ${define DO_FIELD { &mut builder.$fname, }}
+            ${define DO_FIELD {
+                ${if fmeta(component(with)) {
+                    &mut ComponentWith {
+                        value: &mut builder.$fname,
+                        to_slug: ${fmeta(component(with)) as token_stream}::to_slug,
+                        from_slug: ${fmeta(component(with)) as token_stream}::from_slug,
+                    },
+                } else {
+                    &mut builder.$fname,
+                }}
+            }}
             ${define DO_LITERAL { &mut $LIT, }}
 
             parse_key_path(
@@ -630,4 +767,13 @@ define_derive_deftly! {
 
     // Register the info extractor with `KeyMgr`.
     $crate::inventory::submit!(&$< $tname InfoExtractor > as &dyn $crate::KeyPathInfoExtractor);
+
+    // Register the prefix and role, so we can check that they're unique.
+    ${if tmeta(role) {
+        $crate::inventory::submit!($crate::key_specifier_derive::KeySpecifierRegistration {
+            prefix: ${tmeta(prefix) as str},
+            role: stringify!(${snake_case ${tmeta(role)}}),
+            type_name: concat!(module_path!(), "::", stringify!($tname)),
+        });
+    }}
 }
@@ -8,9 +8,13 @@ pub(crate) mod fs_utils;
 #[cfg(feature = "ephemeral-keystore")]
 pub(crate) mod ephemeral;
 
+use std::fmt::Debug;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
 use tor_key_forge::{EncodableItem, ErasedKey, KeystoreItemType};
 
-use crate::{KeyPath, KeySpecifier, KeystoreId, Result};
+use crate::{ArtiPath, KeyPath, KeySpecifier, KeystoreId, Result};
 
 /// A generic key store.
 pub trait Keystore: Send + Sync + 'static {
@@ -23,6 +27,19 @@ pub trait Keystore: Send + Sync + 'static {
     /// Check if the key identified by `key_spec` exists in this key store.
     fn contains(&self, key_spec: &dyn KeySpecifier, item_type: &KeystoreItemType) -> Result<bool>;
 
+    /// Check which of the keys identified by `keys` exist in this key store.
+    ///
+    /// Returns a vector with one entry for each element of `keys`, in the same order,
+    /// which is `true` if the corresponding key exists.
+    ///
+    /// The default implementation calls [`contains`](Keystore::contains) for each key.
+    /// Implementations that can check several keys at once more cheaply should override it.
+    fn contains_many(&self, keys: &[(&dyn KeySpecifier, &KeystoreItemType)]) -> Result<Vec<bool>> {
+        keys.iter()
+            .map(|(key_spec, item_type)| self.contains(*key_spec, item_type))
+            .collect()
+    }
+
     /// Retrieve the key identified by `key_spec`.
     ///
     /// Returns `Ok(Some(key))` if the key was successfully retrieved. Returns `Ok(None)` if the
@@ -48,6 +65,230 @@ pub trait Keystore: Send + Sync + 'static {
         item_type: &KeystoreItemType,
     ) -> Result<Option<()>>;
 
+    /// Update the modification time of the specified key to the current time,
+    /// without changing the key itself.
+    ///
+    /// A return value of `Ok(None)` indicates the key doesn't exist in this key store, whereas
+    /// `Ok(Some(())` means its modification time was updated.
+    ///
+    /// The default implementation returns [`Error::NotSupported`](crate::Error::NotSupported),
+    /// for keystores that don't record modification times
+    /// (see [`modified`](Keystore::modified)).
+    fn touch(
+        &self,
+        key_spec: &dyn KeySpecifier,
+        item_type: &KeystoreItemType,
+    ) -> Result<Option<()>> {
+        let _ = (key_spec, item_type);
+        Err(crate::Error::NotSupported {
+            keystore: self.id().clone(),
+            action: "touch",
+        })
+    }
+
+    /// Return the time at which the specified key was last modified,
+    /// by [`insert`](Keystore::insert) or [`touch`](Keystore::touch).
+    ///
+    /// Returns `Ok(None)` if the key doesn't exist,
+    /// or if this keystore doesn't record modification times.
+    ///
+    /// The default implementation always returns `Ok(None)`.
+    fn modified(
+        &self,
+        key_spec: &dyn KeySpecifier,
+        item_type: &KeystoreItemType,
+    ) -> Result<Option<SystemTime>> {
+        let _ = (key_spec, item_type);
+        Ok(None)
+    }
+
     /// List all the keys in this keystore.
     fn list(&self) -> Result<Vec<(KeyPath, KeystoreItemType)>>;
+
+    /// List the paths of all the keys of type `item_type` in this keystore.
+    ///
+    /// The default implementation filters the result of [`list`](Keystore::list).
+    /// Implementations that can cheaply skip entries of other types should override it.
+    fn list_by_type(&self, item_type: &KeystoreItemType) -> Result<Vec<KeyPath>> {
+        Ok(self
+            .list()?
+            .into_iter()
+            .filter_map(|(path, ty)| (&ty == item_type).then_some(path))
+            .collect())
+    }
+
+    /// List at most `limit` of the keys in this keystore, skipping the first `offset`.
+    ///
+    /// Alongside the keys, returns `true` if there are more keys after the ones returned.
+    ///
+    /// The keys are listed in an order that is unspecified, but deterministic:
+    /// as long as the keystore isn't modified,
+    /// successive pages are contiguous and don't overlap,
+    /// and together they contain every key that [`list`](Keystore::list) returns.
+    ///
+    /// The default implementation sorts the result of [`list`](Keystore::list)
+    /// by path, and then by item type.
+    /// Implementations that can produce their keys in a deterministic order
+    /// without listing all of them should override it.
+    fn list_page(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<(KeyPath, KeystoreItemType)>, bool)> {
+        let mut entries = self.list()?;
+        entries
+            .sort_by_cached_key(|(path, item_type)| (path.to_string(), item_type.arti_extension()));
+        let has_more = entries.len() > offset.saturating_add(limit);
+        let page = entries.into_iter().skip(offset).take(limit).collect();
+        Ok((page, has_more))
+    }
+
+    /// Return a description of this keystore, suitable for showing to an administrator.
+    ///
+    /// The default implementation reports a [`KeystoreKind::Other`] keystore
+    /// with no known location,
+    /// and counts the entries returned by [`list`](Keystore::list).
+    fn describe(&self) -> Result<KeystoreDescription> {
+        Ok(KeystoreDescription::new(
+            self.id().clone(),
+            KeystoreKind::Other,
+            None,
+            self.list()?.len(),
+        ))
+    }
+
+    /// Whether this keystore is able to store items of type `item_type`.
+    ///
+    /// [`KeyMgr`](crate::KeyMgr) checks this before writing to a keystore,
+    /// and returns [`Error::UnsupportedItemType`](crate::Error::UnsupportedItemType)
+    /// if the selected keystore can't hold the item.
+    ///
+    /// Keystores that are read-only, and can't store anything,
+    /// must return `false` for every item type.
+    ///
+    /// The default implementation returns `true` for every item type.
+    fn supports(&self, item_type: &KeystoreItemType) -> bool {
+        let _ = item_type;
+        true
+    }
+
+    /// Check the integrity of every entry in this keystore.
+    ///
+    /// Returns the paths of the entries that are corrupt.
+    ///
+    /// The default implementation returns an empty list,
+    /// for keystores that have no way of detecting corruption.
+    fn verify_all(&self) -> Result<Vec<KeyPath>> {
+        Ok(vec![])
+    }
+
+    /// Return the paths of the entries that this keystore has moved aside because they were corrupt.
+    ///
+    /// Most keystores never do this, and the default implementation returns an empty list.
+    fn quarantined(&self) -> Result<Vec<PathBuf>> {
+        Ok(vec![])
+    }
+}
+
+/// An operation on a [`Keystore`], as reported to a [`KeystoreObserver`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, derive_more::Display)]
+#[non_exhaustive]
+pub enum KeystoreOperation {
+    /// [`Keystore::contains`].
+    #[display("contains")]
+    Contains,
+    /// [`Keystore::get`].
+    #[display("get")]
+    Get,
+    /// [`Keystore::insert`].
+    #[display("insert")]
+    Insert,
+    /// [`Keystore::remove`].
+    #[display("remove")]
+    Remove,
+    /// [`Keystore::touch`].
+    #[display("touch")]
+    Touch,
+    /// [`Keystore::list`], or [`Keystore::list_by_type`].
+    #[display("list")]
+    List,
+}
+
+/// A hook for observing the operations performed on a [`Keystore`].
+///
+/// This is meant for building audit logs, or for detecting unexpected key accesses.
+///
+/// Keystores that support observers (such as [`ArtiNativeKeystore`](crate::ArtiNativeKeystore))
+/// call [`on_access`](KeystoreObserver::on_access) once per operation,
+/// after the operation has completed.
+pub trait KeystoreObserver: Debug + Send + Sync + 'static {
+    /// Called after the keystore has performed `op`.
+    ///
+    /// `path` is the [`ArtiPath`] of the entry that `op` accessed.
+    /// It is `None` for operations that don't access a single entry (like listing),
+    /// and for keys that have no `ArtiPath`.
+    ///
+    /// `ok` is true if the operation succeeded.
+    /// (Note that looking up or removing an entry that doesn't exist counts as success.)
+    ///
+    /// This is called before the keystore method returns,
+    /// so it should return quickly.
+    fn on_access(&self, op: KeystoreOperation, path: Option<&ArtiPath>, ok: bool);
+}
+
+/// The kind of backend behind a [`Keystore`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, derive_more::Display)]
+#[non_exhaustive]
+pub enum KeystoreKind {
+    /// An [`ArtiNativeKeystore`](crate::ArtiNativeKeystore).
+    #[display("arti-fs")]
+    ArtiNative,
+    /// A C Tor client keystore (`ClientOnionAuthDir`).
+    #[display("ctor-client")]
+    CTorClient,
+    /// A C Tor onion service keystore (`HiddenServiceDir`).
+    #[display("ctor-service")]
+    CTorService,
+    /// A C Tor relay keystore (`KeyDirectory`).
+    #[display("ctor-relay")]
+    CTorRelay,
+    /// An in-memory keystore.
+    #[display("ephemeral")]
+    Ephemeral,
+    /// Some other kind of keystore.
+    #[display("other")]
+    Other,
+}
+
+/// A description of a [`Keystore`], as returned by [`Keystore::describe`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct KeystoreDescription {
+    /// The identifier of the keystore.
+    pub id: KeystoreId,
+    /// The kind of the keystore.
+    pub kind: KeystoreKind,
+    /// The directory in which the keystore keeps its entries.
+    ///
+    /// `None` if the keystore is not stored on disk, or if its location is unknown.
+    pub location: Option<PathBuf>,
+    /// The number of entries (keys and certificates) in the keystore.
+    pub n_keys: usize,
+}
+
+impl KeystoreDescription {
+    /// Create a new `KeystoreDescription`.
+    pub fn new(
+        id: KeystoreId,
+        kind: KeystoreKind,
+        location: Option<PathBuf>,
+        n_keys: usize,
+    ) -> Self {
+        Self {
+            id,
+            kind,
+            location,
+            n_keys,
+        }
+    }
 }
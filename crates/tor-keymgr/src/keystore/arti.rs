@@ -4,29 +4,44 @@
 
 pub(crate) mod certs;
 pub(crate) mod err;
+pub(crate) mod naming;
 pub(crate) mod ssh;
 
-use std::io::{self, ErrorKind};
-use std::path::Path;
+use std::collections::HashSet;
+use std::io::{self, ErrorKind, Read as _, Write as _};
+use std::path::{Path, PathBuf};
 use std::result::Result as StdResult;
 use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 
 use crate::keystore::fs_utils::{checked_op, FilesystemAction, FilesystemError, RelKeyPath};
-use crate::keystore::{EncodableItem, ErasedKey, KeySpecifier, Keystore};
+use crate::keystore::{
+    EncodableItem, ErasedKey, KeySpecifier, Keystore, KeystoreDescription, KeystoreKind,
+    KeystoreObserver, KeystoreOperation,
+};
 use crate::{
-    arti_path, ArtiPath, ArtiPathUnavailableError, KeyPath, KeystoreId, Result, UnknownKeyTypeError,
+    arti_path, ArtiPath, ArtiPathUnavailableError, KeyPath, KeystoreCorruptionError, KeystoreId,
+    Result, UnknownKeyTypeError,
 };
 use certs::UnparsedCert;
 use err::ArtiNativeKeystoreError;
+use naming::{DefaultFileNamingScheme, FileNamingScheme};
 use ssh::UnparsedOpenSshKey;
 
+use digest::Digest as _;
+use filetime::FileTime;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
 use fs_mistrust::{CheckedDir, Mistrust};
 use itertools::Itertools;
-use tor_error::internal;
+use tor_error::{internal, ErrorReport as _};
+use tracing::warn;
 use walkdir::WalkDir;
 
 use tor_basic_utils::PathExt as _;
 use tor_key_forge::{CertData, KeystoreItem, KeystoreItemType};
+use tor_llcrypto::d::Sha256;
 
 /// The Arti key store.
 ///
@@ -47,6 +62,75 @@ use tor_key_forge::{CertData, KeystoreItem, KeystoreItemType};
 /// [algorithm name]: https://www.iana.org/assignments/ssh-parameters/ssh-parameters.xhtml#ssh-parameters-19
 /// [RFC4251 § 6]: https://www.rfc-editor.org/rfc/rfc4251.html#section-6
 /// [SSH protocol extensions]: https://spec.torproject.org/ssh-protocols.html
+///
+/// # File names
+///
+/// By default, each entry is stored in a file whose path, relative to the keystore directory,
+/// is its [`ArtiPath`] followed by an extension indicating its type
+/// (see [`DefaultFileNamingScheme`]).
+/// A different [`FileNamingScheme`] can be set with
+/// [`with_naming_scheme`](ArtiNativeKeystore::with_naming_scheme).
+///
+/// # Permissions
+///
+/// On unix platforms, [`insert`](Keystore::insert) creates key files with mode `0o600`
+/// and directories with mode `0o700`.
+///
+/// In addition to the checks performed by the [`Mistrust`] the keystore was created with,
+/// [`get`](Keystore::get) and [`list`](Keystore::list) check that
+/// the keystore directory and the key files are not readable by the group or by other users.
+/// What happens if they are is controlled by the keystore's [`InsecurePermissionsPolicy`].
+/// In [`Warn`](InsecurePermissionsPolicy::Warn) mode, we only warn once about any given path.
+///
+/// These checks are skipped entirely if the [`Mistrust`] is disabled
+/// (for example, with `ARTI_FS_DISABLE_PERMISSION_CHECKS`).
+///
+/// # Quarantine
+///
+/// By default, [`list`](Keystore::list) fails if the keystore contains
+/// a file whose path is not a valid [`ArtiPath`].
+/// If quarantine is enabled (see [`with_quarantine`](ArtiNativeKeystore::with_quarantine)),
+/// such files are instead moved into the `quarantine/` subdirectory of the keystore,
+/// a warning is logged, and the rest of the keys are listed as usual.
+/// The top-level `quarantine/` subdirectory is never searched for keys,
+/// whether or not quarantine is enabled,
+/// so its name is reserved and must not be used for keys.
+///
+/// Quarantined entries stay in the `quarantine/` subdirectory until they are removed by hand,
+/// and [`quarantined`](Keystore::quarantined) lists all of them,
+/// including the ones quarantined by previous runs of Arti.
+///
+/// # Checksums
+///
+/// Whenever it writes an entry, [`insert`](Keystore::insert) also stores
+/// the SHA-256 digest of the entry's contents in a file next to it,
+/// whose name is that of the entry followed by `.sha256`.
+/// [`get`](Keystore::get) and [`verify_all`](Keystore::verify_all) check
+/// the contents of each entry against its digest,
+/// and report a [`KeystoreCorruptionError::ChecksumMismatch`] if they differ.
+///
+/// Entries with no stored digest (for example, ones written by older versions of Arti)
+/// are not checked.
+/// An entry that is replaced by something other than the keystore
+/// (for example, restored from a backup by hand) is reported as corrupt,
+/// unless its digest file is replaced or removed along with it.
+/// Inserting the entry again stores a fresh digest.
+///
+/// # Compression
+///
+/// If compression is enabled (see [`with_compression`](ArtiNativeKeystore::with_compression)),
+/// [`insert`](Keystore::insert) stores entries larger than the configured threshold
+/// compressed with zlib, after a marker that identifies them as such.
+/// [`get`](Keystore::get) recognizes the marker, and decompresses the entry transparently.
+/// Entries without the marker (including all those written while compression was disabled)
+/// are read as before, so compression can be enabled or disabled on an existing keystore.
+///
+/// The checksum of a compressed entry is that of its compressed form.
+///
+/// # Auditing
+///
+/// A [`KeystoreObserver`] set with [`with_observer`](ArtiNativeKeystore::with_observer)
+/// is told about every [`Keystore`] operation on this keystore, and whether it succeeded.
 #[derive(Debug)]
 pub struct ArtiNativeKeystore {
     /// The root of the key store.
@@ -55,6 +139,54 @@ pub struct ArtiNativeKeystore {
     keystore_dir: CheckedDir,
     /// The unique identifier of this instance.
     id: KeystoreId,
+    /// What to do if we find a key file or directory that is readable by other users.
+    insecure_perms: InsecurePermissionsPolicy,
+    /// Whether to check the permissions of key files and directories at all.
+    ///
+    /// This is false if the [`Mistrust`] we were created with is disabled.
+    check_perms: bool,
+    /// The paths we have already warned about being readable by other users.
+    warned_insecure: Mutex<HashSet<PathBuf>>,
+    /// Whether to move entries with invalid paths out of the way, rather than failing.
+    quarantine: bool,
+    /// The observer to notify of every access to this keystore, if any.
+    observer: Option<Arc<dyn KeystoreObserver>>,
+    /// The scheme that maps entries to the files that hold them.
+    naming: Arc<dyn FileNamingScheme>,
+    /// The size (in bytes) above which we compress entries, if compression is enabled.
+    compression_threshold: Option<usize>,
+}
+
+/// The subdirectory of the keystore into which we move corrupt entries.
+const QUARANTINE_DIR: &str = "quarantine";
+
+/// The extension we append to the name of an entry to get the name of the file
+/// holding its checksum.
+const CHECKSUM_EXTENSION: &str = "sha256";
+
+/// The marker at the start of a compressed entry.
+///
+/// Neither OpenSSH keys (which are text) nor our certificates (which start with a version byte)
+/// can start with a NUL byte, so no uncompressed entry is ever mistaken for a compressed one.
+const COMPRESSED_MARKER: &[u8] = b"\0arti-zlib\0";
+
+/// The largest size we will decompress a compressed entry to.
+///
+/// This protects us from compressed entries that would decompress to an enormous size.
+const MAX_DECOMPRESSED_LEN: u64 = 16 * 1024 * 1024;
+
+/// What an [`ArtiNativeKeystore`] should do if it finds a key file or directory
+/// that is readable by the group or by other users.
+///
+/// These checks only apply on unix platforms.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InsecurePermissionsPolicy {
+    /// Log a warning, but otherwise allow the access.
+    #[default]
+    Warn,
+    /// Refuse the access, and return an error.
+    Reject,
 }
 
 impl ArtiNativeKeystore {
@@ -82,7 +214,168 @@ impl ArtiNativeKeystore {
 
         // TODO: load the keystore ID from config.
         let id = KeystoreId::from_str("arti")?;
-        Ok(Self { keystore_dir, id })
+        Ok(Self {
+            keystore_dir,
+            id,
+            insecure_perms: InsecurePermissionsPolicy::default(),
+            check_perms: !mistrust.is_disabled(),
+            warned_insecure: Mutex::new(HashSet::new()),
+            quarantine: false,
+            observer: None,
+            naming: Arc::new(DefaultFileNamingScheme),
+            compression_threshold: None,
+        })
+    }
+
+    /// Set the [`InsecurePermissionsPolicy`] of this keystore.
+    ///
+    /// By default, keys with overly permissive permissions cause a warning to be logged.
+    pub fn with_insecure_permissions_policy(mut self, policy: InsecurePermissionsPolicy) -> Self {
+        self.insecure_perms = policy;
+        self
+    }
+
+    /// Enable or disable quarantining of corrupt entries.
+    ///
+    /// This is disabled by default, since it moves files around inside the keystore.
+    /// See the [type-level documentation](ArtiNativeKeystore#quarantine) for details.
+    pub fn with_quarantine(mut self, enabled: bool) -> Self {
+        self.quarantine = enabled;
+        self
+    }
+
+    /// Set the [`KeystoreObserver`] to notify of every access to this keystore.
+    ///
+    /// By default, there is no observer.
+    pub fn with_observer(mut self, observer: Arc<dyn KeystoreObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Set the [`FileNamingScheme`] that decides where each entry of this keystore is stored.
+    ///
+    /// By default, the [`DefaultFileNamingScheme`] is used.
+    ///
+    /// Entries written using one scheme can't be found using another,
+    /// so this must not be changed for an existing keystore.
+    pub fn with_naming_scheme(mut self, naming: Arc<dyn FileNamingScheme>) -> Self {
+        self.naming = naming;
+        self
+    }
+
+    /// Compress entries larger than `threshold` bytes when storing them.
+    ///
+    /// This is disabled by default.
+    /// Entries are only stored compressed if that makes them smaller.
+    /// See the [type-level documentation](ArtiNativeKeystore#compression) for details.
+    pub fn with_compression(mut self, threshold: usize) -> Self {
+        self.compression_threshold = Some(threshold);
+        self
+    }
+
+    /// List the entries of this keystore whose path doesn't start with a known prefix.
+    ///
+    /// This returns the subset of [`list`](Keystore::list) whose first
+    /// [`ArtiPath`] component is not the `prefix` of any [`KeySpecifier`] type
+    /// Arti knows about (such as `relay`, or `hss`).
+    /// Such entries are probably misplaced or foreign files,
+    /// which no part of Arti will ever use.
+    ///
+    /// Only the types that derive `KeySpecifier` with a toplevel
+    /// `#[deftly(role = "...")]` are taken into account.
+    pub fn list_unknown(&self) -> Result<Vec<(KeyPath, KeystoreItemType)>> {
+        Ok(self
+            .list()?
+            .into_iter()
+            .filter(|(path, _)| match path {
+                KeyPath::Arti(path) => {
+                    let prefix = path
+                        .as_str()
+                        .split(arti_path::PATH_SEP)
+                        .next()
+                        .unwrap_or_default();
+                    !crate::key_specifier::is_known_key_specifier_prefix(prefix)
+                }
+                // We never return CTor paths from list().
+                KeyPath::CTor(_) => false,
+            })
+            .collect())
+    }
+
+    /// Check that the file or directory at the absolute path `path`
+    /// is not readable by the group or by other users.
+    ///
+    /// If it is, log a warning (at most once per path) or return an error,
+    /// depending on our [`InsecurePermissionsPolicy`].
+    ///
+    /// Does nothing if our [`Mistrust`] was disabled.
+    #[cfg(unix)]
+    fn check_permissions(&self, path: &Path) -> StdResult<(), ArtiNativeKeystoreError> {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        if !self.check_perms {
+            return Ok(());
+        }
+
+        let meta = path.symlink_metadata().map_err(|err| FilesystemError::Io {
+            action: FilesystemAction::Read,
+            path: path.into(),
+            err: err.into(),
+        })?;
+
+        let mode = meta.permissions().mode() & 0o777;
+        if mode & 0o044 == 0 {
+            return Ok(());
+        }
+
+        match self.insecure_perms {
+            InsecurePermissionsPolicy::Warn => {
+                let first_time = self
+                    .warned_insecure
+                    .lock()
+                    .expect("lock poisoned")
+                    .insert(path.into());
+                if first_time {
+                    warn!(
+                        "{} is readable by other users (mode {mode:o})",
+                        path.display_lossy()
+                    );
+                }
+                Ok(())
+            }
+            InsecurePermissionsPolicy::Reject => {
+                Err(ArtiNativeKeystoreError::InsecurePermissions {
+                    path: path.into(),
+                    mode,
+                })
+            }
+        }
+    }
+
+    /// Check that the file or directory at the absolute path `path`
+    /// is not readable by other users.
+    ///
+    /// This is a no-op on non-unix platforms.
+    #[cfg(not(unix))]
+    #[allow(clippy::unnecessary_wraps)]
+    fn check_permissions(&self, _path: &Path) -> StdResult<(), ArtiNativeKeystoreError> {
+        Ok(())
+    }
+
+    /// Run `op`, which performs `operation` on the key identified by `key_spec` (if any),
+    /// and tell our [`KeystoreObserver`] (if we have one) whether it succeeded.
+    fn observe<T>(
+        &self,
+        operation: KeystoreOperation,
+        key_spec: Option<&dyn KeySpecifier>,
+        op: impl FnOnce() -> Result<T>,
+    ) -> Result<T> {
+        let res = op();
+        if let Some(observer) = &self.observer {
+            let path = key_spec.and_then(|key_spec| key_spec.arti_path().ok());
+            observer.on_access(operation, path.as_ref(), res.is_ok());
+        }
+        res
     }
 
     /// The path on disk of the key with the specified identity and type, relative to
@@ -92,7 +385,100 @@ impl ArtiNativeKeystore {
         key_spec: &dyn KeySpecifier,
         item_type: &KeystoreItemType,
     ) -> StdResult<RelKeyPath, ArtiPathUnavailableError> {
-        RelKeyPath::arti(&self.keystore_dir, key_spec, item_type)
+        RelKeyPath::arti(&self.keystore_dir, &*self.naming, key_spec, item_type)
+    }
+}
+
+/// Compress `item_bytes`, and prefix the result with [`COMPRESSED_MARKER`].
+///
+/// If that wouldn't make the entry any smaller, return `item_bytes` unchanged.
+fn compress_entry(item_bytes: Vec<u8>) -> io::Result<Vec<u8>> {
+    let mut encoder = ZlibEncoder::new(COMPRESSED_MARKER.to_vec(), flate2::Compression::default());
+    encoder.write_all(&item_bytes)?;
+    let compressed = encoder.finish()?;
+    Ok(if compressed.len() < item_bytes.len() {
+        compressed
+    } else {
+        item_bytes
+    })
+}
+
+/// If `contents` starts with [`COMPRESSED_MARKER`], decompress the rest of it.
+///
+/// Otherwise, return `contents` unchanged.
+fn decompress_entry(contents: Vec<u8>) -> io::Result<Vec<u8>> {
+    let Some(compressed) = contents.strip_prefix(COMPRESSED_MARKER) else {
+        return Ok(contents);
+    };
+    let mut decompressed = vec![];
+    ZlibDecoder::new(compressed)
+        .take(MAX_DECOMPRESSED_LEN + 1)
+        .read_to_end(&mut decompressed)?;
+    if decompressed.len() as u64 > MAX_DECOMPRESSED_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "compressed entry is too large",
+        ));
+    }
+    Ok(decompressed)
+}
+
+/// Encode `item` the way it is stored in the Arti keystore (before any compression).
+///
+/// Keys are encoded in OpenSSH format, and certificates in their native encoding.
+pub(crate) fn encode_item(item: KeystoreItem) -> Result<Vec<u8>> {
+    let item_type = item.item_type()?;
+    let item_bytes = match item {
+        KeystoreItem::Key(key) => {
+            // TODO (#1095): decide what information, if any, to put in the comment
+            let comment = "";
+            key.to_openssh_string(comment)?.into_bytes()
+        }
+        KeystoreItem::Cert(cert) => match cert {
+            CertData::TorEd25519Cert(cert) => cert.into(),
+            _ => return Err(internal!("unknown cert type {item_type:?}").into()),
+        },
+        _ => return Err(internal!("unknown item type {item_type:?}").into()),
+    };
+
+    Ok(item_bytes)
+}
+
+/// Decode an item of type `item_type`, encoded with [`encode_item`].
+///
+/// `path` is where the item was read from, and is only used in error messages.
+pub(crate) fn decode_item(
+    contents: Vec<u8>,
+    item_type: &KeystoreItemType,
+    path: PathBuf,
+) -> Result<ErasedKey> {
+    match item_type {
+        KeystoreItemType::Key(key_type) => {
+            let contents = String::from_utf8(contents).map_err(|_| {
+                let err = io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "OpenSSH key is not valid UTF-8".to_string(),
+                );
+
+                ArtiNativeKeystoreError::Filesystem(FilesystemError::Io {
+                    action: FilesystemAction::Read,
+                    path: path.clone(),
+                    err: err.into(),
+                })
+            })?;
+
+            UnparsedOpenSshKey::new(contents, path).parse_ssh_format_erased(key_type)
+        }
+        KeystoreItemType::Cert(cert_type) => {
+            UnparsedCert::new(contents, path).parse_certificate_erased(cert_type)
+        }
+        KeystoreItemType::Unknown { arti_extension } => Err(
+            ArtiNativeKeystoreError::UnknownKeyType(UnknownKeyTypeError {
+                arti_extension: arti_extension.clone(),
+            })
+            .into(),
+        ),
+        _ => Err(internal!("unknown item type {item_type:?}").into()),
     }
 }
 
@@ -119,32 +505,11 @@ impl Keystore for ArtiNativeKeystore {
     }
 
     fn contains(&self, key_spec: &dyn KeySpecifier, item_type: &KeystoreItemType) -> Result<bool> {
-        let path = rel_path_if_supported!(self.rel_path(key_spec, item_type), Ok(false));
-
-        let meta = match checked_op!(metadata, path) {
-            Ok(meta) => meta,
-            Err(fs_mistrust::Error::NotFound(_)) => return Ok(false),
-            Err(e) => {
-                return Err(FilesystemError::FsMistrust {
-                    action: FilesystemAction::Read,
-                    path: path.rel_path_unchecked().into(),
-                    err: e.into(),
-                })
-                .map_err(|e| ArtiNativeKeystoreError::Filesystem(e).into());
-            }
-        };
+        self.observe(KeystoreOperation::Contains, Some(key_spec), || {
+            let path = rel_path_if_supported!(self.rel_path(key_spec, item_type), Ok(false));
 
-        // The path exists, now check that it's actually a file and not a directory or symlink.
-        if meta.is_file() {
-            Ok(true)
-        } else {
-            Err(
-                ArtiNativeKeystoreError::Filesystem(FilesystemError::NotARegularFile(
-                    path.rel_path_unchecked().into(),
-                ))
-                .into(),
-            )
-        }
+            Ok(self.entry_metadata(&path)?.is_some())
+        })
     }
 
     fn get(
@@ -152,95 +517,112 @@ impl Keystore for ArtiNativeKeystore {
         key_spec: &dyn KeySpecifier,
         item_type: &KeystoreItemType,
     ) -> Result<Option<ErasedKey>> {
-        let path = rel_path_if_supported!(self.rel_path(key_spec, item_type), Ok(None));
+        self.observe(KeystoreOperation::Get, Some(key_spec), || {
+            let path = rel_path_if_supported!(self.rel_path(key_spec, item_type), Ok(None));
 
-        let inner = match checked_op!(read, path) {
-            Err(fs_mistrust::Error::NotFound(_)) => return Ok(None),
-            res => res
-                .map_err(|err| FilesystemError::FsMistrust {
+            let inner = match checked_op!(read, path) {
+                Err(fs_mistrust::Error::NotFound(_)) => return Ok(None),
+                res => res
+                    .map_err(|err| FilesystemError::FsMistrust {
+                        action: FilesystemAction::Read,
+                        path: path.rel_path_unchecked().into(),
+                        err: err.into(),
+                    })
+                    .map_err(ArtiNativeKeystoreError::Filesystem)?,
+            };
+
+            let abs_path = path
+                .checked_path()
+                .map_err(ArtiNativeKeystoreError::Filesystem)?;
+
+            self.check_permissions(self.keystore_dir.as_path())?;
+            self.check_permissions(&abs_path)?;
+
+            if !self.checksum_matches(&path, &inner)? {
+                let arti_path = key_spec
+                    .arti_path()
+                    .map_err(|e| tor_error::internal!("invalid ArtiPath: {e}"))?;
+                return Err(KeystoreCorruptionError::ChecksumMismatch {
+                    path: arti_path.into(),
+                }
+                .into());
+            }
+
+            let inner = decompress_entry(inner).map_err(|err| {
+                ArtiNativeKeystoreError::Filesystem(FilesystemError::Io {
                     action: FilesystemAction::Read,
-                    path: path.rel_path_unchecked().into(),
+                    path: abs_path.clone(),
                     err: err.into(),
                 })
-                .map_err(ArtiNativeKeystoreError::Filesystem)?,
-        };
+            })?;
 
-        let abs_path = path
-            .checked_path()
-            .map_err(ArtiNativeKeystoreError::Filesystem)?;
+            decode_item(inner, item_type, abs_path).map(Some)
+        })
+    }
 
-        match item_type {
-            KeystoreItemType::Key(key_type) => {
-                let inner = String::from_utf8(inner).map_err(|_| {
-                    let err = io::Error::new(
-                        io::ErrorKind::InvalidData,
-                        "OpenSSH key is not valid UTF-8".to_string(),
-                    );
+    fn insert(&self, key: &dyn EncodableItem, key_spec: &dyn KeySpecifier) -> Result<()> {
+        self.observe(KeystoreOperation::Insert, Some(key_spec), || {
+            let keystore_item = key.as_keystore_item()?;
+            let item_type = keystore_item.item_type()?;
+            let path = match self.rel_path(key_spec, &item_type) {
+                Ok(path) => path,
+                Err(ArtiPathUnavailableError::ArtiPathUnavailable) => {
+                    return Err(ArtiNativeKeystoreError::NoArtiPath.into());
+                }
+                Err(e) => return Err(tor_error::internal!("{e}").into()),
+            };
+            let unchecked_path = path.rel_path_unchecked();
+
+            // Create the parent directories as needed
+            if let Some(parent) = unchecked_path.parent() {
+                self.keystore_dir
+                    .make_directory(parent)
+                    .map_err(|err| FilesystemError::FsMistrust {
+                        action: FilesystemAction::Write,
+                        path: parent.to_path_buf(),
+                        err: err.into(),
+                    })
+                    .map_err(ArtiNativeKeystoreError::Filesystem)?;
+            }
 
-                    ArtiNativeKeystoreError::Filesystem(FilesystemError::Io {
-                        action: FilesystemAction::Read,
-                        path: abs_path.clone(),
+            let item_bytes = encode_item(keystore_item)?;
+
+            let item_bytes = match self.compression_threshold {
+                Some(threshold) if item_bytes.len() > threshold => compress_entry(item_bytes)
+                    .map_err(|err| FilesystemError::Io {
+                        action: FilesystemAction::Write,
+                        path: unchecked_path.into(),
                         err: err.into(),
                     })
-                })?;
+                    .map_err(ArtiNativeKeystoreError::Filesystem)?,
+                _ => item_bytes,
+            };
 
-                UnparsedOpenSshKey::new(inner, abs_path)
-                    .parse_ssh_format_erased(key_type)
-                    .map(Some)
-            }
-            KeystoreItemType::Cert(cert_type) => UnparsedCert::new(inner, abs_path)
-                .parse_certificate_erased(cert_type)
-                .map(Some),
-            KeystoreItemType::Unknown { arti_extension } => Err(
-                ArtiNativeKeystoreError::UnknownKeyType(UnknownKeyTypeError {
-                    arti_extension: arti_extension.clone(),
-                })
-                .into(),
-            ),
-            _ => Err(internal!("unknown item type {item_type:?}").into()),
-        }
-    }
+            // Remove the checksum of the old entry (if any) first,
+            // so that we never leave a stale checksum next to the new entry.
+            self.remove_checksum(&path)?;
 
-    fn insert(&self, key: &dyn EncodableItem, key_spec: &dyn KeySpecifier) -> Result<()> {
-        let keystore_item = key.as_keystore_item()?;
-        let item_type = keystore_item.item_type()?;
-        let path = self
-            .rel_path(key_spec, &item_type)
-            .map_err(|e| tor_error::internal!("{e}"))?;
-        let unchecked_path = path.rel_path_unchecked();
-
-        // Create the parent directories as needed
-        if let Some(parent) = unchecked_path.parent() {
-            self.keystore_dir
-                .make_directory(parent)
+            checked_op!(write_and_replace, path, &item_bytes)
                 .map_err(|err| FilesystemError::FsMistrust {
                     action: FilesystemAction::Write,
-                    path: parent.to_path_buf(),
+                    path: unchecked_path.into(),
                     err: err.into(),
                 })
                 .map_err(ArtiNativeKeystoreError::Filesystem)?;
-        }
 
-        let item_bytes: Vec<u8> = match keystore_item {
-            KeystoreItem::Key(key) => {
-                // TODO (#1095): decide what information, if any, to put in the comment
-                let comment = "";
-                key.to_openssh_string(comment)?.into_bytes()
-            }
-            KeystoreItem::Cert(cert) => match cert {
-                CertData::TorEd25519Cert(cert) => cert.into(),
-                _ => return Err(internal!("unknown cert type {item_type:?}").into()),
-            },
-            _ => return Err(internal!("unknown item type {item_type:?}").into()),
-        };
-
-        Ok(checked_op!(write_and_replace, path, item_bytes)
+            let checksum_path = path.with_added_extension(CHECKSUM_EXTENSION);
+            Ok(checked_op!(
+                write_and_replace,
+                checksum_path,
+                Sha256::digest(&item_bytes)
+            )
             .map_err(|err| FilesystemError::FsMistrust {
                 action: FilesystemAction::Write,
-                path: unchecked_path.into(),
+                path: checksum_path.rel_path_unchecked().into(),
                 err: err.into(),
             })
             .map_err(ArtiNativeKeystoreError::Filesystem)?)
+        })
     }
 
     fn remove(
@@ -248,45 +630,260 @@ impl Keystore for ArtiNativeKeystore {
         key_spec: &dyn KeySpecifier,
         item_type: &KeystoreItemType,
     ) -> Result<Option<()>> {
-        let rel_path = self
-            .rel_path(key_spec, item_type)
-            .map_err(|e| tor_error::internal!("{e}"))?;
-
-        match checked_op!(remove_file, rel_path) {
-            Ok(()) => Ok(Some(())),
-            Err(fs_mistrust::Error::NotFound(_)) => Ok(None),
-            Err(e) => Err(ArtiNativeKeystoreError::Filesystem(
-                FilesystemError::FsMistrust {
-                    action: FilesystemAction::Remove,
-                    path: rel_path.rel_path_unchecked().into(),
+        self.observe(KeystoreOperation::Remove, Some(key_spec), || {
+            let rel_path = self
+                .rel_path(key_spec, item_type)
+                .map_err(|e| tor_error::internal!("{e}"))?;
+
+            match checked_op!(remove_file, rel_path) {
+                Ok(()) => {
+                    self.remove_checksum(&rel_path)?;
+                    Ok(Some(()))
+                }
+                Err(fs_mistrust::Error::NotFound(_)) => Ok(None),
+                Err(e) => Err(ArtiNativeKeystoreError::Filesystem(
+                    FilesystemError::FsMistrust {
+                        action: FilesystemAction::Remove,
+                        path: rel_path.rel_path_unchecked().into(),
+                        err: e.into(),
+                    },
+                ))?,
+            }
+        })
+    }
+
+    fn touch(
+        &self,
+        key_spec: &dyn KeySpecifier,
+        item_type: &KeystoreItemType,
+    ) -> Result<Option<()>> {
+        self.observe(KeystoreOperation::Touch, Some(key_spec), || {
+            let path = rel_path_if_supported!(self.rel_path(key_spec, item_type), Ok(None));
+
+            if self.entry_metadata(&path)?.is_none() {
+                return Ok(None);
+            }
+
+            let abs_path = path
+                .checked_path()
+                .map_err(ArtiNativeKeystoreError::Filesystem)?;
+            match filetime::set_file_mtime(&abs_path, FileTime::now()) {
+                Ok(()) => Ok(Some(())),
+                // The entry was removed after we checked for it.
+                Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(ArtiNativeKeystoreError::Filesystem(FilesystemError::Io {
+                    action: FilesystemAction::Write,
+                    path: abs_path,
                     err: e.into(),
-                },
-            ))?,
-        }
+                }))?,
+            }
+        })
+    }
+
+    fn modified(
+        &self,
+        key_spec: &dyn KeySpecifier,
+        item_type: &KeystoreItemType,
+    ) -> Result<Option<SystemTime>> {
+        let path = rel_path_if_supported!(self.rel_path(key_spec, item_type), Ok(None));
+
+        let Some(meta) = self.entry_metadata(&path)? else {
+            return Ok(None);
+        };
+        let modified = meta
+            .modified()
+            .map_err(|err| FilesystemError::Io {
+                action: FilesystemAction::Read,
+                path: path.rel_path_unchecked().into(),
+                err: err.into(),
+            })
+            .map_err(ArtiNativeKeystoreError::Filesystem)?;
+        Ok(Some(modified))
     }
 
     fn list(&self) -> Result<Vec<(KeyPath, KeystoreItemType)>> {
+        self.observe(KeystoreOperation::List, None, || self.list_filtered(None))
+    }
+
+    fn list_by_type(&self, item_type: &KeystoreItemType) -> Result<Vec<KeyPath>> {
+        self.observe(KeystoreOperation::List, None, || {
+            Ok(self
+                .list_filtered(Some(item_type))?
+                .into_iter()
+                .map(|(path, _)| path)
+                .collect())
+        })
+    }
+
+    fn list_page(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<(KeyPath, KeystoreItemType)>, bool)> {
+        self.observe(KeystoreOperation::List, None, || {
+            // We walk the keystore in a deterministic order, and stop as soon as we have
+            // found the entry after the last one we want.
+            //
+            // Errors are propagated even if they occur before the requested page,
+            // just like `list` would, and they don't count towards `offset`:
+            // otherwise, the pages wouldn't line up with the output of `list`.
+            let mut skipped = 0;
+            let mut page = vec![];
+            for entry in self.entries(None) {
+                let entry = entry?;
+                if skipped < offset {
+                    skipped += 1;
+                    continue;
+                }
+                if page.len() == limit {
+                    return Ok((page, true));
+                }
+                page.push(entry);
+            }
+            Ok((page, false))
+        })
+    }
+
+    fn describe(&self) -> Result<KeystoreDescription> {
+        Ok(KeystoreDescription::new(
+            self.id.clone(),
+            KeystoreKind::ArtiNative,
+            Some(self.keystore_dir.as_path().to_owned()),
+            self.list()?.len(),
+        ))
+    }
+
+    fn verify_all(&self) -> Result<Vec<KeyPath>> {
+        let mut corrupt = vec![];
+        for (key_path, item_type) in self.list()? {
+            let path = self
+                .rel_path(&key_path, &item_type)
+                .map_err(|e| tor_error::internal!("invalid ArtiPath: {e}"))?;
+            let contents = match checked_op!(read, path) {
+                Ok(contents) => contents,
+                // The entry was removed after we listed it.
+                Err(fs_mistrust::Error::NotFound(_)) => continue,
+                Err(err) => {
+                    return Err(
+                        ArtiNativeKeystoreError::Filesystem(FilesystemError::FsMistrust {
+                            action: FilesystemAction::Read,
+                            path: path.rel_path_unchecked().into(),
+                            err: err.into(),
+                        })
+                        .into(),
+                    )
+                }
+            };
+            if !self.checksum_matches(&path, &contents)? {
+                corrupt.push(key_path);
+            }
+        }
+        Ok(corrupt)
+    }
+
+    fn quarantined(&self) -> Result<Vec<PathBuf>> {
+        match self.keystore_dir.metadata(QUARANTINE_DIR) {
+            Ok(_) => {}
+            Err(fs_mistrust::Error::NotFound(_)) => return Ok(vec![]),
+            Err(err) => {
+                return Err(
+                    ArtiNativeKeystoreError::Filesystem(FilesystemError::FsMistrust {
+                        action: FilesystemAction::Read,
+                        path: QUARANTINE_DIR.into(),
+                        err: err.into(),
+                    })
+                    .into(),
+                )
+            }
+        }
+
+        let quarantine_dir = self.keystore_dir.as_path().join(QUARANTINE_DIR);
+        WalkDir::new(&quarantine_dir)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|entry| match entry {
+                Ok(entry) if entry.file_type().is_dir() => None,
+                Ok(entry) => Some(Ok(entry.into_path())),
+                Err(e) => Some(Err(ArtiNativeKeystoreError::Filesystem(walkdir_error(
+                    &quarantine_dir,
+                    e,
+                ))
+                .into())),
+            })
+            .collect()
+    }
+}
+
+impl ArtiNativeKeystore {
+    /// Return the metadata of the entry at `path`, or `None` if there is no such entry.
+    ///
+    /// Returns an error if `path` is not a regular file.
+    fn entry_metadata(&self, path: &RelKeyPath) -> Result<Option<std::fs::Metadata>> {
+        let meta = match checked_op!(metadata, path) {
+            Ok(meta) => meta,
+            Err(fs_mistrust::Error::NotFound(_)) => return Ok(None),
+            Err(e) => {
+                return Err(FilesystemError::FsMistrust {
+                    action: FilesystemAction::Read,
+                    path: path.rel_path_unchecked().into(),
+                    err: e.into(),
+                })
+                .map_err(|e| ArtiNativeKeystoreError::Filesystem(e).into());
+            }
+        };
+
+        // The path exists, now check that it's actually a file and not a directory or symlink.
+        if meta.is_file() {
+            Ok(Some(meta))
+        } else {
+            Err(
+                ArtiNativeKeystoreError::Filesystem(FilesystemError::NotARegularFile(
+                    path.rel_path_unchecked().into(),
+                ))
+                .into(),
+            )
+        }
+    }
+
+    /// List the keys in this keystore, optionally restricting to those of type `want_type`.
+    ///
+    /// Entries of any other type are skipped based on their file extension alone,
+    /// without validating the rest of their path.
+    fn list_filtered(
+        &self,
+        want_type: Option<&KeystoreItemType>,
+    ) -> Result<Vec<(KeyPath, KeystoreItemType)>> {
+        self.entries(want_type).collect()
+    }
+
+    /// Return an iterator over the keys in this keystore,
+    /// optionally restricting to those of type `want_type`.
+    ///
+    /// The keystore is walked lazily, in a deterministic order:
+    /// the entries of each directory are visited in order of their file names.
+    ///
+    /// See [`list_filtered`](ArtiNativeKeystore::list_filtered).
+    fn entries<'a>(
+        &'a self,
+        want_type: Option<&'a KeystoreItemType>,
+    ) -> impl Iterator<Item = Result<(KeyPath, KeystoreItemType)>> + 'a {
         WalkDir::new(self.keystore_dir.as_path())
+            .sort_by_file_name()
             .into_iter()
+            .filter_entry(|entry| {
+                // Don't look for keys among the entries we have quarantined,
+                // even if quarantine has since been disabled.
+                !(entry.depth() == 1 && entry.file_name() == QUARANTINE_DIR)
+            })
             .map(|entry| {
                 let entry = entry
-                    .map_err(|e| {
-                        let msg = e.to_string();
-                        FilesystemError::Io {
-                            action: FilesystemAction::Read,
-                            path: self.keystore_dir.as_path().into(),
-                            err: e
-                                .into_io_error()
-                                .unwrap_or_else(|| {
-                                    io::Error::new(ErrorKind::Other, msg.to_string())
-                                })
-                                .into(),
-                        }
-                    })
+                    .map_err(|e| walkdir_error(self.keystore_dir.as_path(), e))
                     .map_err(ArtiNativeKeystoreError::Filesystem)?;
 
                 let path = entry.path();
 
+                self.check_permissions(path)?;
+
                 // Skip over directories as they won't be valid arti-paths
                 //
                 // TODO (#1118): provide a mechanism for warning about unrecognized keys?
@@ -294,6 +891,14 @@ impl Keystore for ArtiNativeKeystore {
                     return Ok(None);
                 }
 
+                // Skip over the checksums of the entries, which aren't entries themselves.
+                if path
+                    .extension()
+                    .is_some_and(|ext| ext == CHECKSUM_EXTENSION)
+                {
+                    return Ok(None);
+                }
+
                 let path = path
                     .strip_prefix(self.keystore_dir.as_path())
                     .map_err(|_| {
@@ -318,34 +923,139 @@ impl Keystore for ArtiNativeKeystore {
                         .map_err(ArtiNativeKeystoreError::Filesystem)?;
                 }
 
-                let malformed_err = |path: &Path, err| ArtiNativeKeystoreError::MalformedPath {
-                    path: path.into(),
-                    err,
-                };
-
-                let extension = path
-                    .extension()
-                    .ok_or_else(|| malformed_err(path, err::MalformedPathError::NoExtension))?
-                    .to_str()
-                    .ok_or_else(|| malformed_err(path, err::MalformedPathError::Utf8))?;
-
-                let item_type = KeystoreItemType::from(extension);
-                // Strip away the file extension
-                let path = path.with_extension("");
-                // Construct slugs in platform-independent way
-                let slugs = path
-                    .components()
-                    .map(|component| component.as_os_str().to_string_lossy())
-                    .collect::<Vec<_>>()
-                    .join(&arti_path::PATH_SEP.to_string());
-                ArtiPath::new(slugs)
-                    .map(|path| Some((path.into(), item_type)))
-                    .map_err(|e| {
-                        malformed_err(&path, err::MalformedPathError::InvalidArtiPath(e)).into()
-                    })
+                match self.parse_entry(path, want_type) {
+                    Err(e @ ArtiNativeKeystoreError::MalformedPath { .. }) if self.quarantine => {
+                        self.quarantine_entry(path, &e)?;
+                        Ok(None)
+                    }
+                    res => Ok(res?),
+                }
             })
             .flatten_ok()
-            .collect()
+    }
+
+    /// Work out the [`KeyPath`] and type of the key at `path`, relative to the keystore directory.
+    ///
+    /// Returns `Ok(None)` if the key is not of type `want_type`.
+    fn parse_entry(
+        &self,
+        path: &Path,
+        want_type: Option<&KeystoreItemType>,
+    ) -> StdResult<Option<(KeyPath, KeystoreItemType)>, ArtiNativeKeystoreError> {
+        let malformed_err = |path: &Path, err| ArtiNativeKeystoreError::MalformedPath {
+            path: path.into(),
+            err,
+        };
+
+        let item_type = self
+            .naming
+            .item_type(path)
+            .ok_or_else(|| malformed_err(path, err::MalformedPathError::NoItemType))?;
+
+        if want_type.is_some_and(|want| want != &item_type) {
+            return Ok(None);
+        }
+        self.naming
+            .arti_path(path)
+            .map(|arti_path| Some((arti_path.into(), item_type)))
+            .map_err(|e| malformed_err(path, err::MalformedPathError::InvalidArtiPath(e)))
+    }
+
+    /// Check `contents`, the contents of the entry at `path`, against its stored checksum.
+    ///
+    /// Returns `true` if they match, or if there is no stored checksum for the entry.
+    fn checksum_matches(
+        &self,
+        path: &RelKeyPath<'_>,
+        contents: &[u8],
+    ) -> StdResult<bool, ArtiNativeKeystoreError> {
+        let checksum_path = path.with_added_extension(CHECKSUM_EXTENSION);
+        let expected = match checked_op!(read, checksum_path) {
+            Ok(expected) => expected,
+            Err(fs_mistrust::Error::NotFound(_)) => return Ok(true),
+            Err(err) => {
+                return Err(FilesystemError::FsMistrust {
+                    action: FilesystemAction::Read,
+                    path: checksum_path.rel_path_unchecked().into(),
+                    err: err.into(),
+                }
+                .into())
+            }
+        };
+
+        Ok(Sha256::digest(contents).as_slice() == expected.as_slice())
+    }
+
+    /// Remove the stored checksum of the entry at `path`, if there is one.
+    fn remove_checksum(&self, path: &RelKeyPath<'_>) -> StdResult<(), ArtiNativeKeystoreError> {
+        let checksum_path = path.with_added_extension(CHECKSUM_EXTENSION);
+        match checked_op!(remove_file, checksum_path) {
+            Ok(()) | Err(fs_mistrust::Error::NotFound(_)) => Ok(()),
+            Err(err) => Err(FilesystemError::FsMistrust {
+                action: FilesystemAction::Remove,
+                path: checksum_path.rel_path_unchecked().into(),
+                err: err.into(),
+            }
+            .into()),
+        }
+    }
+
+    /// Move the entry at `rel_path` (relative to the keystore directory) into quarantine,
+    /// because of `problem`.
+    fn quarantine_entry(
+        &self,
+        rel_path: &Path,
+        problem: &ArtiNativeKeystoreError,
+    ) -> StdResult<(), ArtiNativeKeystoreError> {
+        let mistrust_err = |path: &Path, e: fs_mistrust::Error| FilesystemError::FsMistrust {
+            action: FilesystemAction::Write,
+            path: path.into(),
+            err: e.into(),
+        };
+
+        let quarantine_dir = self
+            .keystore_dir
+            .make_secure_directory(QUARANTINE_DIR)
+            .map_err(|e| mistrust_err(Path::new(QUARANTINE_DIR), e))?;
+        if let Some(parent) = rel_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            quarantine_dir
+                .make_directory(parent)
+                .map_err(|e| mistrust_err(parent, e))?;
+        }
+        let from = self
+            .keystore_dir
+            .join(rel_path)
+            .map_err(|e| mistrust_err(rel_path, e))?;
+        let to = quarantine_dir
+            .join(rel_path)
+            .map_err(|e| mistrust_err(rel_path, e))?;
+
+        std::fs::rename(&from, &to).map_err(|e| FilesystemError::Io {
+            action: FilesystemAction::Write,
+            path: from.clone(),
+            err: e.into(),
+        })?;
+        warn!(
+            "Moved corrupt keystore entry {} to {}: {}",
+            from.display_lossy(),
+            to.display_lossy(),
+            problem.report()
+        );
+
+        Ok(())
+    }
+}
+
+/// Convert an error that occurred while walking `dir` into a [`FilesystemError`].
+fn walkdir_error(dir: &Path, e: walkdir::Error) -> FilesystemError {
+    let msg = e.to_string();
+    FilesystemError::Io {
+        action: FilesystemAction::Read,
+        path: dir.into(),
+        err: e
+            .into_io_error()
+            .unwrap_or_else(|| io::Error::new(ErrorKind::Other, msg))
+            .into(),
     }
 }
 
@@ -367,17 +1077,21 @@ mod tests {
     use super::*;
     use crate::test_utils::ssh_keys::*;
     use crate::test_utils::sshkeygen_ed25519_strings;
-    use crate::test_utils::{assert_found, TestSpecifier};
-    use crate::KeyPath;
+    use crate::test_utils::{assert_found, TestCTorSpecifier, TestSpecifier};
+    use crate::{ArtiPathSyntaxError, KeyPath};
+    use crate::{CTorPath, CTorServicePath};
     use std::cmp::Ordering;
     use std::fs;
     use std::path::PathBuf;
+    use std::sync::Mutex;
     use std::time::{Duration, SystemTime};
     use tempfile::{tempdir, TempDir};
     use tor_cert::{CertifiedKey, Ed25519Cert};
     use tor_checkable::{SelfSigned, Timebound};
+    use tor_error::HasKind as _;
     use tor_key_forge::{CertType, KeyType, ParsedEd25519Cert};
     use tor_llcrypto::pk::ed25519::{self, Ed25519PublicKey as _};
+    use tor_persist::hsnickname::HsNickname;
 
     #[cfg(unix)]
     use std::os::unix::fs::PermissionsExt;
@@ -469,6 +1183,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn describe() {
+        let (key_store, keystore_dir) = init_keystore(false);
+        let desc = key_store.describe().unwrap();
+        assert_eq!(&desc.id, key_store.id());
+        assert_eq!(desc.kind, KeystoreKind::ArtiNative);
+        assert_eq!(desc.kind.to_string(), "arti-fs");
+        assert_eq!(desc.location.as_deref(), Some(keystore_dir.path()));
+        assert_eq!(desc.n_keys, 0);
+
+        let (key_store, _keystore_dir) = init_keystore(true);
+        assert_eq!(key_store.describe().unwrap().n_keys, 1);
+    }
+
     #[test]
     fn key_path_repr() {
         let (key_store, _) = init_keystore(false);
@@ -535,6 +1263,93 @@ mod tests {
         );
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn insert_perms() {
+        let (key_store, _keystore_dir) = init_keystore(false);
+
+        let mut rng = rand::rng();
+        let key = ed25519::Keypair::generate(&mut rng);
+        key_store.insert(&key, &TestSpecifier::default()).unwrap();
+
+        let key_path = key_path(&key_store, &KeyType::Ed25519Keypair);
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&key_path), 0o600);
+        assert_eq!(mode(key_path.parent().unwrap()), 0o700);
+    }
+
+    #[test]
+    fn insert_no_arti_path() {
+        let (key_store, _keystore_dir) = init_keystore(false);
+
+        let mut rng = rand::rng();
+        let key = ed25519::Keypair::generate(&mut rng);
+        let spec = TestCTorSpecifier(CTorPath::Service {
+            nickname: HsNickname::from_str("allium-cepa").unwrap(),
+            path: CTorServicePath::PublicKey,
+        });
+
+        let err = key_store.insert(&key, &spec).unwrap_err();
+        assert_eq!(err.kind(), tor_error::ErrorKind::BadApiUsage);
+        assert_eq!(
+            err.to_string(),
+            "Key specifier has no ArtiPath; cannot store in Arti keystore"
+        );
+        assert!(key_store.list().unwrap().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn insecure_perms_policy() {
+        use std::os::unix::fs::MetadataExt as _;
+
+        let keystore_dir = tempdir().unwrap();
+        // Trust our own group, so that the Mistrust allows a group-readable key,
+        // and only our own check flags it.
+        let gid = fs::metadata(&keystore_dir).unwrap().gid();
+        let mistrust = Mistrust::builder().trust_group(gid).build().unwrap();
+
+        let new_keystore = |mistrust: &Mistrust, policy| {
+            ArtiNativeKeystore::from_path_and_mistrust(&keystore_dir, mistrust)
+                .unwrap()
+                .with_insecure_permissions_policy(policy)
+        };
+
+        let key_store = new_keystore(&mistrust, InsecurePermissionsPolicy::Reject);
+        let mut rng = rand::rng();
+        let key = ed25519::Keypair::generate(&mut rng);
+        key_store.insert(&key, &TestSpecifier::default()).unwrap();
+
+        let spec = TestSpecifier::default();
+        let item_type: KeystoreItemType = KeyType::Ed25519Keypair.into();
+        assert!(key_store.get(&spec, &item_type).unwrap().is_some());
+        assert_eq!(key_store.list().unwrap().len(), 1);
+
+        // Make the key readable by the group.
+        let key_path = key_path(&key_store, &KeyType::Ed25519Keypair);
+        fs::set_permissions(&key_path, fs::Permissions::from_mode(0o640)).unwrap();
+
+        let err = key_store.get(&spec, &item_type).unwrap_err();
+        assert!(err.to_string().contains("readable by other users"), "{err}");
+        assert!(key_store.list().is_err());
+
+        // In the default (warn) mode, the key is still usable,
+        // and we only warn about it once.
+        let key_store = new_keystore(&mistrust, InsecurePermissionsPolicy::Warn);
+        assert!(key_store.get(&spec, &item_type).unwrap().is_some());
+        assert_eq!(key_store.list().unwrap().len(), 1);
+        assert_eq!(key_store.list().unwrap().len(), 1);
+        assert_eq!(key_store.warned_insecure.lock().unwrap().len(), 1);
+
+        // If the Mistrust is disabled, we don't check permissions at all.
+        let key_store = new_keystore(
+            &Mistrust::new_dangerously_trust_everyone(),
+            InsecurePermissionsPolicy::Reject,
+        );
+        assert!(key_store.get(&spec, &item_type).unwrap().is_some());
+        assert_eq!(key_store.list().unwrap().len(), 1);
+    }
+
     #[test]
     fn get() {
         // Initialize an empty key store
@@ -724,6 +1539,430 @@ mod tests {
         }
     }
 
+    #[test]
+    fn list_by_type() {
+        let (key_store, _keystore_dir) = init_keystore(true);
+
+        // Add a cert alongside the ed25519 keypair created by init_keystore.
+        let mut rng = rand::rng();
+        let subject_key = ed25519::Keypair::generate(&mut rng);
+        let signing_key = ed25519::Keypair::generate(&mut rng);
+        let encoded_cert = Ed25519Cert::constructor()
+            .cert_type(tor_cert::CertType::IDENTITY_V_SIGNING)
+            .expiration(SystemTime::UNIX_EPOCH + Duration::from_secs(60 * 60))
+            .signing_key(signing_key.public_key().into())
+            .cert_key(CertifiedKey::Ed25519(subject_key.public_key().into()))
+            .encode_and_sign(&signing_key)
+            .unwrap();
+        let cert_spec = TestSpecifier::new("-cert");
+        key_store.insert(&encoded_cert, &cert_spec).unwrap();
+        assert_eq!(key_store.list().unwrap().len(), 2);
+
+        let keys = key_store
+            .list_by_type(&KeyType::Ed25519Keypair.into())
+            .unwrap();
+        assert_eq!(
+            keys,
+            vec![KeyPath::Arti(TestSpecifier::default().arti_path().unwrap())]
+        );
+
+        let certs = key_store
+            .list_by_type(&CertType::Ed25519TorCert.into())
+            .unwrap();
+        assert_eq!(certs, vec![KeyPath::Arti(cert_spec.arti_path().unwrap())]);
+
+        assert!(key_store
+            .list_by_type(&KeyType::X25519StaticKeypair.into())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn quarantine() {
+        let (key_store, keystore_dir) = init_keystore(true);
+        let bad_name = "not a valid slug.ed25519_private";
+        let bad_path = keystore_dir.path().join(bad_name);
+        fs::write(&bad_path, ED25519_OPENSSH).unwrap();
+        #[cfg(unix)]
+        fs::set_permissions(&bad_path, fs::Permissions::from_mode(0o600)).unwrap();
+
+        // By default, the corrupt entry causes listing to fail.
+        let err = key_store.list().unwrap_err();
+        assert!(err.to_string().contains("invalid path"), "{err}");
+        assert!(key_store.quarantined().unwrap().is_empty());
+
+        let key_store = key_store.with_quarantine(true);
+        let expected_arti_paths = vec![TestSpecifier::default().arti_path().unwrap()];
+        assert_contains_arti_paths!(expected_arti_paths, key_store.list().unwrap());
+
+        // The corrupt entry was moved aside...
+        assert!(!bad_path.exists());
+        let quarantined = key_store.quarantined().unwrap();
+        assert_eq!(quarantined.len(), 1);
+        assert!(quarantined[0].ends_with(Path::new("quarantine").join(bad_name)));
+        assert!(quarantined[0].exists());
+
+        // ...and the valid key is still usable.
+        assert!(key_store
+            .get(&TestSpecifier::default(), &KeyType::Ed25519Keypair.into())
+            .unwrap()
+            .is_some());
+
+        // Quarantined entries aren't listed, or quarantined again.
+        assert_contains_arti_paths!(expected_arti_paths, key_store.list().unwrap());
+        assert_eq!(key_store.quarantined().unwrap(), quarantined);
+
+        // The quarantined entries are remembered across restarts.
+        let key_store =
+            ArtiNativeKeystore::from_path_and_mistrust(&keystore_dir, &Mistrust::default())
+                .unwrap();
+        assert_eq!(key_store.quarantined().unwrap(), quarantined);
+
+        // With quarantine disabled, the quarantined entries still aren't listed.
+        assert_contains_arti_paths!(expected_arti_paths, key_store.list().unwrap());
+    }
+
+    #[test]
+    fn checksum_mismatch() {
+        let (key_store, _keystore_dir) = init_keystore(false);
+        let spec = TestSpecifier::default();
+        let item_type: KeystoreItemType = KeyType::Ed25519Keypair.into();
+
+        let mut rng = rand::rng();
+        let key = ed25519::Keypair::generate(&mut rng);
+        key_store.insert(&key, &spec).unwrap();
+
+        let key_path = key_path(&key_store, &KeyType::Ed25519Keypair);
+        let checksum_path = key_path.with_extension("ed25519_private.sha256");
+        assert!(checksum_path.exists());
+
+        // The checksum isn't an entry of its own.
+        assert_eq!(key_store.list().unwrap().len(), 1);
+        assert!(key_store.get(&spec, &item_type).unwrap().is_some());
+        assert!(key_store.verify_all().unwrap().is_empty());
+
+        // Flip a bit in the middle of the key.
+        let mut contents = fs::read(&key_path).unwrap();
+        let mid = contents.len() / 2;
+        contents[mid] ^= 1;
+        fs::write(&key_path, contents).unwrap();
+
+        let expected_path = KeyPath::Arti(spec.arti_path().unwrap());
+        let err = key_store.get(&spec, &item_type).unwrap_err();
+        assert!(
+            matches!(
+                &err,
+                crate::Error::Corruption(KeystoreCorruptionError::ChecksumMismatch { path })
+                    if path == &expected_path
+            ),
+            "{err}"
+        );
+        assert_eq!(err.kind(), tor_error::ErrorKind::KeystoreCorrupted);
+        assert_eq!(key_store.verify_all().unwrap(), vec![expected_path]);
+
+        // Replacing the key replaces its checksum.
+        key_store.insert(&key, &spec).unwrap();
+        assert!(key_store.get(&spec, &item_type).unwrap().is_some());
+        assert!(key_store.verify_all().unwrap().is_empty());
+
+        // Truncating the key is detected too.
+        let contents = fs::read(&key_path).unwrap();
+        fs::write(&key_path, &contents[..contents.len() / 2]).unwrap();
+        assert!(matches!(
+            key_store.get(&spec, &item_type),
+            Err(crate::Error::Corruption(
+                KeystoreCorruptionError::ChecksumMismatch { .. }
+            ))
+        ));
+        assert_eq!(key_store.verify_all().unwrap().len(), 1);
+
+        // So is replacing it with another key behind the keystore's back...
+        let other = ed25519::Keypair::generate(&mut rng);
+        let (other_store, _other_dir) = init_keystore(false);
+        other_store.insert(&other, &spec).unwrap();
+        let other_path = other_store
+            .rel_path(&spec, &item_type)
+            .unwrap()
+            .checked_path()
+            .unwrap();
+        fs::copy(other_path, &key_path).unwrap();
+        assert!(key_store.get(&spec, &item_type).is_err());
+        assert_eq!(key_store.verify_all().unwrap().len(), 1);
+
+        // ...and touching it doesn't change that.
+        assert_eq!(key_store.touch(&spec, &item_type).unwrap(), Some(()));
+        assert_eq!(key_store.verify_all().unwrap().len(), 1);
+
+        // Removing the stale checksum lets us read the replaced key, unchecked.
+        fs::remove_file(&checksum_path).unwrap();
+        assert!(key_store.get(&spec, &item_type).unwrap().is_some());
+        assert!(key_store.verify_all().unwrap().is_empty());
+
+        // Removing the key removes its checksum.
+        assert_eq!(key_store.remove(&spec, &item_type).unwrap(), Some(()));
+        assert!(!checksum_path.exists());
+    }
+
+    /// A [`KeystoreObserver`] that records every access.
+    #[derive(Debug, Default)]
+    struct RecordingObserver {
+        /// The accesses we have been told about, in order.
+        accesses: Mutex<Vec<(KeystoreOperation, Option<ArtiPath>, bool)>>,
+    }
+
+    impl KeystoreObserver for RecordingObserver {
+        fn on_access(&self, op: KeystoreOperation, path: Option<&ArtiPath>, ok: bool) {
+            self.accesses.lock().unwrap().push((op, path.cloned(), ok));
+        }
+    }
+
+    #[test]
+    fn observer() {
+        let (key_store, _keystore_dir) = init_keystore(false);
+        let observer = Arc::new(RecordingObserver::default());
+        let key_store = key_store.with_observer(observer.clone());
+
+        let mut rng = rand::rng();
+        let key = ed25519::Keypair::generate(&mut rng);
+        let spec = TestSpecifier::default();
+        let item_type: KeystoreItemType = KeyType::Ed25519Keypair.into();
+
+        key_store.insert(&key, &spec).unwrap();
+        assert!(key_store.get(&spec, &item_type).unwrap().is_some());
+        assert_eq!(key_store.remove(&spec, &item_type).unwrap(), Some(()));
+        assert!(key_store.get(&spec, &item_type).unwrap().is_none());
+        assert!(key_store.list().unwrap().is_empty());
+
+        // A failed operation is reported too.
+        let ctor_spec = TestCTorSpecifier(CTorPath::Service {
+            nickname: HsNickname::from_str("allium-cepa").unwrap(),
+            path: CTorServicePath::PublicKey,
+        });
+        assert!(key_store.insert(&key, &ctor_spec).is_err());
+
+        let path = Some(spec.arti_path().unwrap());
+        assert_eq!(
+            *observer.accesses.lock().unwrap(),
+            vec![
+                (KeystoreOperation::Insert, path.clone(), true),
+                (KeystoreOperation::Get, path.clone(), true),
+                (KeystoreOperation::Remove, path.clone(), true),
+                (KeystoreOperation::Get, path, true),
+                (KeystoreOperation::List, None, true),
+                (KeystoreOperation::Insert, None, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn touch() {
+        let (key_store, _keystore_dir) = init_keystore(true);
+        let spec = TestSpecifier::default();
+        let item_type: KeystoreItemType = KeyType::Ed25519Keypair.into();
+
+        // Make the key look old.
+        let long_ago = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let path = key_path(&key_store, &KeyType::Ed25519Keypair);
+        filetime::set_file_mtime(&path, FileTime::from_system_time(long_ago)).unwrap();
+        assert_eq!(
+            key_store.modified(&spec, &item_type).unwrap(),
+            Some(long_ago)
+        );
+
+        // Touching it updates its modification time, but not its contents.
+        assert_eq!(key_store.touch(&spec, &item_type).unwrap(), Some(()));
+        assert!(key_store.modified(&spec, &item_type).unwrap().unwrap() > long_ago);
+        assert_eq!(fs::read_to_string(&path).unwrap(), ED25519_OPENSSH);
+
+        // Missing keys can't be touched.
+        let missing = TestSpecifier::new("-missing");
+        assert_eq!(key_store.touch(&missing, &item_type).unwrap(), None);
+        assert_eq!(key_store.modified(&missing, &item_type).unwrap(), None);
+    }
+
+    /// A naming scheme that stores entries of each type in a separate directory.
+    #[derive(Debug)]
+    struct ByTypeNamingScheme;
+
+    impl FileNamingScheme for ByTypeNamingScheme {
+        fn rel_path(&self, path: &ArtiPath, item_type: &KeystoreItemType) -> PathBuf {
+            Path::new(&*item_type.arti_extension()).join(path.as_str())
+        }
+
+        fn item_type(&self, rel_path: &Path) -> Option<KeystoreItemType> {
+            let dir = rel_path.components().next()?.as_os_str().to_str()?;
+            Some(KeystoreItemType::from(dir))
+        }
+
+        fn arti_path(&self, rel_path: &Path) -> StdResult<ArtiPath, ArtiPathSyntaxError> {
+            let path = rel_path.components().skip(1).collect::<PathBuf>();
+            ArtiPath::new(path.to_string_lossy().into_owned())
+        }
+    }
+
+    #[test]
+    fn naming_scheme() {
+        let (key_store, keystore_dir) = init_keystore(false);
+        let key_store = key_store.with_naming_scheme(Arc::new(ByTypeNamingScheme));
+
+        let mut rng = rand::rng();
+        let key = ed25519::Keypair::generate(&mut rng);
+        let spec = TestSpecifier::default();
+        let item_type: KeystoreItemType = KeyType::Ed25519Keypair.into();
+        key_store.insert(&key, &spec).unwrap();
+
+        // The key is stored where our scheme says it should be...
+        let arti_path = spec.arti_path().unwrap();
+        let expected = keystore_dir
+            .path()
+            .join(&*item_type.arti_extension())
+            .join(arti_path.as_str());
+        assert!(expected.is_file());
+        assert!(!keystore_dir
+            .path()
+            .join(DefaultFileNamingScheme.rel_path(&arti_path, &item_type))
+            .exists());
+
+        // ...and can be found again.
+        assert_found!(key_store, &spec, &KeyType::Ed25519Keypair, true);
+        assert_eq!(
+            key_store.list().unwrap(),
+            vec![(KeyPath::Arti(arti_path.clone()), item_type.clone())]
+        );
+        assert_eq!(
+            key_store.list_by_type(&item_type).unwrap(),
+            vec![KeyPath::Arti(arti_path)]
+        );
+
+        assert_eq!(key_store.remove(&spec, &item_type).unwrap(), Some(()));
+        assert_found!(key_store, &spec, &KeyType::Ed25519Keypair, false);
+    }
+
+    #[test]
+    fn list_page() {
+        let (key_store, keystore_dir) = init_keystore(false);
+        let mut rng = rand::rng();
+        for i in 0..50 {
+            let key = ed25519::Keypair::generate(&mut rng);
+            key_store
+                .insert(&key, &TestSpecifier::new(format!("-{i}")))
+                .unwrap();
+        }
+        let all = key_store.list().unwrap();
+        assert_eq!(all.len(), 50);
+
+        // Walk through the keystore one page at a time.
+        let mut paged = vec![];
+        loop {
+            let (page, has_more) = key_store.list_page(paged.len(), 7).unwrap();
+            assert!(page.len() <= 7);
+            paged.extend(page);
+            if !has_more {
+                break;
+            }
+        }
+        // The pages don't overlap, and cover every key.
+        assert_eq!(paged.len(), all.len());
+        assert_contains_arti_paths!(
+            all.iter()
+                .map(|(path, _)| path.arti().unwrap().clone())
+                .collect::<Vec<_>>(),
+            paged
+        );
+
+        // Pages starting anywhere agree with the ones we walked through.
+        let (page, has_more) = key_store.list_page(10, 5).unwrap();
+        assert!(has_more);
+        assert_eq!(page, paged[10..15]);
+        let (page, has_more) = key_store.list_page(45, 10).unwrap();
+        assert!(!has_more);
+        assert_eq!(page, paged[45..]);
+        let (page, has_more) = key_store.list_page(100, 10).unwrap();
+        assert!(!has_more);
+        assert!(page.is_empty());
+
+        // A corrupt entry that comes before the requested page isn't skipped over:
+        // it makes listing fail, just like it does for `list`.
+        let bad_path = keystore_dir.path().join("0 not a slug.ed25519_private");
+        fs::write(&bad_path, ED25519_OPENSSH).unwrap();
+        #[cfg(unix)]
+        fs::set_permissions(&bad_path, fs::Permissions::from_mode(0o600)).unwrap();
+        assert!(key_store.list().is_err());
+        let err = key_store.list_page(10, 5).unwrap_err();
+        assert!(err.to_string().contains("invalid path"), "{err}");
+    }
+
+    #[test]
+    fn compression() {
+        let mut rng = rand::rng();
+        let key = ed25519::Keypair::generate(&mut rng);
+        let spec = TestSpecifier::default();
+        let item_type: KeystoreItemType = KeyType::Ed25519Keypair.into();
+
+        // Store the key uncompressed, to find out how large it is.
+        let (plain_store, _plain_dir) = init_keystore(false);
+        plain_store.insert(&key, &spec).unwrap();
+        let plain = fs::read(key_path(&plain_store, &KeyType::Ed25519Keypair)).unwrap();
+        assert!(!plain.starts_with(COMPRESSED_MARKER));
+
+        // The key is larger than the threshold, so it is stored compressed...
+        let (key_store, _keystore_dir) = init_keystore(false);
+        let key_store = key_store.with_compression(64);
+        key_store.insert(&key, &spec).unwrap();
+        let on_disk = fs::read(key_path(&key_store, &KeyType::Ed25519Keypair)).unwrap();
+        assert!(on_disk.starts_with(COMPRESSED_MARKER));
+        assert!(on_disk.len() < plain.len());
+        assert_eq!(decompress_entry(on_disk).unwrap(), plain);
+
+        // ...and read back transparently.
+        let found = key_store.get(&spec, &item_type).unwrap().unwrap();
+        let Ok(found) = found.downcast::<ed25519::Keypair>() else {
+            panic!("failed to downcast key to ed25519::Keypair")
+        };
+        assert_eq!(found.to_bytes(), key.to_bytes());
+
+        // Entries below the threshold are stored as they are.
+        let (key_store, _keystore_dir) = init_keystore(false);
+        let key_store = key_store.with_compression(plain.len());
+        key_store.insert(&key, &spec).unwrap();
+        let on_disk = fs::read(key_path(&key_store, &KeyType::Ed25519Keypair)).unwrap();
+        assert_eq!(on_disk, plain);
+
+        // Uncompressed entries can still be read if compression is enabled.
+        let (key_store, _keystore_dir) = init_keystore(true);
+        let key_store = key_store.with_compression(0);
+        assert_found!(key_store, &spec, &KeyType::Ed25519Keypair, true);
+    }
+
+    #[test]
+    fn list_unknown() {
+        inventory::submit!(crate::key_specifier_derive::KeySpecifierRegistration {
+            prefix: "known-prefix",
+            role: "list_unknown",
+            type_name: "list_unknown::TestSpecifier",
+        });
+
+        let (key_store, keystore_dir) = init_keystore(false);
+        for dir in ["known-prefix", "wombat"] {
+            let dir = keystore_dir.path().join(dir);
+            fs::create_dir(&dir).unwrap();
+            #[cfg(unix)]
+            fs::set_permissions(&dir, fs::Permissions::from_mode(0o700)).unwrap();
+            let key = dir.join("marzlevane.ed25519_private");
+            fs::write(&key, ED25519_OPENSSH).unwrap();
+            #[cfg(unix)]
+            fs::set_permissions(&key, fs::Permissions::from_mode(0o600)).unwrap();
+        }
+
+        let known = ArtiPath::new("known-prefix/marzlevane".into()).unwrap();
+        let unknown = ArtiPath::new("wombat/marzlevane".into()).unwrap();
+
+        // Both entries are listed as usual...
+        assert_contains_arti_paths!(vec![known, unknown.clone()], key_store.list().unwrap());
+        // ...but only the one with an unrecognized prefix is reported as unknown.
+        assert_contains_arti_paths!(vec![unknown], key_store.list_unknown().unwrap());
+    }
+
     #[test]
     fn key_path_not_regular_file() {
         let (key_store, _keystore_dir) = init_keystore(false);
@@ -2,6 +2,7 @@
 
 use crate::keystore::fs_utils::FilesystemError;
 use crate::{ArtiPathSyntaxError, KeystoreError, UnknownKeyTypeError};
+use tor_basic_utils::PathExt as _;
 use tor_error::{ErrorKind, HasKind};
 use tor_key_forge::{CertType, KeyType, SshKeyAlgorithm};
 
@@ -65,6 +66,20 @@ pub(crate) enum ArtiNativeKeystoreError {
         err: tor_bytes::Error,
     },
 
+    /// Found a key file or directory that is readable by other users.
+    #[error("{} is readable by other users (mode {mode:o})", path.display_lossy())]
+    InsecurePermissions {
+        /// The path of the file or directory.
+        path: PathBuf,
+        /// The permissions of the file or directory.
+        mode: u32,
+    },
+
+    /// Tried to store a key whose [`KeySpecifier`](crate::KeySpecifier)
+    /// has no [`ArtiPath`](crate::ArtiPath).
+    #[error("Key specifier has no ArtiPath; cannot store in Arti keystore")]
+    NoArtiPath,
+
     /// An internal error.
     #[error("Internal error")]
     Bug(#[from] tor_error::Bug),
@@ -72,19 +87,19 @@ pub(crate) enum ArtiNativeKeystoreError {
 
 /// The keystore contained a file whose name syntactically improper
 ///
-/// Keys are supposed to have pathnames consisting of an `ArtiPath`
-/// followed by a file extension.
+/// Keys are supposed to have pathnames from which the keystore's
+/// [`FileNamingScheme`](crate::FileNamingScheme) can recover
+/// an `ArtiPath` and an item type.
 ///
 /// See also [`KeyPathError`](crate::KeyPathError), which occurs at a higher level.
 #[derive(thiserror::Error, Debug, Clone)]
 pub(crate) enum MalformedPathError {
-    /// Found a key with a non-UTF-8 path.
-    #[error("the path is not valid UTF-8")]
-    Utf8,
-
-    /// Found a key with no extension.
-    #[error("no extension")]
-    NoExtension,
+    /// Found a key whose name doesn't specify its type.
+    ///
+    /// With the default naming scheme,
+    /// this means the key has no extension, or a non-UTF-8 one.
+    #[error("the name does not specify an item type")]
+    NoItemType,
 
     /// The file path is not a valid [`ArtiPath`](crate::ArtiPath).
     #[error("not a valid ArtiPath")]
@@ -101,6 +116,8 @@ impl HasKind for ArtiNativeKeystoreError {
             KE::Filesystem(e) => e.kind(),
             KE::MalformedPath { .. } => ErrorKind::KeystoreAccessFailed,
             KE::UnknownKeyType(_) => ErrorKind::KeystoreAccessFailed,
+            KE::InsecurePermissions { .. } => ErrorKind::FsPermissions,
+            KE::NoArtiPath => ErrorKind::BadApiUsage,
             KE::SshKeyParse { .. } | KE::UnexpectedSshKeyType { .. } | KE::CertParse { .. } => {
                 ErrorKind::KeystoreCorrupted
             }
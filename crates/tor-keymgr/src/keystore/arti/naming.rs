@@ -0,0 +1,78 @@
+//! The mapping between keystore entries and the files that hold them.
+
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+
+use tor_key_forge::KeystoreItemType;
+
+use crate::{arti_path, ArtiPath, ArtiPathSyntaxError};
+
+/// A scheme for naming the files of an [`ArtiNativeKeystore`](crate::ArtiNativeKeystore).
+///
+/// A `FileNamingScheme` decides where, relative to the keystore directory,
+/// the entry with a given [`ArtiPath`] and [`KeystoreItemType`] is stored,
+/// and works out the `ArtiPath` and type of the entry stored in a given file.
+/// The two directions must agree:
+/// for every `path` and `item_type`, parsing `rel_path(path, item_type)`
+/// must give back `path` and `item_type`.
+///
+/// The [`DefaultFileNamingScheme`] is used unless another one is set with
+/// [`with_naming_scheme`](crate::ArtiNativeKeystore::with_naming_scheme).
+///
+/// Note that the keystore stores the checksum of each entry in a file
+/// whose name is that of the entry followed by `.sha256`,
+/// and treats every file with that extension as a checksum.
+/// A scheme must therefore never return a path with a `.sha256` extension.
+pub trait FileNamingScheme: Debug + Send + Sync + 'static {
+    /// Return the path, relative to the keystore directory,
+    /// of the file holding the entry with the specified `path` and `item_type`.
+    fn rel_path(&self, path: &ArtiPath, item_type: &KeystoreItemType) -> PathBuf;
+
+    /// Return the type of the entry stored in the file at `rel_path`,
+    /// relative to the keystore directory.
+    ///
+    /// Returns `None` if the name of the file doesn't specify a type.
+    ///
+    /// This is called before [`arti_path`](FileNamingScheme::arti_path),
+    /// so that files holding entries of an unwanted type can be skipped
+    /// without checking the rest of their name.
+    fn item_type(&self, rel_path: &Path) -> Option<KeystoreItemType>;
+
+    /// Return the [`ArtiPath`] of the entry stored in the file at `rel_path`,
+    /// relative to the keystore directory.
+    fn arti_path(&self, rel_path: &Path) -> Result<ArtiPath, ArtiPathSyntaxError>;
+}
+
+/// The default [`FileNamingScheme`].
+///
+/// Each entry is stored at its [`ArtiPath`], relative to the keystore directory,
+/// with the [`arti_extension`](KeystoreItemType::arti_extension) of its type
+/// as the file extension.
+#[derive(Debug, Default, Clone, Copy)]
+#[non_exhaustive]
+pub struct DefaultFileNamingScheme;
+
+impl FileNamingScheme for DefaultFileNamingScheme {
+    fn rel_path(&self, path: &ArtiPath, item_type: &KeystoreItemType) -> PathBuf {
+        let mut rel_path = PathBuf::from(path.as_str());
+        rel_path.set_extension(item_type.arti_extension());
+        rel_path
+    }
+
+    fn item_type(&self, rel_path: &Path) -> Option<KeystoreItemType> {
+        let extension = rel_path.extension()?.to_str()?;
+        Some(KeystoreItemType::from(extension))
+    }
+
+    fn arti_path(&self, rel_path: &Path) -> Result<ArtiPath, ArtiPathSyntaxError> {
+        // Strip away the file extension
+        let path = rel_path.with_extension("");
+        // Construct slugs in platform-independent way
+        let slugs = path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(&arti_path::PATH_SEP.to_string());
+        ArtiPath::new(slugs)
+    }
+}
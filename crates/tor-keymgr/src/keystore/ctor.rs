@@ -2,7 +2,9 @@
 
 pub(crate) mod client;
 pub(crate) mod err;
+pub(crate) mod relay;
 pub(crate) mod service;
+mod tagged;
 
 use crate::keystore::fs_utils::{FilesystemAction, FilesystemError, RelKeyPath};
 use crate::{KeystoreId, Result};
@@ -13,6 +15,7 @@ use std::path::{Path, PathBuf};
 use err::CTorKeystoreError;
 
 pub use client::CTorClientKeystore;
+pub use relay::CTorRelayKeystore;
 pub use service::CTorServiceKeystore;
 
 /// Common fields for C Tor keystores.
@@ -10,7 +10,9 @@ use std::str::FromStr as _;
 use crate::keystore::ctor::err::{CTorKeystoreError, MalformedClientKeyError};
 use crate::keystore::ctor::CTorKeystore;
 use crate::keystore::fs_utils::{checked_op, FilesystemAction, FilesystemError, RelKeyPath};
-use crate::keystore::{EncodableItem, ErasedKey, KeySpecifier, Keystore};
+use crate::keystore::{
+    EncodableItem, ErasedKey, KeySpecifier, Keystore, KeystoreDescription, KeystoreKind,
+};
 use crate::{CTorPath, KeyPath, KeystoreId, Result};
 
 use fs_mistrust::Mistrust;
@@ -263,6 +265,14 @@ impl Keystore for CTorClientKeystore {
         Err(CTorKeystoreError::NotSupported { action: "remove" }.into())
     }
 
+    fn touch(
+        &self,
+        _key_spec: &dyn KeySpecifier,
+        _item_type: &KeystoreItemType,
+    ) -> Result<Option<()>> {
+        Err(CTorKeystoreError::NotSupported { action: "touch" }.into())
+    }
+
     fn list(&self) -> Result<Vec<(KeyPath, KeystoreItemType)>> {
         let keys = self
             .list_keys()?
@@ -276,6 +286,21 @@ impl Keystore for CTorClientKeystore {
 
         Ok(keys)
     }
+
+    fn describe(&self) -> Result<KeystoreDescription> {
+        Ok(KeystoreDescription::new(
+            self.0.id.clone(),
+            KeystoreKind::CTorClient,
+            Some(self.0.keystore_dir.as_path().to_owned()),
+            self.list()?.len(),
+        ))
+    }
+
+    fn supports(&self, _item_type: &KeystoreItemType) -> bool {
+        // This keystore is read-only: it can hold client keys,
+        // but it can't be used to store them.
+        false
+    }
 }
 
 #[cfg(test)]
@@ -297,7 +322,11 @@ mod tests {
     use std::fs;
     use tempfile::{tempdir, TempDir};
 
-    use crate::test_utils::{assert_found, DummyKey, TestCTorSpecifier};
+    use crate::test_utils::{
+        assert_found, DummyKey, TestCTorSpecifier, TestKeystore, TestSpecifier,
+    };
+    use crate::{KeyMgrBuilder, KeystoreSelector};
+    use tor_basic_utils::test_rng::testing_rng;
 
     #[cfg(unix)]
     use std::os::unix::fs::PermissionsExt;
@@ -423,4 +452,60 @@ mod tests {
             "Invalid item type Ed25519PublicKey for client restricted discovery key"
         );
     }
+
+    #[test]
+    fn describe() {
+        let (keystore, keystore_dir) = init_keystore("foo");
+        let desc = keystore.describe().unwrap();
+
+        assert_eq!(desc.id, KeystoreId::from_str("foo").unwrap());
+        assert_eq!(desc.kind, KeystoreKind::CTorClient);
+        assert_eq!(desc.kind.to_string(), "ctor-client");
+        assert_eq!(desc.location.as_deref(), Some(keystore_dir.path()));
+        // Only alice and dan have valid keys with the right extension
+        assert_eq!(desc.n_keys, 2);
+    }
+
+    #[test]
+    fn supports() {
+        let (keystore, _keystore_dir) = init_keystore("foo");
+
+        // We can't store anything, not even the keys we can read.
+        assert!(!keystore.supports(&KeyType::X25519StaticKeypair.into()));
+        assert!(!keystore.supports(&KeyType::Ed25519Keypair.into()));
+    }
+
+    #[test]
+    fn keymgr_insert() {
+        let (keystore, _keystore_dir) = init_keystore("foo");
+        let writable = TestKeystore::new("writable");
+        let mut builder = KeyMgrBuilder::default().primary_store(Box::new(writable.clone()));
+        builder.secondary_stores().push(Box::new(keystore));
+        let mgr = builder.build().unwrap();
+
+        let key = || {
+            let secret = curve25519::StaticSecret::random_from_rng(testing_rng());
+            let public = curve25519::PublicKey::from(&secret);
+            HsClientDescEncKeypair::from(curve25519::StaticKeypair { secret, public })
+        };
+        let spec = TestSpecifier::default();
+
+        // The KeyMgr refuses to write to the C Tor keystore,
+        // without trying to insert anything into it...
+        let err = mgr
+            .insert(
+                key(),
+                &spec,
+                KeystoreSelector::Id(&KeystoreId::from_str("foo").unwrap()),
+                false,
+            )
+            .unwrap_err();
+        assert!(matches!(err, crate::Error::UnsupportedItemType { .. }));
+
+        // ...but the key can be written to the writable keystore.
+        mgr.insert(key(), &spec, KeystoreSelector::Primary, false)
+            .unwrap();
+        assert_eq!(writable.len(), 1);
+        assert_eq!(writable.calls().insert, 1);
+    }
 }
@@ -51,18 +51,20 @@ pub(crate) enum CTorKeystoreError {
 /// Encountered a malformed C Tor key.
 #[derive(thiserror::Error, Debug, Clone)]
 pub(crate) enum MalformedKeyError {
-    /// A malformed hidden service key.
+    /// A malformed hidden service or relay key.
     #[error("{0}")]
-    Service(#[from] MalformedServiceKeyError),
+    Tagged(#[from] MalformedTaggedKeyError),
 
     /// A malformed hidden service client key.
     #[error("{0}")]
     Client(#[from] MalformedClientKeyError),
 }
 
-/// Encountered a malformed C Tor service key.
+/// Encountered a malformed C Tor key in the tagged key file format.
+///
+/// C Tor uses this format for the keys of onion services and relays.
 #[derive(thiserror::Error, Debug, Clone)]
-pub(crate) enum MalformedServiceKeyError {
+pub(crate) enum MalformedTaggedKeyError {
     /// Found a key with an invalid tag
     #[error("invalid key length: {len} (expected {expected_len})")]
     InvalidKeyLen {
@@ -94,6 +96,10 @@ pub(crate) enum MalformedServiceKeyError {
     #[error("invalid ed25519 keypair")]
     Ed25519Keypair,
 
+    /// Found a curve25519 keypair whose public key doesn't match its secret key
+    #[error("curve25519 public key does not match the secret key")]
+    X25519KeyMismatch,
+
     /// An internal error.
     #[error("Internal error")]
     Bug(#[from] tor_error::Bug),
@@ -0,0 +1,515 @@
+//! Read-only C Tor relay key store implementation
+//!
+//! See [`CTorRelayKeystore`] for more details.
+
+use crate::keystore::ctor::err::{CTorKeystoreError, MalformedTaggedKeyError};
+use crate::keystore::ctor::tagged::{
+    parse_ed25519_keypair, parse_ed25519_public, parse_x25519_onion_keypair,
+};
+use crate::keystore::ctor::CTorKeystore;
+use crate::keystore::fs_utils::{checked_op, FilesystemAction, FilesystemError};
+use crate::keystore::{
+    EncodableItem, ErasedKey, KeySpecifier, Keystore, KeystoreDescription, KeystoreId, KeystoreKind,
+};
+use crate::{CTorPath, CTorRelayPath, KeyPath, Result};
+
+use fs_mistrust::Mistrust;
+use itertools::Itertools as _;
+use tor_basic_utils::PathExt as _;
+use tor_key_forge::{KeyType, KeystoreItemType};
+
+use std::path::{Path, PathBuf};
+
+/// A read-only C Tor relay keystore.
+///
+/// This keystore provides read-only access to the relay keys
+/// rooted at a given `KeyDirectory` directory
+/// (by default, `DataDirectory/keys`; see `KeyDirectory` in `tor(1)`).
+///
+/// This keystore can be used to read the following C Tor keys:
+///
+/// | File                           | [`CTorRelayPath`]            | [`KeyType`]                 |
+/// |--------------------------------|------------------------------|-----------------------------|
+/// | `ed25519_master_id_public_key` | `Ed25519MasterIdPublicKey`   | `Ed25519PublicKey`          |
+/// | `ed25519_master_id_secret_key` | `Ed25519MasterIdSecretKey`   | `Ed25519ExpandedKeypair`    |
+/// | `ed25519_signing_secret_key`   | `Ed25519SigningSecretKey`    | `Ed25519ExpandedKeypair`    |
+/// | `secret_onion_key_ntor`        | `SecretOnionKeyNtor`         | `X25519StaticKeypair`       |
+///
+/// Arti doesn't support RSA keys, so the RSA identity and onion keys
+/// ([`SecretIdKey`](CTorRelayPath::SecretIdKey) and
+/// [`SecretOnionKey`](CTorRelayPath::SecretOnionKey))
+/// are returned by [`list`](Keystore::list) with an unknown item type
+/// (`KeystoreItemType::Unknown { arti_extension: "rsa" }`),
+/// but trying to [`get`](Keystore::get) them returns an error.
+///
+/// Any other files stored in `KeyDirectory` will be ignored.
+/// In particular, the encrypted master identity key, the certificates,
+/// and the previous onion keys (`*.old`) can't be read from this keystore.
+///
+/// The only supported [`Keystore`] operations are [`contains`](Keystore::contains),
+/// [`get`](Keystore::get), and [`list`](Keystore::list). All other keystore operations
+/// will return an error.
+///
+/// This keystore implementation uses the [`CTorPath`] of the requested [`KeySpecifier`]
+/// and the [`KeystoreItemType`] to identify the appropriate key.
+/// If the requested `CTorPath` is not [`Relay`](CTorPath::Relay),
+/// the key will be declared not found.
+/// If the requested `CTorPath` is [`Relay`](CTorPath::Relay),
+/// but the `ItemType` and [`CTorRelayPath`] are mismatched,
+/// an error is returned.
+pub struct CTorRelayKeystore(CTorKeystore);
+
+impl CTorRelayKeystore {
+    /// Create a new `CTorRelayKeystore`
+    /// rooted at the specified `keystore_dir` directory.
+    ///
+    /// This function returns an error if `keystore_dir` is not a directory,
+    /// or if it does not conform to the requirements of the specified `Mistrust`.
+    pub fn from_path_and_mistrust(
+        keystore_dir: impl AsRef<Path>,
+        mistrust: &Mistrust,
+        id: KeystoreId,
+    ) -> Result<Self> {
+        CTorKeystore::from_path_and_mistrust(keystore_dir, mistrust, id).map(Self)
+    }
+}
+
+/// All the relay keys that can be stored in this keystore.
+const ALL_KEYS: &[CTorRelayPath] = &[
+    CTorRelayPath::Ed25519MasterIdPublicKey,
+    CTorRelayPath::Ed25519MasterIdSecretKey,
+    CTorRelayPath::Ed25519SigningSecretKey,
+    CTorRelayPath::SecretOnionKeyNtor,
+    CTorRelayPath::SecretIdKey,
+    CTorRelayPath::SecretOnionKey,
+];
+
+/// The `arti_extension` of the (unknown) item type we report for RSA keys.
+const RSA_EXTENSION: &str = "rsa";
+
+/// Return the type of the key C Tor stores at `path`.
+///
+/// Arti has no [`KeyType`] for RSA keys,
+/// so their type is [`Unknown`](KeystoreItemType::Unknown).
+fn item_type(path: CTorRelayPath) -> KeystoreItemType {
+    use CTorRelayPath as P;
+
+    match path {
+        P::Ed25519MasterIdPublicKey => KeyType::Ed25519PublicKey.into(),
+        P::Ed25519MasterIdSecretKey | P::Ed25519SigningSecretKey => {
+            KeyType::Ed25519ExpandedKeypair.into()
+        }
+        P::SecretOnionKeyNtor => KeyType::X25519StaticKeypair.into(),
+        P::SecretIdKey | P::SecretOnionKey => KeystoreItemType::Unknown {
+            arti_extension: RSA_EXTENSION.into(),
+        },
+    }
+}
+
+/// Extract the [`CTorRelayPath`] of the key specifier `spec`,
+/// along with the path of its file (relative to the keystore root),
+/// or return an error if `item_type` is not the type of that key.
+///
+/// If `spec` is not a relay key specifier, return `ret`.
+macro_rules! rel_path_if_supported {
+    ($self:expr, $spec:expr, $ret:expr, $item_type:expr) => {{
+        // If the key specifier doesn't have a CTorPath,
+        // we can't possibly handle this key.
+        let Some(ctor_path) = $spec.ctor_path() else {
+            return $ret;
+        };
+
+        // This keystore only deals with relay keys.
+        let CTorPath::Relay(path) = ctor_path else {
+            return $ret;
+        };
+
+        let relpath = $self.0.rel_path(PathBuf::from(path.to_string()));
+        if *$item_type != item_type(path) {
+            return Err(CTorKeystoreError::InvalidKeystoreItemType {
+                item_type: $item_type.clone(),
+                item: format!("key {}", relpath.rel_path_unchecked().display_lossy()),
+            }
+            .into());
+        }
+
+        (path, relpath)
+    }};
+}
+
+impl Keystore for CTorRelayKeystore {
+    fn id(&self) -> &KeystoreId {
+        &self.0.id
+    }
+
+    fn contains(&self, key_spec: &dyn KeySpecifier, item_type: &KeystoreItemType) -> Result<bool> {
+        let (_, path) = rel_path_if_supported!(self, key_spec, Ok(false), item_type);
+
+        let meta = match checked_op!(metadata, path) {
+            Ok(meta) => meta,
+            Err(fs_mistrust::Error::NotFound(_)) => return Ok(false),
+            Err(e) => {
+                return Err(FilesystemError::FsMistrust {
+                    action: FilesystemAction::Read,
+                    path: path.rel_path_unchecked().into(),
+                    err: e.into(),
+                })
+                .map_err(|e| CTorKeystoreError::Filesystem(e).into());
+            }
+        };
+
+        // The path exists, now check that it's actually a file and not a directory or symlink.
+        if meta.is_file() {
+            Ok(true)
+        } else {
+            Err(
+                CTorKeystoreError::Filesystem(FilesystemError::NotARegularFile(
+                    path.rel_path_unchecked().into(),
+                ))
+                .into(),
+            )
+        }
+    }
+
+    fn get(
+        &self,
+        key_spec: &dyn KeySpecifier,
+        item_type: &KeystoreItemType,
+    ) -> Result<Option<ErasedKey>> {
+        use CTorRelayPath as P;
+
+        let (relay_path, path) = rel_path_if_supported!(self, key_spec, Ok(None), item_type);
+
+        let key = match checked_op!(read, path) {
+            Err(fs_mistrust::Error::NotFound(_)) => return Ok(None),
+            res => res
+                .map_err(|err| FilesystemError::FsMistrust {
+                    action: FilesystemAction::Read,
+                    path: path.rel_path_unchecked().into(),
+                    err: err.into(),
+                })
+                .map_err(CTorKeystoreError::Filesystem)?,
+        };
+
+        let parse_err = |err: MalformedTaggedKeyError| CTorKeystoreError::MalformedKey {
+            path: path.rel_path_unchecked().into(),
+            err: err.into(),
+        };
+
+        let parsed_key: ErasedKey = match relay_path {
+            P::Ed25519MasterIdPublicKey => parse_ed25519_public(&key)
+                .map_err(parse_err)
+                .map(Box::new)?,
+            P::Ed25519MasterIdSecretKey | P::Ed25519SigningSecretKey => parse_ed25519_keypair(&key)
+                .map_err(parse_err)
+                .map(Box::new)?,
+            P::SecretOnionKeyNtor => parse_x25519_onion_keypair(&key)
+                .map_err(parse_err)
+                .map(Box::new)?,
+            P::SecretIdKey | P::SecretOnionKey => {
+                return Err(CTorKeystoreError::NotSupported {
+                    action: "read RSA key",
+                }
+                .into());
+            }
+        };
+
+        Ok(Some(parsed_key))
+    }
+
+    fn insert(&self, _key: &dyn EncodableItem, _key_spec: &dyn KeySpecifier) -> Result<()> {
+        Err(CTorKeystoreError::NotSupported { action: "insert" }.into())
+    }
+
+    fn remove(
+        &self,
+        _key_spec: &dyn KeySpecifier,
+        _item_type: &KeystoreItemType,
+    ) -> Result<Option<()>> {
+        Err(CTorKeystoreError::NotSupported { action: "remove" }.into())
+    }
+
+    fn touch(
+        &self,
+        _key_spec: &dyn KeySpecifier,
+        _item_type: &KeystoreItemType,
+    ) -> Result<Option<()>> {
+        Err(CTorKeystoreError::NotSupported { action: "touch" }.into())
+    }
+
+    fn list(&self) -> Result<Vec<(KeyPath, KeystoreItemType)>> {
+        ALL_KEYS
+            .iter()
+            .map(|&path| {
+                let item_type = item_type(path);
+                let path = CTorPath::Relay(path);
+                self.contains(&path, &item_type)
+                    .map(|res: bool| (path, item_type, res))
+            })
+            .filter_map_ok(|(path, item_type, res)| res.then_some((path.into(), item_type)))
+            .collect()
+    }
+
+    fn describe(&self) -> Result<KeystoreDescription> {
+        Ok(KeystoreDescription::new(
+            self.0.id.clone(),
+            KeystoreKind::CTorRelay,
+            Some(self.0.keystore_dir.as_path().to_owned()),
+            self.list()?.len(),
+        ))
+    }
+
+    fn supports(&self, _item_type: &KeystoreItemType) -> bool {
+        // This keystore is read-only: it can hold relay keys,
+        // but it can't be used to store them.
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // @@ begin test lint list maintained by maint/add_warning @@
+    #![allow(clippy::bool_assert_comparison)]
+    #![allow(clippy::clone_on_copy)]
+    #![allow(clippy::dbg_macro)]
+    #![allow(clippy::mixed_attributes_style)]
+    #![allow(clippy::print_stderr)]
+    #![allow(clippy::print_stdout)]
+    #![allow(clippy::single_char_pattern)]
+    #![allow(clippy::unwrap_used)]
+    #![allow(clippy::unchecked_duration_subtraction)]
+    #![allow(clippy::useless_vec)]
+    #![allow(clippy::needless_pass_by_value)]
+    //! <!-- @@ end test lint list maintained by maint/add_warning @@ -->
+
+    use super::*;
+    use std::fs;
+    use std::str::FromStr as _;
+    use tempfile::{tempdir, TempDir};
+    use tor_llcrypto::pk::{curve25519, ed25519};
+    use tor_persist::hsnickname::HsNickname;
+
+    use crate::test_utils::{assert_found, DummyKey, TestCTorSpecifier};
+    use crate::CTorServicePath;
+
+    /// The C Tor relay keys, as laid out in C Tor's `KeyDirectory`.
+    const KEYS: &[(&str, &[u8])] = &[
+        (
+            "ed25519_master_id_public_key",
+            include_bytes!("../../../testdata/tor-relay/keys/ed25519_master_id_public_key"),
+        ),
+        (
+            "ed25519_master_id_secret_key",
+            include_bytes!("../../../testdata/tor-relay/keys/ed25519_master_id_secret_key"),
+        ),
+        (
+            "ed25519_signing_secret_key",
+            include_bytes!("../../../testdata/tor-relay/keys/ed25519_signing_secret_key"),
+        ),
+        (
+            "secret_onion_key_ntor",
+            include_bytes!("../../../testdata/tor-relay/keys/secret_onion_key_ntor"),
+        ),
+    ];
+
+    #[cfg(unix)]
+    use std::os::unix::fs::PermissionsExt;
+
+    fn init_keystore(id: &str) -> (CTorRelayKeystore, TempDir) {
+        let keystore_dir = tempdir().unwrap();
+
+        #[cfg(unix)]
+        fs::set_permissions(&keystore_dir, fs::Permissions::from_mode(0o700)).unwrap();
+
+        let id = KeystoreId::from_str(id).unwrap();
+        let keystore =
+            CTorRelayKeystore::from_path_and_mistrust(&keystore_dir, &Mistrust::default(), id)
+                .unwrap();
+
+        for (name, key) in KEYS {
+            fs::write(keystore_dir.path().join(name), key).unwrap();
+        }
+
+        // Some keys we can't read.
+        for name in [
+            "secret_id_key",
+            "secret_onion_key",
+            "secret_onion_key_ntor.old",
+        ] {
+            fs::write(keystore_dir.path().join(name), b"not a key we understand").unwrap();
+        }
+
+        (keystore, keystore_dir)
+    }
+
+    /// Return a specifier for the relay key at `path`.
+    fn spec(path: CTorRelayPath) -> TestCTorSpecifier {
+        TestCTorSpecifier(CTorPath::relay(path))
+    }
+
+    /// Read the relay key at `path`, which must exist and be a `K`.
+    fn get_key<K: tor_key_forge::ItemType>(keystore: &CTorRelayKeystore, path: CTorRelayPath) -> K {
+        let key = keystore
+            .get(&spec(path), &item_type(path))
+            .unwrap()
+            .unwrap();
+        let Ok(key) = key.downcast::<K>() else {
+            panic!("failed to downcast {path}");
+        };
+        *key
+    }
+
+    #[test]
+    fn get() {
+        use CTorRelayPath as P;
+
+        let (keystore, _keystore_dir) = init_keystore("foo");
+
+        for &path in ALL_KEYS {
+            if matches!(item_type(path), KeystoreItemType::Unknown { .. }) {
+                continue;
+            }
+            assert_found!(keystore, &spec(path), &item_type(path), true);
+        }
+
+        let public: ed25519::PublicKey = get_key(&keystore, P::Ed25519MasterIdPublicKey);
+        let secret: ed25519::ExpandedKeypair = get_key(&keystore, P::Ed25519MasterIdSecretKey);
+        assert_eq!(secret.public(), &public);
+
+        let ntor: curve25519::StaticKeypair = get_key(&keystore, P::SecretOnionKeyNtor);
+        assert_eq!(ntor.public, curve25519::PublicKey::from(&ntor.secret));
+
+        // A service key is never found in a relay keystore.
+        let path = CTorPath::Service {
+            nickname: HsNickname::from_str("allium-cepa").unwrap(),
+            path: CTorServicePath::PublicKey,
+        };
+        assert_found!(
+            keystore,
+            &TestCTorSpecifier(path),
+            &KeyType::Ed25519PublicKey,
+            false
+        );
+    }
+
+    #[test]
+    fn missing_and_malformed() {
+        use CTorRelayPath as P;
+
+        let (keystore, keystore_dir) = init_keystore("foo");
+
+        fs::remove_file(keystore_dir.path().join("ed25519_signing_secret_key")).unwrap();
+        assert_found!(
+            keystore,
+            &spec(P::Ed25519SigningSecretKey),
+            &KeyType::Ed25519ExpandedKeypair,
+            false
+        );
+
+        let ntor = fs::read(keystore_dir.path().join("secret_onion_key_ntor")).unwrap();
+        fs::write(
+            keystore_dir.path().join("secret_onion_key_ntor"),
+            &ntor[..ntor.len() - 1],
+        )
+        .unwrap();
+        let err = keystore
+            .get(
+                &spec(P::SecretOnionKeyNtor),
+                &KeyType::X25519StaticKeypair.into(),
+            )
+            .map(|_| ())
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Key secret_onion_key_ntor is malformed");
+    }
+
+    #[test]
+    fn unsupported_operation() {
+        let (keystore, _keystore_dir) = init_keystore("foo");
+        let spec = spec(CTorRelayPath::Ed25519MasterIdPublicKey);
+
+        let err = keystore
+            .remove(&spec, &KeyType::Ed25519PublicKey.into())
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), "Operation not supported: remove");
+
+        let err = keystore.insert(&DummyKey, &spec).unwrap_err();
+
+        assert_eq!(err.to_string(), "Operation not supported: insert");
+
+        // The RSA keys exist, but we can't read them.
+        for path in [CTorRelayPath::SecretIdKey, CTorRelayPath::SecretOnionKey] {
+            assert!(keystore.contains(&spec(path), &item_type(path)).unwrap());
+            let err = keystore
+                .get(&spec(path), &item_type(path))
+                .map(|_| ())
+                .unwrap_err();
+            assert_eq!(err.to_string(), "Operation not supported: read RSA key");
+        }
+    }
+
+    #[test]
+    fn wrong_keytype() {
+        let (keystore, _keystore_dir) = init_keystore("foo");
+
+        let err = keystore
+            .get(
+                &spec(CTorRelayPath::SecretOnionKeyNtor),
+                &KeyType::Ed25519ExpandedKeypair.into(),
+            )
+            .map(|_| ())
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Invalid item type Ed25519ExpandedKeypair for key secret_onion_key_ntor"
+        );
+    }
+
+    #[test]
+    fn list() {
+        let (keystore, keystore_dir) = init_keystore("foo");
+        let keys: Vec<_> = keystore.list().unwrap();
+
+        assert_eq!(keys.len(), ALL_KEYS.len());
+        for &path in ALL_KEYS {
+            assert!(keys.contains(&(CTorPath::relay(path).into(), item_type(path))));
+        }
+        assert!(keys.contains(&(
+            CTorPath::relay(CTorRelayPath::SecretIdKey).into(),
+            KeystoreItemType::Unknown {
+                arti_extension: "rsa".into()
+            }
+        )));
+
+        fs::remove_file(keystore_dir.path().join("ed25519_master_id_secret_key")).unwrap();
+        let keys: Vec<_> = keystore.list().unwrap();
+        assert_eq!(keys.len(), ALL_KEYS.len() - 1);
+        assert!(!keys.iter().any(|(path, _)| {
+            *path == CTorPath::relay(CTorRelayPath::Ed25519MasterIdSecretKey).into()
+        }));
+    }
+
+    #[test]
+    fn describe() {
+        let (keystore, keystore_dir) = init_keystore("foo");
+        let desc = keystore.describe().unwrap();
+
+        assert_eq!(desc.id, KeystoreId::from_str("foo").unwrap());
+        assert_eq!(desc.kind, KeystoreKind::CTorRelay);
+        assert_eq!(desc.kind.to_string(), "ctor-relay");
+        assert_eq!(desc.location.as_deref(), Some(keystore_dir.path()));
+        assert_eq!(desc.n_keys, 6);
+    }
+
+    #[test]
+    fn supports() {
+        let (keystore, _keystore_dir) = init_keystore("foo");
+
+        // We can't store anything, not even the keys we can read.
+        for &path in ALL_KEYS {
+            assert!(!keystore.supports(&item_type(path)));
+        }
+        assert!(!keystore.supports(&KeyType::Ed25519Keypair.into()));
+    }
+}
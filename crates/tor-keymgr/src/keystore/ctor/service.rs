@@ -2,22 +2,22 @@
 //!
 //! See [`CTorServiceKeystore`] for more details.
 
-use crate::keystore::ctor::err::{CTorKeystoreError, MalformedServiceKeyError};
+use crate::keystore::ctor::err::{CTorKeystoreError, MalformedTaggedKeyError};
+use crate::keystore::ctor::tagged::{parse_ed25519_keypair, parse_ed25519_public};
 use crate::keystore::ctor::CTorKeystore;
 use crate::keystore::fs_utils::{checked_op, FilesystemAction, FilesystemError};
-use crate::keystore::{EncodableItem, ErasedKey, KeySpecifier, Keystore, KeystoreId};
+use crate::keystore::{
+    EncodableItem, ErasedKey, KeySpecifier, Keystore, KeystoreDescription, KeystoreId, KeystoreKind,
+};
 use crate::{CTorPath, CTorServicePath, KeyPath, Result};
 
 use fs_mistrust::Mistrust;
 use tor_basic_utils::PathExt as _;
 use tor_error::internal;
 use tor_key_forge::{KeyType, KeystoreItemType};
-use tor_llcrypto::pk::ed25519;
 use tor_persist::hsnickname::HsNickname;
 
 use std::path::{Path, PathBuf};
-use std::result::Result as StdResult;
-use std::sync::Arc;
 
 /// A read-only C Tor service keystore.
 ///
@@ -161,7 +161,7 @@ impl Keystore for CTorServiceKeystore {
                 .map_err(CTorKeystoreError::Filesystem)?,
         };
 
-        let parse_err = |err: MalformedServiceKeyError| CTorKeystoreError::MalformedKey {
+        let parse_err = |err: MalformedTaggedKeyError| CTorKeystoreError::MalformedKey {
             path: path.rel_path_unchecked().into(),
             err: err.into(),
         };
@@ -195,6 +195,14 @@ impl Keystore for CTorServiceKeystore {
         Err(CTorKeystoreError::NotSupported { action: "remove" }.into())
     }
 
+    fn touch(
+        &self,
+        _key_spec: &dyn KeySpecifier,
+        _item_type: &KeystoreItemType,
+    ) -> Result<Option<()>> {
+        Err(CTorKeystoreError::NotSupported { action: "touch" }.into())
+    }
+
     fn list(&self) -> Result<Vec<(KeyPath, KeystoreItemType)>> {
         use crate::CTorServicePath::*;
         use itertools::Itertools;
@@ -227,70 +235,21 @@ impl Keystore for CTorServiceKeystore {
             .filter_map_ok(|(path, key_type, res)| res.then_some((path.into(), key_type)))
             .collect()
     }
-}
-
-/// Helper for parsing C Tor's ed25519 key format.
-macro_rules! parse_ed25519 {
-    ($key:expr, $parse_fn:expr, $tag:expr, $key_len:expr) => {{
-        let expected_len = $tag.len() + $key_len;
-
-        if $key.len() != expected_len {
-            return Err(MalformedServiceKeyError::InvalidKeyLen {
-                len: $key.len(),
-                expected_len,
-            });
-        }
-
-        let (tag, key) = $key.split_at($tag.len());
 
-        if tag != $tag {
-            return Err(MalformedServiceKeyError::InvalidTag {
-                tag: tag.to_vec(),
-                expected_tag: $tag.into(),
-            });
-        }
-
-        ($parse_fn)(key)
-    }};
-}
-
-/// Helper for parsing C Tor's ed25519 public key format.
-fn parse_ed25519_public(key: &[u8]) -> StdResult<ed25519::PublicKey, MalformedServiceKeyError> {
-    /// The tag C Tor ed25519 public keys are expected to begin with.
-    const PUBKEY_TAG: &[u8] = b"== ed25519v1-public: type0 ==\0\0\0";
-    /// The size of an ed25519 public key.
-    const PUBKEY_LEN: usize = 32;
-
-    parse_ed25519!(
-        key,
-        |key| ed25519::PublicKey::try_from(key)
-            .map_err(|e| MalformedServiceKeyError::from(Arc::new(e))),
-        PUBKEY_TAG,
-        PUBKEY_LEN
-    )
-}
+    fn describe(&self) -> Result<KeystoreDescription> {
+        Ok(KeystoreDescription::new(
+            self.keystore.id.clone(),
+            KeystoreKind::CTorService,
+            Some(self.keystore.keystore_dir.as_path().to_owned()),
+            self.list()?.len(),
+        ))
+    }
 
-/// Helper for parsing C Tor's ed25519 keypair format.
-fn parse_ed25519_keypair(
-    key: &[u8],
-) -> StdResult<ed25519::ExpandedKeypair, MalformedServiceKeyError> {
-    /// The tag C Tor ed25519 keypairs are expected to begin with.
-    const KEYPAIR_TAG: &[u8] = b"== ed25519v1-secret: type0 ==\0\0\0";
-    /// The size of an ed25519 keypair.
-    const KEYPAIR_LEN: usize = 64;
-
-    parse_ed25519!(
-        key,
-        |key: &[u8]| {
-            let key: [u8; 64] = key
-                .try_into()
-                .map_err(|_| internal!("bad length on expanded ed25519 secret key "))?;
-            ed25519::ExpandedKeypair::from_secret_key_bytes(key)
-                .ok_or(MalformedServiceKeyError::Ed25519Keypair)
-        },
-        KEYPAIR_TAG,
-        KEYPAIR_LEN
-    )
+    fn supports(&self, _item_type: &KeystoreItemType) -> bool {
+        // This keystore is read-only: it can hold service keys,
+        // but it can't be used to store them.
+        false
+    }
 }
 
 #[cfg(test)]
@@ -456,4 +415,16 @@ mod tests {
             .iter()
             .any(|(_, key_type)| *key_type == KeyType::Ed25519PublicKey.into()));
     }
+
+    #[test]
+    fn describe() {
+        let (keystore, keystore_dir) = init_keystore("foo", "allium-cepa");
+        let desc = keystore.describe().unwrap();
+
+        assert_eq!(desc.id, KeystoreId::from_str("foo").unwrap());
+        assert_eq!(desc.kind, KeystoreKind::CTorService);
+        assert_eq!(desc.kind.to_string(), "ctor-service");
+        assert_eq!(desc.location.as_deref(), Some(keystore_dir.path()));
+        assert_eq!(desc.n_keys, 2);
+    }
 }
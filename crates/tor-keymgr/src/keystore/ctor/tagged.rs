@@ -0,0 +1,150 @@
+//! Parsers for C Tor's tagged key file format.
+//!
+//! C Tor stores the keys of onion services and relays in files that consist of
+//! a 32-byte header (`== <type>: <tag> ==`, padded with NUL bytes)
+//! followed by the raw key material.
+
+use crate::keystore::ctor::err::MalformedTaggedKeyError;
+
+use tor_error::internal;
+use tor_llcrypto::pk::{curve25519, ed25519};
+
+use std::result::Result as StdResult;
+use std::sync::Arc;
+
+/// Check that `key` consists of the header `tag` followed by `key_len` bytes,
+/// and return those bytes.
+fn strip_tag<'k>(
+    key: &'k [u8],
+    tag: &[u8],
+    key_len: usize,
+) -> StdResult<&'k [u8], MalformedTaggedKeyError> {
+    let expected_len = tag.len() + key_len;
+
+    if key.len() != expected_len {
+        return Err(MalformedTaggedKeyError::InvalidKeyLen {
+            len: key.len(),
+            expected_len,
+        });
+    }
+
+    let (found_tag, key) = key.split_at(tag.len());
+
+    if found_tag != tag {
+        return Err(MalformedTaggedKeyError::InvalidTag {
+            tag: found_tag.to_vec(),
+            expected_tag: tag.into(),
+        });
+    }
+
+    Ok(key)
+}
+
+/// Helper for parsing C Tor's ed25519 public key format.
+pub(super) fn parse_ed25519_public(
+    key: &[u8],
+) -> StdResult<ed25519::PublicKey, MalformedTaggedKeyError> {
+    /// The tag C Tor ed25519 public keys are expected to begin with.
+    const PUBKEY_TAG: &[u8] = b"== ed25519v1-public: type0 ==\0\0\0";
+    /// The size of an ed25519 public key.
+    const PUBKEY_LEN: usize = 32;
+
+    let key = strip_tag(key, PUBKEY_TAG, PUBKEY_LEN)?;
+    ed25519::PublicKey::try_from(key).map_err(|e| MalformedTaggedKeyError::from(Arc::new(e)))
+}
+
+/// Helper for parsing C Tor's ed25519 keypair format.
+pub(super) fn parse_ed25519_keypair(
+    key: &[u8],
+) -> StdResult<ed25519::ExpandedKeypair, MalformedTaggedKeyError> {
+    /// The tag C Tor ed25519 keypairs are expected to begin with.
+    const KEYPAIR_TAG: &[u8] = b"== ed25519v1-secret: type0 ==\0\0\0";
+    /// The size of an ed25519 keypair.
+    const KEYPAIR_LEN: usize = 64;
+
+    let key: [u8; KEYPAIR_LEN] = strip_tag(key, KEYPAIR_TAG, KEYPAIR_LEN)?
+        .try_into()
+        .map_err(|_| internal!("bad length on expanded ed25519 secret key "))?;
+    ed25519::ExpandedKeypair::from_secret_key_bytes(key)
+        .ok_or(MalformedTaggedKeyError::Ed25519Keypair)
+}
+
+/// Helper for parsing C Tor's curve25519 onion keypair format.
+///
+/// The key material is the secret key followed by the public key.
+pub(super) fn parse_x25519_onion_keypair(
+    key: &[u8],
+) -> StdResult<curve25519::StaticKeypair, MalformedTaggedKeyError> {
+    /// The tag C Tor curve25519 onion keys are expected to begin with.
+    const KEYPAIR_TAG: &[u8] = b"== c25519v1: onion ==\0\0\0\0\0\0\0\0\0\0\0";
+    /// The size of a curve25519 keypair.
+    const KEYPAIR_LEN: usize = 64;
+
+    let key = strip_tag(key, KEYPAIR_TAG, KEYPAIR_LEN)?;
+    let (secret, public) = key.split_at(32);
+    let secret: [u8; 32] = secret
+        .try_into()
+        .map_err(|_| internal!("bad length on curve25519 secret key"))?;
+    let public: [u8; 32] = public
+        .try_into()
+        .map_err(|_| internal!("bad length on curve25519 public key"))?;
+
+    let secret = curve25519::StaticSecret::from(secret);
+    let public = curve25519::PublicKey::from(public);
+    // Don't trust the stored public key: it must be the one that belongs to the secret key.
+    if curve25519::PublicKey::from(&secret) != public {
+        return Err(MalformedTaggedKeyError::X25519KeyMismatch);
+    }
+
+    Ok(curve25519::StaticKeypair { secret, public })
+}
+
+#[cfg(test)]
+mod tests {
+    // @@ begin test lint list maintained by maint/add_warning @@
+    #![allow(clippy::bool_assert_comparison)]
+    #![allow(clippy::clone_on_copy)]
+    #![allow(clippy::dbg_macro)]
+    #![allow(clippy::mixed_attributes_style)]
+    #![allow(clippy::print_stderr)]
+    #![allow(clippy::print_stdout)]
+    #![allow(clippy::single_char_pattern)]
+    #![allow(clippy::unwrap_used)]
+    #![allow(clippy::unchecked_duration_subtraction)]
+    #![allow(clippy::useless_vec)]
+    #![allow(clippy::needless_pass_by_value)]
+    //! <!-- @@ end test lint list maintained by maint/add_warning @@ -->
+
+    use super::*;
+
+    const NTOR_KEY: &[u8] =
+        include_bytes!("../../../testdata/tor-relay/keys/secret_onion_key_ntor");
+
+    #[test]
+    fn x25519_onion_keypair() {
+        let kp = parse_x25519_onion_keypair(NTOR_KEY).unwrap();
+        assert_eq!(kp.public.as_bytes(), &NTOR_KEY[64..]);
+
+        // Truncated.
+        let err = parse_x25519_onion_keypair(&NTOR_KEY[..95]).unwrap_err();
+        assert!(matches!(
+            err,
+            MalformedTaggedKeyError::InvalidKeyLen {
+                len: 95,
+                expected_len: 96
+            }
+        ));
+
+        // An ed25519 key is not a curve25519 key.
+        let mut wrong_tag = NTOR_KEY.to_vec();
+        wrong_tag[..32].copy_from_slice(b"== ed25519v1-secret: type0 ==\0\0\0");
+        let err = parse_x25519_onion_keypair(&wrong_tag).unwrap_err();
+        assert!(matches!(err, MalformedTaggedKeyError::InvalidTag { .. }));
+
+        // The public key doesn't belong to the secret key.
+        let mut mismatched = NTOR_KEY.to_vec();
+        mismatched[95] ^= 1;
+        let err = parse_x25519_onion_keypair(&mismatched).unwrap_err();
+        assert!(matches!(err, MalformedTaggedKeyError::X25519KeyMismatch));
+    }
+}
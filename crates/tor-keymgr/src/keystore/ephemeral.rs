@@ -4,16 +4,26 @@ pub(crate) mod err;
 
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 
 use tor_key_forge::{EncodableItem, ErasedKey, KeystoreItem, KeystoreItemType};
 
 use crate::keystore::ephemeral::err::ArtiEphemeralKeystoreError;
+use crate::keystore::{KeystoreDescription, KeystoreKind};
 use crate::Error;
 use crate::{ArtiPath, KeyPath, KeySpecifier, Keystore, KeystoreId};
 
 /// The identifier of a key stored in the `ArtiEphemeralKeystore`.
 type KeyIdent = (ArtiPath, KeystoreItemType);
 
+/// A key stored in the `ArtiEphemeralKeystore`.
+struct Entry {
+    /// The key itself.
+    item: KeystoreItem,
+    /// The last time at which the key was inserted or touched.
+    modified: SystemTime,
+}
+
 /// The Ephemeral Arti key store
 ///
 /// This is a purely in-memory key store. Keys written to this store
@@ -28,8 +38,8 @@ type KeyIdent = (ArtiPath, KeystoreItemType);
 pub struct ArtiEphemeralKeystore {
     /// Identifier hard-coded to 'ephemeral'
     id: KeystoreId,
-    /// Keys stored as [`KeystoreItem`].
-    key_dictionary: Arc<Mutex<HashMap<KeyIdent, KeystoreItem>>>,
+    /// Keys stored as [`KeystoreItem`]s, along with their modification times.
+    key_dictionary: Arc<Mutex<HashMap<KeyIdent, Entry>>>,
 }
 
 impl ArtiEphemeralKeystore {
@@ -60,6 +70,27 @@ impl Keystore for ArtiEphemeralKeystore {
         Ok(contains_key)
     }
 
+    fn contains_many(
+        &self,
+        keys: &[(&dyn KeySpecifier, &KeystoreItemType)],
+    ) -> Result<Vec<bool>, Error> {
+        let arti_paths = keys
+            .iter()
+            .map(|(key_spec, item_type)| {
+                let arti_path = key_spec
+                    .arti_path()
+                    .map_err(ArtiEphemeralKeystoreError::ArtiPathUnavailableError)?;
+                Ok((arti_path, (*item_type).clone()))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        // Only take the lock once, so that we see a consistent view of the keystore.
+        let key_dictionary = self.key_dictionary.lock().expect("lock poisoned");
+        Ok(arti_paths
+            .into_iter()
+            .map(|key| key_dictionary.contains_key(&key))
+            .collect())
+    }
+
     fn get(
         &self,
         key_spec: &dyn KeySpecifier,
@@ -70,8 +101,8 @@ impl Keystore for ArtiEphemeralKeystore {
             .map_err(ArtiEphemeralKeystoreError::ArtiPathUnavailableError)?;
         let key_dictionary = self.key_dictionary.lock().expect("lock poisoned");
         match key_dictionary.get(&(arti_path.clone(), item_type.clone())) {
-            Some(key) => {
-                let key: KeystoreItem = key.clone();
+            Some(entry) => {
+                let key: KeystoreItem = entry.item.clone();
                 let key: ErasedKey = key.into_erased()?;
                 Ok(Some(key))
             }
@@ -93,7 +124,11 @@ impl Keystore for ArtiEphemeralKeystore {
 
         // save to dictionary
         let mut key_dictionary = self.key_dictionary.lock().expect("lock poisoned");
-        let _ = key_dictionary.insert((arti_path, item_type), key_data);
+        let entry = Entry {
+            item: key_data,
+            modified: SystemTime::now(),
+        };
+        let _ = key_dictionary.insert((arti_path, item_type), entry);
         Ok(())
     }
 
@@ -111,6 +146,34 @@ impl Keystore for ArtiEphemeralKeystore {
             .map(|_| ()))
     }
 
+    fn touch(
+        &self,
+        key_spec: &dyn KeySpecifier,
+        item_type: &KeystoreItemType,
+    ) -> Result<Option<()>, Error> {
+        let arti_path = key_spec
+            .arti_path()
+            .map_err(ArtiEphemeralKeystoreError::ArtiPathUnavailableError)?;
+        let mut key_dictionary = self.key_dictionary.lock().expect("lock poisoned");
+        Ok(key_dictionary
+            .get_mut(&(arti_path, item_type.clone()))
+            .map(|entry| entry.modified = SystemTime::now()))
+    }
+
+    fn modified(
+        &self,
+        key_spec: &dyn KeySpecifier,
+        item_type: &KeystoreItemType,
+    ) -> Result<Option<SystemTime>, Error> {
+        let arti_path = key_spec
+            .arti_path()
+            .map_err(ArtiEphemeralKeystoreError::ArtiPathUnavailableError)?;
+        let key_dictionary = self.key_dictionary.lock().expect("lock poisoned");
+        Ok(key_dictionary
+            .get(&(arti_path, item_type.clone()))
+            .map(|entry| entry.modified))
+    }
+
     fn list(&self) -> Result<Vec<(KeyPath, KeystoreItemType)>, Error> {
         let key_dictionary = self.key_dictionary.lock().expect("lock poisoned");
         Ok(key_dictionary
@@ -118,6 +181,16 @@ impl Keystore for ArtiEphemeralKeystore {
             .map(|(arti_path, item_type)| (arti_path.clone().into(), item_type.clone()))
             .collect())
     }
+
+    fn describe(&self) -> Result<KeystoreDescription, Error> {
+        let n_keys = self.key_dictionary.lock().expect("lock poisoned").len();
+        Ok(KeystoreDescription::new(
+            self.id.clone(),
+            KeystoreKind::Ephemeral,
+            None,
+            n_keys,
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -142,7 +215,7 @@ mod tests {
 
     use super::*;
 
-    use crate::test_utils::TestSpecifier;
+    use crate::test_utils::{TestSpecifier, TEST_SPECIFIER_PATH};
 
     // some helper methods
 
@@ -191,6 +264,30 @@ mod tests {
             .unwrap());
     }
 
+    #[test]
+    fn contains_many() {
+        let key_store = ArtiEphemeralKeystore::new("test-ephemeral".to_string());
+        assert!(key_store
+            .insert(key().as_ref(), key_spec().as_ref())
+            .is_ok());
+
+        let spec = key_spec();
+        let other_spec = TestSpecifier::new("-other");
+        let (key_type, key_type_bad) = (key_type(), key_type_bad());
+        let keys: Vec<(&dyn KeySpecifier, &KeystoreItemType)> = vec![
+            (&other_spec, &key_type),
+            (spec.as_ref(), &key_type),
+            (spec.as_ref(), &key_type_bad),
+        ];
+        let expected = keys
+            .iter()
+            .map(|(spec, ty)| key_store.contains(*spec, ty).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(expected, vec![false, true, false]);
+        assert_eq!(key_store.contains_many(&keys).unwrap(), expected);
+        assert!(key_store.contains_many(&[]).unwrap().is_empty());
+    }
+
     #[test]
     fn get() {
         let key_store = ArtiEphemeralKeystore::new("test-ephemeral".to_string());
@@ -277,4 +374,122 @@ mod tests {
             .is_ok());
         assert_eq!(key_store.list().unwrap().len(), 1);
     }
+
+    #[test]
+    fn list_private() {
+        let key_store = ArtiEphemeralKeystore::new("test-ephemeral".to_string());
+        let keypair = ed25519::Keypair::generate(&mut testing_rng());
+
+        key_store
+            .insert(&keypair, &TestSpecifier::new("-keypair"))
+            .unwrap();
+        key_store
+            .insert(&keypair.verifying_key(), &TestSpecifier::new("-public"))
+            .unwrap();
+
+        let mut entries = key_store
+            .list()
+            .unwrap()
+            .into_iter()
+            .map(|(path, ty)| (path.arti().unwrap().to_string(), ty.is_private()))
+            .collect::<Vec<_>>();
+        entries.sort();
+
+        assert_eq!(
+            entries,
+            vec![
+                (format!("{TEST_SPECIFIER_PATH}-keypair"), true),
+                (format!("{TEST_SPECIFIER_PATH}-public"), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn touch() {
+        let key_store = ArtiEphemeralKeystore::new("test-ephemeral".to_string());
+        let item_type = KeyType::Ed25519Keypair.into();
+
+        assert_eq!(
+            key_store.touch(key_spec().as_ref(), &item_type).unwrap(),
+            None
+        );
+        assert_eq!(
+            key_store.modified(key_spec().as_ref(), &item_type).unwrap(),
+            None
+        );
+
+        key_store
+            .insert(key().as_ref(), key_spec().as_ref())
+            .unwrap();
+        let inserted = key_store
+            .modified(key_spec().as_ref(), &item_type)
+            .unwrap()
+            .unwrap();
+
+        // Pretend the key was inserted a long time ago,
+        // so that we don't depend on the resolution of the system clock.
+        let long_ago = inserted - std::time::Duration::from_secs(86400);
+        key_store
+            .key_dictionary
+            .lock()
+            .unwrap()
+            .values_mut()
+            .for_each(|entry| entry.modified = long_ago);
+
+        assert_eq!(
+            key_store.touch(key_spec().as_ref(), &item_type).unwrap(),
+            Some(())
+        );
+        let touched = key_store
+            .modified(key_spec().as_ref(), &item_type)
+            .unwrap()
+            .unwrap();
+        assert!(touched >= inserted);
+    }
+
+    #[test]
+    fn list_page() {
+        let key_store = ArtiEphemeralKeystore::new("test-ephemeral".to_string());
+        for i in 0..5 {
+            let keypair = ed25519::Keypair::generate(&mut testing_rng());
+            key_store
+                .insert(&keypair, &TestSpecifier::new(format!("-{i}")))
+                .unwrap();
+        }
+
+        // The default implementation sorts the keys by path.
+        let paths = |page: Vec<(KeyPath, KeystoreItemType)>| {
+            page.into_iter()
+                .map(|(path, _)| path.to_string())
+                .collect::<Vec<_>>()
+        };
+        let (page, has_more) = key_store.list_page(1, 3).unwrap();
+        assert!(has_more);
+        assert_eq!(
+            paths(page),
+            (1..4)
+                .map(|i| format!("{TEST_SPECIFIER_PATH}-{i}"))
+                .collect::<Vec<_>>()
+        );
+        let (page, has_more) = key_store.list_page(4, 3).unwrap();
+        assert!(!has_more);
+        assert_eq!(paths(page), vec![format!("{TEST_SPECIFIER_PATH}-4")]);
+    }
+
+    #[test]
+    fn describe() {
+        let key_store = ArtiEphemeralKeystore::new("test-ephemeral".to_string());
+        let desc = key_store.describe().unwrap();
+
+        assert_eq!(desc.id, KeystoreId("test-ephemeral".to_string()));
+        assert_eq!(desc.kind, KeystoreKind::Ephemeral);
+        assert_eq!(desc.kind.to_string(), "ephemeral");
+        assert_eq!(desc.location, None);
+        assert_eq!(desc.n_keys, 0);
+
+        key_store
+            .insert(key().as_ref(), key_spec().as_ref())
+            .unwrap();
+        assert_eq!(key_store.describe().unwrap().n_keys, 1);
+    }
 }
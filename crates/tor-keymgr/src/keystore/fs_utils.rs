@@ -13,6 +13,7 @@ use fs_mistrust::CheckedDir;
 use tor_error::{ErrorKind, HasKind};
 use tor_key_forge::KeystoreItemType;
 
+use crate::keystore::arti::naming::FileNamingScheme;
 use crate::{ArtiPathUnavailableError, KeySpecifier};
 
 /// The path of a key, relative to a [`CheckedDir`].
@@ -27,17 +28,17 @@ pub(super) struct RelKeyPath<'a> {
 }
 
 impl<'a> RelKeyPath<'a> {
-    /// Create a new [`RelKeyPath`] representing an `ArtiPath`.
+    /// Create a new [`RelKeyPath`] representing an `ArtiPath`,
+    /// named according to `naming`.
     ///
     /// Returns an error if `key_spec` does not have an `ArtiPath`.
     pub(super) fn arti(
         dir: &'a CheckedDir,
+        naming: &dyn FileNamingScheme,
         key_spec: &dyn KeySpecifier,
         item_type: &KeystoreItemType,
     ) -> Result<Self, ArtiPathUnavailableError> {
-        let arti_path: String = key_spec.arti_path()?.into();
-        let mut path = PathBuf::from(arti_path);
-        path.set_extension(item_type.arti_extension());
+        let path = naming.rel_path(&key_spec.arti_path()?, item_type);
         Ok(Self { dir, path })
     }
 
@@ -66,6 +67,18 @@ impl<'a> RelKeyPath<'a> {
         &self.path
     }
 
+    /// Return a new `RelKeyPath` for the sibling of this path
+    /// obtained by appending `.{extension}` to its file name.
+    pub(super) fn with_added_extension(&self, extension: &str) -> Self {
+        let mut path = self.path.clone().into_os_string();
+        path.push(".");
+        path.push(extension);
+        Self {
+            dir: self.dir,
+            path: path.into(),
+        }
+    }
+
     /// Return the [`CheckedDir`] of this `RelKeyPath`.
     pub(super) fn checked_dir(&self) -> &CheckedDir {
         self.dir
@@ -50,6 +50,8 @@
 // stable
 
 mod arti_path;
+#[cfg(feature = "keymgr")]
+mod bundle;
 pub mod config;
 mod err;
 mod key_specifier;
@@ -69,18 +71,24 @@ pub use err::{
     ArtiPathSyntaxError, Error, KeystoreCorruptionError, KeystoreError, UnknownKeyTypeError,
 };
 pub use key_specifier::{
-    ArtiPathRange, ArtiPathUnavailableError, CTorPath, CTorServicePath,
-    InvalidKeyPathComponentValue, KeyCertificateSpecifier, KeyPath, KeyPathError, KeyPathInfo,
-    KeyPathInfoBuilder, KeyPathInfoExtractor, KeyPathPattern, KeySpecifier, KeySpecifierComponent,
+    find_colliding_key_specifiers, ArtiPathRange, ArtiPathUnavailableError, CTorPath,
+    CTorRelayPath, CTorServicePath, InvalidKeyPathComponentValue, KeyCertificateSpecifier, KeyPath,
+    KeyPathError, KeyPathInfo, KeyPathInfoBuilder, KeyPathInfoExtractor, KeyPathPattern,
+    KeySpecifier, KeySpecifierCollision, KeySpecifierComponent,
     KeySpecifierComponentViaDisplayFromStr, KeySpecifierPattern,
 };
 
 #[cfg(feature = "keymgr")]
 #[cfg_attr(docsrs, doc(cfg(feature = "keymgr")))]
 pub use {
-    keystore::arti::ArtiNativeKeystore,
-    keystore::Keystore,
-    mgr::{KeyMgr, KeyMgrBuilder, KeyMgrBuilderError, KeystoreEntry},
+    bundle::KeyBundle,
+    keystore::arti::naming::{DefaultFileNamingScheme, FileNamingScheme},
+    keystore::arti::{ArtiNativeKeystore, InsecurePermissionsPolicy},
+    keystore::{Keystore, KeystoreDescription, KeystoreKind, KeystoreObserver, KeystoreOperation},
+    mgr::{
+        ImportEntry, ImportReport, KeyMgr, KeyMgrBuilder, KeyMgrBuilderError, KeystoreEntry,
+        MalformedImportEntry,
+    },
     ssh_key,
 };
 
@@ -93,7 +101,7 @@ pub use keystore::ephemeral::ArtiEphemeralKeystore;
 
 #[cfg(all(feature = "keymgr", feature = "ctor-keystore"))]
 #[cfg_attr(docsrs, doc(cfg(all(feature = "keymgr", feature = "ctor-keystore"))))]
-pub use keystore::ctor::{CTorClientKeystore, CTorServiceKeystore};
+pub use keystore::ctor::{CTorClientKeystore, CTorRelayKeystore, CTorServiceKeystore};
 
 #[doc(hidden)]
 pub use key_specifier::derive as key_specifier_derive;
@@ -2,6 +2,7 @@
 //!
 //! See the [`KeyMgr`] docs for more details.
 
+use crate::bundle::{self, KeyBundle};
 use crate::{
     ArtiPath, BoxedKeystore, KeyCertificateSpecifier, KeyPath, KeyPathError, KeyPathInfo,
     KeyPathInfoExtractor, KeyPathPattern, KeySpecifier, KeystoreCorruptionError, KeystoreId,
@@ -11,10 +12,12 @@ use crate::{
 use itertools::Itertools;
 use std::iter;
 use std::result::Result as StdResult;
-use tor_error::{bad_api_usage, internal, into_bad_api_usage};
+use std::sync::Mutex;
+use tor_error::{bad_api_usage, internal, into_bad_api_usage, ErrorReport as _};
 use tor_key_forge::{
     ItemType, Keygen, KeygenRng, KeystoreItemType, ToEncodableCert, ToEncodableKey,
 };
+use tracing::warn;
 
 /// A key manager that acts as a frontend to a primary [`Keystore`](crate::Keystore) and
 /// any number of secondary [`Keystore`](crate::Keystore)s.
@@ -53,6 +56,9 @@ pub struct KeyMgr {
     /// using `inventory`.
     #[builder(default, setter(skip))]
     key_info_extractors: Vec<&'static dyn KeyPathInfoExtractor>,
+    /// A lock held by [`KeyMgr::insert_if_absent`] while it checks for and inserts a key.
+    #[builder(default, setter(skip))]
+    insert_lock: Mutex<()>,
 }
 
 /// A keystore entry descriptor.
@@ -73,6 +79,47 @@ pub struct KeystoreEntry<'a> {
     keystore_id: &'a KeystoreId,
 }
 
+impl KeystoreEntry<'_> {
+    /// Whether this entry includes private key material.
+    ///
+    /// See [`KeystoreItemType::is_private`].
+    pub fn is_private(&self) -> bool {
+        self.key_type.is_private()
+    }
+}
+
+/// The outcome of validating a [`KeyBundle`] with [`KeyMgr::validate_import`].
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct ImportReport {
+    /// The entries that would be imported, in the order they appear in the bundle.
+    pub entries: Vec<ImportEntry>,
+    /// The entries of the bundle that couldn't be parsed.
+    pub errors: Vec<MalformedImportEntry>,
+}
+
+/// An entry of a key bundle, as described by [`KeyMgr::validate_import`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ImportEntry {
+    /// The path of the item.
+    pub path: ArtiPath,
+    /// The type of the item.
+    pub item_type: KeystoreItemType,
+    /// Whether the primary keystore already contains an item with this path and type.
+    pub would_overwrite: bool,
+}
+
+/// A malformed entry of a key bundle, as described by [`KeyMgr::validate_import`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct MalformedImportEntry {
+    /// The offset of the start of the entry within the bundle.
+    pub offset: usize,
+    /// A description of the problem.
+    pub error: String,
+}
+
 impl KeyMgrBuilder {
     /// Construct a [`KeyMgr`] from this builder.
     pub fn build(self) -> StdResult<KeyMgr, KeyMgrBuilderError> {
@@ -86,6 +133,15 @@ impl KeyMgrBuilder {
             ));
         }
 
+        for collision in crate::find_colliding_key_specifiers() {
+            warn!(
+                "Internal issue: key specifiers {} share the same prefix ({:?}) and role ({:?})",
+                collision.type_names.join(", "),
+                collision.prefix,
+                collision.role,
+            );
+        }
+
         keymgr.key_info_extractors = inventory::iter::<&'static dyn KeyPathInfoExtractor>
             .into_iter()
             .copied()
@@ -201,6 +257,9 @@ impl KeyMgr {
     /// Returns [`Error::KeyAlreadyExists`](crate::Error::KeyAlreadyExists)
     /// if the key already exists in the specified key store and `overwrite` is `false`.
     ///
+    /// Returns [`Error::UnsupportedItemType`](crate::Error::UnsupportedItemType)
+    /// if the specified key store can't store keys of type `K::Key`.
+    ///
     /// **IMPORTANT**: using this function concurrently with any other `KeyMgr` operation that
     /// mutates the key store state is **not** recommended, as it can yield surprising results! The
     /// outcome of [`KeyMgr::generate`] depends on whether the selected key store
@@ -222,7 +281,7 @@ impl KeyMgr {
         K: ToEncodableKey,
         K::Key: Keygen,
     {
-        let store = self.select_keystore(&selector)?;
+        let store = self.select_keystore_for(&selector, &K::Key::item_type())?;
 
         if overwrite || !store.contains(key_spec, &K::Key::item_type())? {
             let key = K::Key::generate(rng)?;
@@ -246,6 +305,10 @@ impl KeyMgr {
     ///
     /// Returns an error if the selected keystore is not the primary keystore or one of the
     /// configured secondary stores.
+    ///
+    /// Returns [`Error::UnsupportedItemType`](crate::Error::UnsupportedItemType)
+    /// if the selected keystore can't store keys of this type.
+    /// Use [`KeystoreSelector::Id`] to insert the key into a keystore that can.
     pub fn insert<K: ToEncodableKey>(
         &self,
         key: K,
@@ -254,8 +317,8 @@ impl KeyMgr {
         overwrite: bool,
     ) -> Result<Option<K>> {
         let key = key.to_encodable_key();
-        let store = self.select_keystore(&selector)?;
         let key_type = K::Key::item_type();
+        let store = self.select_keystore_for(&selector, &key_type)?;
         let old_key: Option<K> = self.get_from_store(key_spec, &key_type, [store].into_iter())?;
 
         if old_key.is_some() && !overwrite {
@@ -266,6 +329,41 @@ impl KeyMgr {
         }
     }
 
+    /// Insert `key` into the [`Keystore`](crate::Keystore) specified by `selector`,
+    /// unless that keystore already contains a key of the same type at `key_spec`.
+    ///
+    /// Returns `Ok(true)` if the key was inserted,
+    /// and `Ok(false)` if a key already existed (in which case nothing is written).
+    ///
+    /// Unlike [`insert`](KeyMgr::insert), this never overwrites an existing key.
+    ///
+    /// Calls to `insert_if_absent` on the same `KeyMgr` are serialized,
+    /// so two of them can't both insert a key at the same `key_spec`.
+    /// However, this is only as atomic as the keystore allows:
+    /// most keystores are not transactional, so another `KeyMgr` or process
+    /// (or a call to `insert` on this `KeyMgr`) can still write a key
+    /// between our check and our write, and that key will be overwritten.
+    ///
+    /// Returns an error if the selected keystore is not the primary keystore or one of the
+    /// configured secondary stores, or if it can't store keys of this type.
+    pub fn insert_if_absent<K: ToEncodableKey>(
+        &self,
+        key: K,
+        key_spec: &dyn KeySpecifier,
+        selector: KeystoreSelector,
+    ) -> Result<bool> {
+        let key = key.to_encodable_key();
+        let key_type = K::Key::item_type();
+        let store = self.select_keystore_for(&selector, &key_type)?;
+
+        let _guard = self.insert_lock.lock().expect("poisoned lock");
+        if store.contains(key_spec, &key_type)? {
+            return Ok(false);
+        }
+        let () = store.insert(&key, key_spec)?;
+        Ok(true)
+    }
+
     /// Remove the key identified by `key_spec` from the [`Keystore`](crate::Keystore)
     /// specified by `selector`.
     ///
@@ -308,6 +406,148 @@ impl KeyMgr {
         store.remove(entry.key_path(), entry.key_type())
     }
 
+    /// Move the key identified by `key_spec` from the keystore `from` to the keystore `to`.
+    ///
+    /// The key is removed from `from` only after it has been successfully written to `to`.
+    /// If the key can't be removed from `from`, it is removed from `to` again,
+    /// so that on failure the key is left only in `from`.
+    ///
+    /// Moving a key to the keystore it is already in does nothing.
+    ///
+    /// Returns an error if `from` or `to` is not the ID of the primary keystore
+    /// or of one of the configured secondary stores,
+    /// or if the key does not exist in `from`.
+    ///
+    /// Returns [`Error::UnsupportedItemType`](crate::Error::UnsupportedItemType)
+    /// if `to` can't store keys of type `K::Key`,
+    /// and [`Error::KeyAlreadyExists`](crate::Error::KeyAlreadyExists)
+    /// if the key already exists in `to`.
+    ///
+    /// **IMPORTANT**: like [`KeyMgr::generate`], this function should not be used
+    /// concurrently with any other `KeyMgr` operation that mutates this key.
+    pub fn migrate_key<K: ToEncodableKey>(
+        &self,
+        key_spec: &dyn KeySpecifier,
+        from: &KeystoreId,
+        to: &KeystoreId,
+    ) -> Result<()> {
+        let key_type = K::Key::item_type();
+        let from_store = self.find_keystore(from)?;
+        let to_store = self.select_keystore_for(&KeystoreSelector::Id(to), &key_type)?;
+
+        let Some(key) =
+            self.get_from_store_raw::<K::Key>(key_spec, &key_type, [from_store].into_iter())?
+        else {
+            return Err(bad_api_usage!("key not found in keystore {from}").into());
+        };
+
+        if from == to {
+            return Ok(());
+        }
+
+        if to_store.contains(key_spec, &key_type)? {
+            return Err(crate::Error::KeyAlreadyExists);
+        }
+
+        let () = to_store.insert(&key, key_spec)?;
+
+        match from_store.remove(key_spec, &key_type) {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                // Roll back, so that the key is only in its original keystore.
+                if let Err(rollback_err) = to_store.remove(key_spec, &key_type) {
+                    warn!(
+                        "failed to remove key from keystore {to} after failing to move it there: {}",
+                        rollback_err.report()
+                    );
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Rotate the key identified by `spec`, retiring any sufficiently old versions of it.
+    ///
+    /// Each version of the key is stored under the [`ArtiPath`] of `spec`, followed by
+    /// a denotator recording its creation time (in seconds since the UNIX epoch).
+    ///
+    /// This generates a new key using `generate_fn`, inserts it into the keystore
+    /// specified by `selector` (as the version created at `now`),
+    /// and then removes all the versions of this key from that keystore
+    /// that were created at least `retire_after` before `now`.
+    /// The new version is always inserted _before_ any of the old ones are removed,
+    /// so there is no point in time at which no version of the key exists.
+    ///
+    /// Returns the [`KeyPath`] of the newly inserted key.
+    ///
+    /// Returns [`Error::KeyAlreadyExists`](crate::Error::KeyAlreadyExists)
+    /// if a version of the key created at `now` already exists.
+    ///
+    /// Returns an error if `spec` does not have an [`ArtiPath`].
+    ///
+    /// **IMPORTANT**: like [`KeyMgr::generate`], this function should not be used
+    /// concurrently with any other `KeyMgr` operation that mutates the versions of this key.
+    #[cfg(feature = "experimental-api")]
+    pub fn rotate_key<K: ToEncodableKey>(
+        &self,
+        spec: &dyn KeySpecifier,
+        generate_fn: impl FnOnce() -> Result<K>,
+        now: std::time::SystemTime,
+        retire_after: std::time::Duration,
+        selector: KeystoreSelector,
+    ) -> Result<KeyPath> {
+        use std::time::SystemTime;
+
+        let store = self.select_keystore(&selector)?;
+        let key_type = K::Key::item_type();
+        let base_path = spec
+            .arti_path()
+            .map_err(into_bad_api_usage!("cannot rotate key without an ArtiPath"))?;
+
+        // The creation time of a given version of this key, if `path` is a version of this key.
+        let created_at = |path: &KeyPath| -> Option<SystemTime> {
+            let secs = path
+                .arti()?
+                .as_str()
+                .strip_prefix(base_path.as_str())?
+                .strip_prefix(crate::DENOTATOR_SEP)?
+                .parse()
+                .ok()?;
+
+            Some(SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(secs))
+        };
+
+        let now_secs = now
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_err(|_| bad_api_usage!("key creation time predates the UNIX epoch"))?
+            .as_secs();
+        let new_path = ArtiPath::new(format!("{base_path}{}{now_secs}", crate::DENOTATOR_SEP))
+            .map_err(into_bad_api_usage!("invalid versioned key path"))?;
+
+        if store.contains(&new_path, &key_type)? {
+            return Err(crate::Error::KeyAlreadyExists);
+        }
+
+        let key = generate_fn()?.to_encodable_key();
+        let () = store.insert(&key, &new_path)?;
+        let new_path = KeyPath::Arti(new_path);
+
+        let retire_before = now
+            .checked_sub(retire_after)
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        for (path, item_type) in store.list()? {
+            if path == new_path || item_type != key_type {
+                continue;
+            }
+
+            if created_at(&path).is_some_and(|created| created <= retire_before) {
+                let _: Option<()> = store.remove(&path, &item_type)?;
+            }
+        }
+
+        Ok(new_path)
+    }
+
     /// Return the keystore entry descriptors of the keys matching the specified [`KeyPathPattern`].
     ///
     /// NOTE: This searches for matching keys in _all_ keystores.
@@ -329,6 +569,109 @@ impl KeyMgr {
             .collect::<Result<Vec<_>>>()
     }
 
+    /// List the keys in each keystore, grouped by the [`KeystoreId`] of the keystore.
+    ///
+    /// The primary keystore comes first,
+    /// followed by the secondary keystores in the order they were configured.
+    /// Keystores with no keys are included, with an empty list.
+    pub fn list_by_keystore(&self) -> Result<Vec<(KeystoreId, Vec<(KeyPath, KeystoreItemType)>)>> {
+        self.all_stores()
+            .map(|store| Ok((store.id().clone(), store.list()?)))
+            .collect()
+    }
+
+    /// Remove all the keys matching the specified [`KeyPathPattern`], from _all_ keystores.
+    ///
+    /// Returns the [`KeyPath`]s of the removed keys.
+    /// If a matching key is present in more than one keystore,
+    /// its path is returned once for each keystore it was removed from.
+    ///
+    /// Keystores don't support transactions, so this operation is not atomic.
+    /// If a removal fails, we stop and return the error immediately:
+    /// any keys removed before the failure stay removed,
+    /// and the remaining matching keys are left in place.
+    /// Callers can use [`KeyMgr::list_matching`] to find out which keys remain.
+    pub fn remove_matching(&self, pat: &KeyPathPattern) -> Result<Vec<KeyPath>> {
+        let mut removed = vec![];
+        for entry in self.list_matching(pat)? {
+            if self.remove_entry(&entry)?.is_some() {
+                removed.push(entry.key_path);
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Add the key identified by `key_spec` to `bundle`.
+    ///
+    /// The key is looked up like in [`KeyMgr::get`].
+    ///
+    /// Returns `Ok(false)` if the key was not found in any keystore,
+    /// in which case `bundle` is left unchanged.
+    ///
+    /// See [`KeyBundle`] for a description of the bundle format.
+    pub fn export<K: ToEncodableKey>(
+        &self,
+        key_spec: &dyn KeySpecifier,
+        bundle: &mut KeyBundle,
+    ) -> Result<bool> {
+        let Some(key) = self.get::<K>(key_spec)? else {
+            return Ok(false);
+        };
+        bundle.push(key_spec, &key.to_encodable_key())?;
+        Ok(true)
+    }
+
+    /// Check what importing the key bundle `bundle` would do, without importing anything.
+    ///
+    /// See [`KeyBundle`] for a description of the bundle format.
+    ///
+    /// The returned [`ImportReport`] lists every valid entry of the bundle,
+    /// along with whether importing it would overwrite an item
+    /// already present in the primary keystore.
+    /// Entries with a malformed name, with an unknown item type,
+    /// or with an item that can't be decoded
+    /// are listed in [`ImportReport::errors`].
+    /// If the bundle is truncated, the entries after the truncation point can't be recovered,
+    /// so the report ends with an error describing the truncated entry.
+    ///
+    /// Returns an error only if we fail to access the primary keystore.
+    pub fn validate_import(&self, bundle: &[u8]) -> Result<ImportReport> {
+        let mut report = ImportReport::default();
+        let mut r = tor_bytes::Reader::from_slice(bundle);
+
+        while r.remaining() > 0 {
+            let offset = r.consumed();
+            let (name, item) = match bundle::read_entry(&mut r) {
+                Ok(entry) => entry,
+                Err(e) => {
+                    report.errors.push(MalformedImportEntry {
+                        offset,
+                        error: format!("truncated entry: {}", e.report()),
+                    });
+                    break;
+                }
+            };
+
+            let (path, item_type) = match bundle::parse_entry(name, item) {
+                Ok(entry) => entry,
+                Err(error) => {
+                    report.errors.push(MalformedImportEntry { offset, error });
+                    continue;
+                }
+            };
+
+            let would_overwrite = self.primary_store.contains(&path, &item_type)?;
+            report.entries.push(ImportEntry {
+                path,
+                item_type,
+                would_overwrite,
+            });
+        }
+
+        Ok(report)
+    }
+
     /// Describe the specified key.
     ///
     /// Returns [`KeyPathError::Unrecognized`] if none of the registered
@@ -573,6 +916,27 @@ impl KeyMgr {
         }
     }
 
+    /// Return the [`Keystore`](crate::Keystore) matching the specified `selector`,
+    /// if it is able to store items of type `item_type`.
+    ///
+    /// Returns [`Error::UnsupportedItemType`](crate::Error::UnsupportedItemType)
+    /// if the selected keystore does not [support](crate::Keystore::supports) `item_type`.
+    fn select_keystore_for(
+        &self,
+        selector: &KeystoreSelector,
+        item_type: &KeystoreItemType,
+    ) -> Result<&BoxedKeystore> {
+        let store = self.select_keystore(selector)?;
+        if !store.supports(item_type) {
+            return Err(crate::Error::UnsupportedItemType {
+                keystore: store.id().clone(),
+                item_type: item_type.clone(),
+            });
+        }
+
+        Ok(store)
+    }
+
     /// Return the [`Keystore`](crate::Keystore) with the specified `id`.
     ///
     /// Returns an error if the specified ID is not the ID of the primary keystore or
@@ -633,7 +997,7 @@ impl KeyMgr {
         C: ToEncodableCert<K>,
     {
         let cert = cert.to_encodable_cert();
-        let store = self.select_keystore(&selector)?;
+        let store = self.select_keystore_for(&selector, &C::EncodableCert::item_type())?;
 
         let () = store.insert(&cert, cert_spec)?;
         Ok(())
@@ -668,6 +1032,7 @@ mod tests {
     use tor_key_forge::{
         CertData, EncodableItem, ErasedKey, InvalidCertError, KeyType, KeystoreItem,
     };
+    use tor_llcrypto::pk::curve25519;
     use tor_llcrypto::pk::ed25519::{self, Ed25519PublicKey as _};
     use tor_llcrypto::rng::FakeEntropicRng;
 
@@ -957,6 +1322,52 @@ mod tests {
 
     impl_specifier!(TestPublicKeySpecifier1, "pub-spec1");
 
+    /// A keystore that can only store public keys.
+    #[derive(Default)]
+    struct PublicOnlyKeystore(Keystore3);
+
+    impl crate::Keystore for PublicOnlyKeystore {
+        fn id(&self) -> &KeystoreId {
+            self.0.id()
+        }
+
+        fn contains(
+            &self,
+            key_spec: &dyn KeySpecifier,
+            item_type: &KeystoreItemType,
+        ) -> Result<bool> {
+            self.0.contains(key_spec, item_type)
+        }
+
+        fn get(
+            &self,
+            key_spec: &dyn KeySpecifier,
+            item_type: &KeystoreItemType,
+        ) -> Result<Option<ErasedKey>> {
+            self.0.get(key_spec, item_type)
+        }
+
+        fn insert(&self, key: &dyn EncodableItem, key_spec: &dyn KeySpecifier) -> Result<()> {
+            self.0.insert(key, key_spec)
+        }
+
+        fn remove(
+            &self,
+            key_spec: &dyn KeySpecifier,
+            item_type: &KeystoreItemType,
+        ) -> Result<Option<()>> {
+            self.0.remove(key_spec, item_type)
+        }
+
+        fn list(&self) -> Result<Vec<(KeyPath, KeystoreItemType)>> {
+            self.0.list()
+        }
+
+        fn supports(&self, item_type: &KeystoreItemType) -> bool {
+            *item_type == TestPublicKey::item_type()
+        }
+    }
+
     /// Create a test `KeystoreEntry`.
     fn entry_descriptor(specifier: impl KeySpecifier, keystore_id: &KeystoreId) -> KeystoreEntry {
         KeystoreEntry {
@@ -1173,6 +1584,110 @@ mod tests {
             .unwrap());
     }
 
+    #[test]
+    fn remove_matching() {
+        let mut builder = KeyMgrBuilder::default().primary_store(Box::<Keystore1>::default());
+        builder.secondary_stores().push(Keystore2::new_boxed());
+        let mgr = builder.build().unwrap();
+        let keystore2 = KeystoreId::from_str("keystore2").unwrap();
+
+        mgr.insert(
+            TestItem::new("coot"),
+            &TestKeySpecifier1,
+            KeystoreSelector::Primary,
+            true,
+        )
+        .unwrap();
+        mgr.insert(
+            TestItem::new("moorhen"),
+            &TestKeySpecifier2,
+            KeystoreSelector::Id(&keystore2),
+            true,
+        )
+        .unwrap();
+        mgr.insert(
+            TestItem::new("grebe"),
+            &TestPublicKeySpecifier1,
+            KeystoreSelector::Primary,
+            true,
+        )
+        .unwrap();
+
+        let removed = mgr
+            .remove_matching(&KeyPathPattern::Arti("spec*".into()))
+            .unwrap()
+            .into_iter()
+            .map(|path| path.to_string())
+            .sorted()
+            .collect::<Vec<_>>();
+        assert_eq!(removed, ["spec1", "spec2"]);
+
+        assert!(mgr.get::<TestItem>(&TestKeySpecifier1).unwrap().is_none());
+        assert!(mgr.get::<TestItem>(&TestKeySpecifier2).unwrap().is_none());
+        // Keys that don't match the pattern are left alone.
+        let remaining = mgr
+            .list_matching(&KeyPathPattern::Arti("*".into()))
+            .unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].key_path().to_string(), "pub-spec1");
+
+        // Nothing else matches
+        assert!(mgr
+            .remove_matching(&KeyPathPattern::Arti("spec*".into()))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn list_by_keystore() {
+        let mut builder = KeyMgrBuilder::default().primary_store(Box::<Keystore1>::default());
+        builder
+            .secondary_stores()
+            .extend([Keystore2::new_boxed(), Keystore3::new_boxed()]);
+        let mgr = builder.build().unwrap();
+        let keystore2 = KeystoreId::from_str("keystore2").unwrap();
+
+        for (id, spec) in [
+            ("coot", &TestKeySpecifier1 as &dyn KeySpecifier),
+            ("moorhen", &TestKeySpecifier2),
+        ] {
+            mgr.insert(TestItem::new(id), spec, KeystoreSelector::Primary, true)
+                .unwrap();
+        }
+        mgr.insert(
+            TestItem::new("grebe"),
+            &TestKeySpecifier3,
+            KeystoreSelector::Id(&keystore2),
+            true,
+        )
+        .unwrap();
+
+        let listed = mgr
+            .list_by_keystore()
+            .unwrap()
+            .into_iter()
+            .map(|(id, entries)| {
+                let paths = entries
+                    .into_iter()
+                    .map(|(path, item_type)| {
+                        assert_eq!(item_type, TestItem::item_type());
+                        path.to_string()
+                    })
+                    .sorted()
+                    .collect::<Vec<_>>();
+                (id.to_string(), paths)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            listed,
+            vec![
+                ("keystore1".into(), vec!["spec1".into(), "spec2".into()]),
+                ("keystore2".into(), vec!["spec3".into()]),
+                ("keystore3".into(), vec![]),
+            ]
+        );
+    }
+
     #[test]
     fn keygen() {
         let mut rng = FakeEntropicRng(testing_rng());
@@ -1348,6 +1863,80 @@ mod tests {
         assert!(mgr.remove_entry(&entry_desc2).unwrap().is_none());
     }
 
+    #[test]
+    #[cfg(feature = "experimental-api")]
+    fn rotate_key() {
+        const HOUR: Duration = Duration::from_secs(60 * 60);
+        const RETIRE_AFTER: Duration = Duration::from_secs(3 * 60 * 60);
+
+        let mgr = KeyMgrBuilder::default()
+            .primary_store(Box::<Keystore1>::default())
+            .build()
+            .unwrap();
+        // An unrelated key that shares a prefix with the rotated key
+        mgr.insert(
+            TestItem::new("coot"),
+            &TestKeySpecifier1,
+            KeystoreSelector::Primary,
+            false,
+        )
+        .unwrap();
+
+        let versions = |mgr: &KeyMgr| {
+            let pat = KeyPathPattern::Arti("spec1+*".into());
+            mgr.list_matching(&pat)
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.key_path().to_string())
+                .sorted()
+                .collect::<Vec<_>>()
+        };
+
+        let t0 = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let rotate = |now| {
+            mgr.rotate_key(
+                &TestKeySpecifier1,
+                || Ok(TestItem::new("moorhen")),
+                now,
+                RETIRE_AFTER,
+                KeystoreSelector::Primary,
+            )
+        };
+
+        let first = rotate(t0).unwrap();
+        assert_eq!(first.to_string(), "spec1+1000000");
+        // Rotating twice at the same instant would overwrite the current version
+        assert!(matches!(
+            rotate(t0).unwrap_err(),
+            crate::Error::KeyAlreadyExists
+        ));
+
+        // The first version is still within its grace period
+        let second = rotate(t0 + 2 * HOUR).unwrap();
+        assert_eq!(versions(&mgr), ["spec1+1000000", "spec1+1007200"]);
+
+        // The grace period of the first version has elapsed, so it's retired,
+        // but the second one is kept.
+        let third = rotate(t0 + RETIRE_AFTER).unwrap();
+        assert_eq!(versions(&mgr), ["spec1+1007200", "spec1+1010800"]);
+        assert!(!mgr
+            .primary_store
+            .contains(&first, &TestItem::item_type())
+            .unwrap());
+        assert!(mgr
+            .primary_store
+            .contains(&second, &TestItem::item_type())
+            .unwrap());
+        assert!(mgr
+            .primary_store
+            .contains(&third, &TestItem::item_type())
+            .unwrap());
+
+        // The unversioned key is left alone
+        let key = mgr.get::<TestItem>(&TestKeySpecifier1).unwrap().unwrap();
+        assert_eq!(key.meta.item_id, "coot");
+    }
+
     /// Whether to generate a given item before running the `run_certificate_test`.
     #[cfg(feature = "experimental-api")]
     #[derive(Clone, Copy, Debug, PartialEq)]
@@ -1474,6 +2063,231 @@ mod tests {
         }}
     }
 
+    #[test]
+    #[cfg(feature = "ephemeral-keystore")]
+    fn migrate_key() {
+        use crate::ArtiEphemeralKeystore;
+
+        tor_key_forge::define_ed25519_keypair!(Migrated);
+
+        let from = KeystoreId::from_str("ephemeral1").unwrap();
+        let to = KeystoreId::from_str("ephemeral2").unwrap();
+        let mut builder = KeyMgrBuilder::default()
+            .primary_store(Box::new(ArtiEphemeralKeystore::new(from.to_string())));
+        builder
+            .secondary_stores()
+            .push(Box::new(ArtiEphemeralKeystore::new(to.to_string())));
+        let mgr = builder.build().unwrap();
+
+        let spec = ArtiPath::new("migrated_key".into()).unwrap();
+        let key = MigratedKeypair::from(ed25519::Keypair::generate(&mut testing_rng()));
+        let id = key.to_ed25519_id();
+        mgr.insert(key, &spec, KeystoreSelector::Primary, false)
+            .unwrap();
+
+        let contains = |store_id: &KeystoreId| {
+            mgr.find_keystore(store_id)
+                .unwrap()
+                .contains(&spec, &MigratedKeypair::item_type())
+                .unwrap()
+        };
+
+        mgr.migrate_key::<MigratedKeypair>(&spec, &from, &to)
+            .unwrap();
+        assert!(!contains(&from));
+        assert!(contains(&to));
+        let key = mgr.get::<MigratedKeypair>(&spec).unwrap().unwrap();
+        assert_eq!(key.to_ed25519_id(), id);
+
+        // The key isn't in the source keystore anymore.
+        assert!(mgr
+            .migrate_key::<MigratedKeypair>(&spec, &from, &to)
+            .is_err());
+        assert!(contains(&to));
+
+        // Moving a key to the keystore it's in is a no-op.
+        mgr.migrate_key::<MigratedKeypair>(&spec, &to, &to).unwrap();
+        assert!(contains(&to));
+    }
+
+    #[test]
+    fn insert_if_absent() {
+        let mut builder = KeyMgrBuilder::default().primary_store(Box::<Keystore1>::default());
+        builder.secondary_stores().push(Keystore2::new_boxed());
+        let mgr = builder.build().unwrap();
+        let keystore2 = KeystoreId::from_str("keystore2").unwrap();
+
+        let inserted = mgr
+            .insert_if_absent(
+                TestItem::new("coot"),
+                &TestKeySpecifier1,
+                KeystoreSelector::Id(&keystore2),
+            )
+            .unwrap();
+        assert!(inserted);
+
+        // A second insertion at the same key specifier doesn't overwrite the first key.
+        let inserted = mgr
+            .insert_if_absent(
+                TestItem::new("gull"),
+                &TestKeySpecifier1,
+                KeystoreSelector::Id(&keystore2),
+            )
+            .unwrap();
+        assert!(!inserted);
+        let key = mgr.get::<TestItem>(&TestKeySpecifier1).unwrap().unwrap();
+        assert_eq!(key.meta.item_id, "coot");
+        assert_eq!(key.meta.retrieved_from, Some(keystore2.clone()));
+
+        // The check is per keystore.
+        let inserted = mgr
+            .insert_if_absent(
+                TestItem::new("gull"),
+                &TestKeySpecifier1,
+                KeystoreSelector::Primary,
+            )
+            .unwrap();
+        assert!(inserted);
+    }
+
+    #[test]
+    fn validate_import() {
+        let mgr = KeyMgrBuilder::default()
+            .primary_store(Box::<Keystore1>::default())
+            .build()
+            .unwrap();
+        mgr.insert(
+            TestItem::new("coot"),
+            &TestKeySpecifier1,
+            KeystoreSelector::Primary,
+            false,
+        )
+        .unwrap();
+
+        // Export the key we just inserted, and another one.
+        let mut bundle = KeyBundle::new();
+        assert!(mgr
+            .export::<TestItem>(&TestKeySpecifier1, &mut bundle)
+            .unwrap());
+        assert!(!mgr
+            .export::<TestItem>(&TestKeySpecifier2, &mut bundle)
+            .unwrap());
+        bundle
+            .push(&TestKeySpecifier2, &TestItem::new("gull"))
+            .unwrap();
+        let secret = curve25519::StaticSecret::random_from_rng(testing_rng());
+        let public = curve25519::PublicKey::from(&secret);
+        let x25519_key = curve25519::StaticKeypair { secret, public };
+        bundle.push(&TestKeySpecifier1, &x25519_key).unwrap();
+        let mut bundle = bundle.into_bytes();
+
+        let push_entry = |bundle: &mut Vec<u8>, name: &str, item: &[u8]| {
+            bundle.extend(u16::try_from(name.len()).unwrap().to_be_bytes());
+            bundle.extend(name.as_bytes());
+            bundle.extend(u32::try_from(item.len()).unwrap().to_be_bytes());
+            bundle.extend(item);
+        };
+        let ext = TestItem::item_type().arti_extension();
+        push_entry(&mut bundle, &format!("not a path.{ext}"), b"");
+        push_entry(&mut bundle, "spec3", b"");
+        push_entry(&mut bundle, "spec3.unknown_type", b"");
+        push_entry(&mut bundle, &format!("spec3.{ext}"), b"not an OpenSSH key");
+        let truncated_at = bundle.len();
+        push_entry(&mut bundle, &format!("spec4.{ext}"), b"auk");
+        bundle.pop();
+
+        let report = mgr.validate_import(&bundle).unwrap();
+        let entries = report
+            .entries
+            .iter()
+            .map(|e| (e.path.to_string(), e.item_type.clone(), e.would_overwrite))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            vec![
+                ("spec1".into(), TestItem::item_type(), true),
+                ("spec2".into(), TestItem::item_type(), false),
+                ("spec1".into(), KeyType::X25519StaticKeypair.into(), false),
+            ]
+        );
+        assert_eq!(report.errors.len(), 5);
+        assert!(report.errors[0].error.contains("not a path"));
+        assert!(report.errors[1].error.contains("malformed entry name"));
+        assert!(report.errors[2].error.contains("unknown item type"));
+        assert!(report.errors[3].error.contains("invalid item"));
+        assert_eq!(report.errors[4].offset, truncated_at);
+
+        // Nothing was imported.
+        assert!(!mgr
+            .primary_store
+            .contains(&TestKeySpecifier2, &TestItem::item_type())
+            .unwrap());
+    }
+
+    #[test]
+    fn touch_not_supported() {
+        // Our test keystores don't record modification times,
+        // so they rely on the default `touch`.
+        let store: BoxedKeystore = Box::<Keystore1>::default();
+        let err = store
+            .touch(&TestKeySpecifier1, &TestItem::item_type())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            crate::Error::NotSupported {
+                action: "touch",
+                ..
+            }
+        ));
+        assert_eq!(err.to_string(), "Keystore keystore1 does not support touch");
+    }
+
+    #[test]
+    fn unsupported_item_type() {
+        let mut builder =
+            KeyMgrBuilder::default().primary_store(Box::<PublicOnlyKeystore>::default());
+        builder.secondary_stores().push(Keystore2::new_boxed());
+        let mgr = builder.build().unwrap();
+        let keystore3 = KeystoreId::from_str("keystore3").unwrap();
+
+        // The primary store can't hold TestItems...
+        let err = mgr
+            .insert(
+                TestItem::new("coot"),
+                &TestKeySpecifier1,
+                KeystoreSelector::Primary,
+                true,
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            crate::Error::UnsupportedItemType { ref keystore, ref item_type }
+                if *keystore == keystore3 && *item_type == TestItem::item_type()
+        ));
+
+        let err = mgr
+            .generate::<TestItem>(
+                &TestKeySpecifier1,
+                KeystoreSelector::Primary,
+                &mut testing_rng(),
+                false,
+            )
+            .unwrap_err();
+        assert!(matches!(err, crate::Error::UnsupportedItemType { .. }));
+        assert!(mgr.get::<TestItem>(&TestKeySpecifier1).unwrap().is_none());
+
+        // ...but the secondary store can.
+        mgr.insert(
+            TestItem::new("coot"),
+            &TestKeySpecifier1,
+            KeystoreSelector::Id(&KeystoreId::from_str("keystore2").unwrap()),
+            true,
+        )
+        .unwrap();
+        let key = mgr.get::<TestItem>(&TestKeySpecifier1).unwrap().unwrap();
+        assert_eq!(key.meta.item_id, "coot");
+    }
+
     #[test]
     #[cfg(feature = "experimental-api")]
     #[rustfmt::skip] // preserve the layout for readability
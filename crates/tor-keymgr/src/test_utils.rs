@@ -300,6 +300,217 @@ mod key {
     }
 }
 
+/// A module exporting an in-memory [`Keystore`](crate::Keystore) for use in tests.
+#[cfg(feature = "keymgr")]
+mod keystore {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use tor_error::into_bad_api_usage;
+    use tor_key_forge::{EncodableItem, ErasedKey, KeystoreItem, KeystoreItemType};
+
+    use crate::{ArtiPath, KeyPath, KeySpecifier, Keystore, KeystoreId, Result};
+
+    /// The number of times each [`Keystore`] method of a [`TestKeystore`] was called.
+    ///
+    /// Returned by [`TestKeystore::calls`].
+    #[derive(Clone, Debug, Default, PartialEq, Eq)]
+    #[allow(clippy::exhaustive_structs)] // Tests are expected to construct this
+    pub struct TestKeystoreCalls {
+        /// Calls to [`Keystore::contains`].
+        pub contains: usize,
+        /// Calls to [`Keystore::get`].
+        pub get: usize,
+        /// Calls to [`Keystore::insert`].
+        pub insert: usize,
+        /// Calls to [`Keystore::remove`].
+        pub remove: usize,
+        /// Calls to [`Keystore::list`].
+        pub list: usize,
+    }
+
+    /// An in-memory [`Keystore`], for testing code that uses a [`KeyMgr`](crate::KeyMgr).
+    ///
+    /// Keys are stored in a `HashMap`, indexed by their [`ArtiPath`] and item type,
+    /// so (like the native Arti keystore) this store can't hold keys
+    /// whose [`KeySpecifier`] has no `ArtiPath`.
+    ///
+    /// The store counts how many times each of its `Keystore` methods was called:
+    /// see [`calls`](TestKeystore::calls).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tor_basic_utils::test_rng::testing_rng;
+    /// use tor_key_forge::define_ed25519_keypair;
+    /// use tor_keymgr::test_utils::TestKeystore;
+    /// use tor_keymgr::{ArtiPath, KeyMgrBuilder, KeystoreSelector};
+    /// use tor_llcrypto::pk::ed25519;
+    ///
+    /// define_ed25519_keypair!(Test);
+    ///
+    /// let store = TestKeystore::new("test");
+    /// let keymgr = KeyMgrBuilder::default()
+    ///     .primary_store(Box::new(store.clone()))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let spec = ArtiPath::new("test_key".into()).unwrap();
+    /// let key = TestKeypair::from(ed25519::Keypair::generate(&mut testing_rng()));
+    /// let id = key.to_ed25519_id();
+    ///
+    /// keymgr
+    ///     .insert(key, &spec, KeystoreSelector::Primary, false)
+    ///     .unwrap();
+    /// let key = keymgr.get::<TestKeypair>(&spec).unwrap().unwrap();
+    /// assert_eq!(key.to_ed25519_id(), id);
+    ///
+    /// assert_eq!(store.len(), 1);
+    /// assert_eq!(store.calls().insert, 1);
+    /// ```
+    #[derive(Clone)]
+    pub struct TestKeystore {
+        /// The identifier of this store.
+        id: KeystoreId,
+        /// The state of this store.
+        ///
+        /// Shared between clones, so that a test can keep a handle to a store
+        /// it has handed over to a `KeyMgr`.
+        inner: std::sync::Arc<Mutex<Inner>>,
+    }
+
+    /// The mutable state of a [`TestKeystore`].
+    #[derive(Default)]
+    struct Inner {
+        /// The keys in the store.
+        keys: HashMap<(ArtiPath, KeystoreItemType), KeystoreItem>,
+        /// The number of calls to each `Keystore` method.
+        calls: TestKeystoreCalls,
+    }
+
+    impl TestKeystore {
+        /// Create an empty `TestKeystore` with the specified identifier.
+        pub fn new(id: &str) -> Self {
+            Self {
+                id: KeystoreId(id.into()),
+                inner: Default::default(),
+            }
+        }
+
+        /// Add `key` to this store, under `key_spec`.
+        ///
+        /// Unlike [`Keystore::insert`], this does not count as a call.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `key_spec` has no [`ArtiPath`], or if `key` can't be encoded.
+        pub fn with_key(self, key: &dyn EncodableItem, key_spec: &dyn KeySpecifier) -> Self {
+            let item = key.as_keystore_item().unwrap();
+            let item_type = item.item_type().unwrap();
+            let path = key_spec.arti_path().unwrap();
+            self.lock().keys.insert((path, item_type), item);
+            self
+        }
+
+        /// Return the number of keys in this store.
+        pub fn len(&self) -> usize {
+            self.lock().keys.len()
+        }
+
+        /// Return true if this store is empty.
+        pub fn is_empty(&self) -> bool {
+            self.lock().keys.is_empty()
+        }
+
+        /// Return the number of calls made so far to each of our `Keystore` methods.
+        pub fn calls(&self) -> TestKeystoreCalls {
+            self.lock().calls.clone()
+        }
+
+        /// Reset the call counts returned by [`calls`](TestKeystore::calls).
+        pub fn reset_calls(&self) {
+            self.lock().calls = Default::default();
+        }
+
+        /// Lock the state of this store.
+        fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+            self.inner.lock().expect("lock poisoned")
+        }
+    }
+
+    /// Return the [`ArtiPath`] of `key_spec`, or an error if it doesn't have one.
+    fn arti_path(key_spec: &dyn KeySpecifier) -> Result<ArtiPath> {
+        Ok(key_spec
+            .arti_path()
+            .map_err(into_bad_api_usage!("TestKeystore requires an ArtiPath"))?)
+    }
+
+    impl Keystore for TestKeystore {
+        fn id(&self) -> &KeystoreId {
+            &self.id
+        }
+
+        fn contains(
+            &self,
+            key_spec: &dyn KeySpecifier,
+            item_type: &KeystoreItemType,
+        ) -> Result<bool> {
+            let mut inner = self.lock();
+            inner.calls.contains += 1;
+            let path = arti_path(key_spec)?;
+            Ok(inner.keys.contains_key(&(path, item_type.clone())))
+        }
+
+        fn get(
+            &self,
+            key_spec: &dyn KeySpecifier,
+            item_type: &KeystoreItemType,
+        ) -> Result<Option<ErasedKey>> {
+            let mut inner = self.lock();
+            inner.calls.get += 1;
+            let path = arti_path(key_spec)?;
+            match inner.keys.get(&(path, item_type.clone())) {
+                Some(item) => Ok(Some(item.clone().into_erased()?)),
+                None => Ok(None),
+            }
+        }
+
+        fn insert(&self, key: &dyn EncodableItem, key_spec: &dyn KeySpecifier) -> Result<()> {
+            let mut inner = self.lock();
+            inner.calls.insert += 1;
+            let path = arti_path(key_spec)?;
+            let item = key.as_keystore_item()?;
+            let item_type = item.item_type()?;
+            inner.keys.insert((path, item_type), item);
+            Ok(())
+        }
+
+        fn remove(
+            &self,
+            key_spec: &dyn KeySpecifier,
+            item_type: &KeystoreItemType,
+        ) -> Result<Option<()>> {
+            let mut inner = self.lock();
+            inner.calls.remove += 1;
+            let path = arti_path(key_spec)?;
+            Ok(inner.keys.remove(&(path, item_type.clone())).map(|_| ()))
+        }
+
+        fn list(&self) -> Result<Vec<(KeyPath, KeystoreItemType)>> {
+            let mut inner = self.lock();
+            inner.calls.list += 1;
+            Ok(inner
+                .keys
+                .keys()
+                .map(|(path, item_type)| (path.clone().into(), item_type.clone()))
+                .collect())
+        }
+    }
+}
+
+#[cfg(feature = "keymgr")]
+pub use keystore::{TestKeystore, TestKeystoreCalls};
+
 #[cfg(test)]
 pub(crate) use specifier::*;
 
@@ -27,14 +27,17 @@ mod rtt;
 pub(crate) mod sendme;
 mod vegas;
 
+use std::sync::Arc;
 use std::time::Instant;
 
+use tor_error::{error_report, internal};
+
 use crate::{Error, Result};
 
 use self::{
     params::{Algorithm, CongestionControlParams, CongestionWindowParams},
     rtt::RoundtripTimeEstimator,
-    sendme::{CircTag, SendmeValidator},
+    sendme::{CircParams, CircTag, SendmeValidator, WindowParams},
 };
 
 /// This trait defines what a congestion control algorithm must implement in order to interface
@@ -72,6 +75,9 @@ pub(crate) trait CongestionControlAlgorithm: Send {
     /// Inform the algorithm that we just sent a SENDME.
     fn sendme_sent(&mut self) -> Result<()>;
 
+    /// Return the number of DATA cells we can send right now before we have to stop.
+    fn headroom(&self) -> u32;
+
     /// Test Only: Return the congestion window.
     #[cfg(test)]
     fn send_window(&self) -> u32;
@@ -243,6 +249,62 @@ impl CongestionWindow {
     }
 }
 
+/// A snapshot of the flow-control state of a circuit hop.
+///
+/// This is the information a scheduler needs to decide which of several circuits
+/// (for example, the legs of a multipath tunnel) has the most room
+/// for the next cell.
+///
+/// The snapshot is not updated as the circuit is used.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CircFlowCtrl {
+    /// The number of cells we can send before we must wait for a SENDME.
+    send_window: u32,
+    /// The number of SENDME tags we are still waiting for.
+    outstanding_tags: usize,
+}
+
+impl CircFlowCtrl {
+    /// Return the number of cells we could send, when this snapshot was taken,
+    /// before having to wait for a SENDME.
+    pub fn send_window(&self) -> u32 {
+        self.send_window
+    }
+
+    /// Return the number of SENDMEs we were waiting for when this snapshot was taken.
+    pub fn outstanding_tags(&self) -> usize {
+        self.outstanding_tags
+    }
+
+    /// Return true if this hop has more room to send than `other`.
+    ///
+    /// Hops are compared by their send window.
+    /// If the windows are equal, the hop waiting for fewer SENDMEs
+    /// is considered to have more headroom.
+    pub fn more_headroom_than(&self, other: &CircFlowCtrl) -> bool {
+        (self.send_window, std::cmp::Reverse(self.outstanding_tags))
+            > (other.send_window, std::cmp::Reverse(other.outstanding_tags))
+    }
+}
+
+/// Return the largest number of SENDME tags that can be outstanding on a hop with `params`.
+///
+/// We record a tag for every batch of cells that the other side must acknowledge with a SENDME,
+/// and we never send more cells than fit in the largest possible window.
+fn max_outstanding_sendmes(params: &CongestionControlParams) -> usize {
+    let (window, increment) = match params.alg() {
+        Algorithm::FixedWindow(p) => (
+            u32::from(p.circ_window_max()),
+            u32::from(CircParams::increment()),
+        ),
+        Algorithm::Vegas(_) => (
+            params.cwnd_params().cwnd_max(),
+            params.cwnd_params().sendme_inc(),
+        ),
+    };
+    usize::try_from(window.div_ceil(increment.max(1))).unwrap_or(usize::MAX)
+}
+
 /// Congestion control state of a hop on a circuit.
 ///
 /// This controls the entire logic of congestion control and circuit level SENDMEs.
@@ -271,14 +333,33 @@ impl CongestionControl {
                 Box::new(vegas::Vegas::new(p, &state, cwnd))
             }
         };
+        let mut sendme_validator = SendmeValidator::new();
+        sendme_validator.set_limit(max_outstanding_sendmes(params));
+        // Our window should keep us from ever going over the limit,
+        // so if we do, the bug is ours and not the other side's:
+        // report it, rather than closing the circuit.
+        sendme_validator.set_overflow_callback(Arc::new(|n_outstanding| {
+            error_report!(
+                internal!("{} SENDME tags outstanding on a hop", n_outstanding),
+                "SENDME tag limit exceeded"
+            );
+        }));
         Self {
             algorithm,
             rtt: RoundtripTimeEstimator::new(params.rtt_params()),
-            sendme_validator: SendmeValidator::new(),
+            sendme_validator,
             state,
         }
     }
 
+    /// Return a snapshot of our current flow-control state.
+    pub(crate) fn flow_ctrl(&self) -> CircFlowCtrl {
+        CircFlowCtrl {
+            send_window: self.algorithm.headroom(),
+            outstanding_tags: self.sendme_validator.n_outstanding(),
+        }
+    }
+
     /// Return true iff a DATA cell is allowed to be sent based on the congestion control state.
     pub(crate) fn can_send(&self) -> bool {
         self.algorithm.can_send()
@@ -339,7 +420,7 @@ impl CongestionControl {
         // If next cell is a SENDME, we need to record the tag of this cell in order to validate
         // the next SENDME when it arrives.
         if self.algorithm.is_next_cell_sendme() {
-            self.sendme_validator.record(tag);
+            self.sendme_validator.record(tag)?;
             // Only keep the SENDME timestamp if the algorithm has a congestion window.
             if self.algorithm.cwnd().is_some() {
                 self.rtt.expect_sendme(Instant::now());
@@ -366,10 +447,10 @@ mod test {
     #![allow(clippy::needless_pass_by_value)]
     //! <!-- @@ end test lint list maintained by maint/add_warning @@ -->
 
-    use crate::congestion::test_utils::new_cwnd;
+    use crate::congestion::test_utils::{new_cwnd, params::build_cc_fixed_params};
 
     use super::sendme::CircTag;
-    use super::CongestionControl;
+    use super::{max_outstanding_sendmes, CircFlowCtrl, CongestionControl};
 
     impl CongestionControl {
         /// For testing: get a copy of the current send window, and the
@@ -382,6 +463,48 @@ mod test {
         }
     }
 
+    #[test]
+    fn flow_ctrl_headroom() {
+        let params = build_cc_fixed_params();
+        let fresh = CongestionControl::new(&params);
+        let mut used = CongestionControl::new(&params);
+        for _ in 0..150 {
+            used.note_data_sent(&[0_u8; 20]).unwrap();
+        }
+
+        let fresh = fresh.flow_ctrl();
+        let used = used.flow_ctrl();
+        assert_eq!(fresh.send_window(), 1000);
+        assert_eq!(fresh.outstanding_tags(), 0);
+        assert_eq!(used.send_window(), 850);
+        assert_eq!(used.outstanding_tags(), 1);
+
+        assert!(fresh.more_headroom_than(&used));
+        assert!(!used.more_headroom_than(&fresh));
+        assert!(!fresh.more_headroom_than(&fresh));
+
+        // With equal windows, fewer outstanding tags wins.
+        let waiting = CircFlowCtrl {
+            send_window: 1000,
+            outstanding_tags: 2,
+        };
+        assert!(fresh.more_headroom_than(&waiting));
+        assert!(!waiting.more_headroom_than(&fresh));
+    }
+
+    #[test]
+    fn sendme_tag_limit() {
+        let params = build_cc_fixed_params();
+        assert_eq!(max_outstanding_sendmes(&params), 10);
+
+        // A whole window's worth of cells stays within the limit.
+        let mut cc = CongestionControl::new(&params);
+        for _ in 0..1000 {
+            cc.note_data_sent(&[0_u8; 20]).unwrap();
+        }
+        assert_eq!(cc.flow_ctrl().outstanding_tags(), 10);
+    }
+
     #[test]
     fn test_cwnd() {
         let mut cwnd = new_cwnd();
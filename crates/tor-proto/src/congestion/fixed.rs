@@ -57,8 +57,7 @@ impl CongestionControlAlgorithm for FixedWindow {
     }
 
     fn sendme_sent(&mut self) -> Result<()> {
-        self.recvwindow.put();
-        Ok(())
+        self.recvwindow.try_put()
     }
 
     fn data_received(&mut self) -> Result<bool> {
@@ -69,6 +68,10 @@ impl CongestionControlAlgorithm for FixedWindow {
         self.sendwindow.take()
     }
 
+    fn headroom(&self) -> u32 {
+        u32::from(self.sendwindow.sendable_before_stall())
+    }
+
     #[cfg(test)]
     fn send_window(&self) -> u32 {
         u32::from(self.sendwindow.window())
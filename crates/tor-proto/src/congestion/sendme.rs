@@ -13,7 +13,11 @@
 use std::array::TryFromSliceError;
 use std::collections::VecDeque;
 use std::result::Result as StdResult;
+#[cfg(any(test, feature = "experimental-api"))]
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::Arc;
 
+use educe::Educe;
 use tor_cell::relaycell::RelayCmd;
 use tor_cell::relaycell::UnparsedRelayMsg;
 use tor_error::internal;
@@ -126,8 +130,14 @@ impl WindowParams for StreamParams {
     }
 }
 
+/// Callback invoked by a [`SendmeValidator`] when recording a tag would exceed its limit.
+///
+/// The argument is the number of outstanding tags, including the one being recorded.
+pub(crate) type SendmeOverflowFn = dyn Fn(usize) + Send + Sync;
+
 /// Object used to validate SENDMEs as in managing the authenticated tag and verifying it.
-#[derive(Clone, Debug)]
+#[derive(Clone, Educe)]
+#[educe(Debug)]
 pub(crate) struct SendmeValidator<T>
 where
     T: PartialEq + Eq + Clone,
@@ -135,6 +145,13 @@ where
     /// Tag values that incoming "SENDME" messages need to match in order
     /// for us to send more data.
     tags: VecDeque<T>,
+    /// The largest number of tags we allow to be outstanding at once, if any.
+    limit: Option<usize>,
+    /// Policy hook to run instead of failing when `limit` is exceeded.
+    ///
+    /// If this is `None`, exceeding the limit is an error.
+    #[educe(Debug(ignore))]
+    on_overflow: Option<Arc<SendmeOverflowFn>>,
 }
 
 impl<T> SendmeValidator<T>
@@ -145,15 +162,54 @@ where
     pub(crate) fn new() -> Self {
         Self {
             tags: VecDeque::new(),
+            limit: None,
+            on_overflow: None,
         }
     }
 
+    /// Limit the number of tags that may be outstanding at once.
+    ///
+    /// Once `limit` tags are outstanding, a further call to [`record`](Self::record)
+    /// either runs the overflow callback (see [`set_overflow_callback`](Self::set_overflow_callback))
+    /// or, if there is none, fails with a protocol error.
+    pub(crate) fn set_limit(&mut self, limit: usize) {
+        self.limit = Some(limit);
+    }
+
+    /// Install a callback to be run when [`record`](Self::record) exceeds the configured limit.
+    ///
+    /// The callback is run once each time the number of outstanding tags crosses the limit,
+    /// and receives the number of outstanding tags at that point.
+    /// When a callback is installed, exceeding the limit is not an error:
+    /// the tag is still recorded, and it is up to the callback to decide
+    /// what to do (for example, to tear down the circuit).
+    pub(crate) fn set_overflow_callback(&mut self, callback: Arc<SendmeOverflowFn>) {
+        self.on_overflow = Some(callback);
+    }
+
     /// Record a SENDME tag for future validation once we receive it.
-    pub(crate) fn record<U>(&mut self, tag: &U)
+    ///
+    /// Return an error if this would exceed our limit on outstanding tags,
+    /// and there is no overflow callback.
+    pub(crate) fn record<U>(&mut self, tag: &U) -> Result<()>
     where
         U: Clone + Into<T>,
     {
+        if let Some(limit) = self.limit {
+            let count = self.tags.len() + 1;
+            if count > limit {
+                match &self.on_overflow {
+                    // Only notify when we first cross the limit.
+                    Some(callback) if count == limit + 1 => callback(count),
+                    Some(_) => {}
+                    None => {
+                        return Err(Error::CircProto("Too many outstanding SENDME tags".into()));
+                    }
+                }
+            }
+        }
         self.tags.push_back(tag.clone().into());
+        Ok(())
     }
 
     /// Validate a received tag (if any). A mismatch leads to a protocol violation and the circuit
@@ -162,7 +218,7 @@ where
     where
         T: PartialEq<U>,
     {
-        match (self.tags.front(), tag) {
+        match (self.peek_next(), tag) {
             (Some(t), Some(tag)) if t == &tag => {} // this is the right tag.
             (Some(_), None) => {}                   // didn't need a tag.
             (Some(_), Some(_)) => {
@@ -178,6 +234,31 @@ where
         Ok(())
     }
 
+    /// Return the number of tags we have recorded and not yet validated.
+    pub(crate) fn n_outstanding(&self) -> usize {
+        self.tags.len()
+    }
+
+    /// Forget every recorded tag, as if this validator had just been constructed.
+    ///
+    /// The configured limit and overflow callback (if any) are kept.
+    ///
+    /// This lets the validator be reused for a new circuit without reallocating it.
+    //
+    // TODO: Nothing reuses circuit state yet, so this is only used by the tests.
+    #[cfg(test)]
+    pub(crate) fn reset(&mut self) {
+        self.tags.clear();
+    }
+
+    /// Return the tag that the next call to [`validate`](Self::validate) will check against,
+    /// without consuming it.
+    ///
+    /// Return `None` if we are not expecting any SENDME.
+    pub(crate) fn peek_next(&self) -> Option<&T> {
+        self.tags.front()
+    }
+
     #[cfg(test)]
     pub(crate) fn expected_tags(&self) -> Vec<T> {
         self.tags.iter().map(Clone::clone).collect()
@@ -196,6 +277,18 @@ where
         }
     }
 
+    /// Construct a SendWindow at a previously saved position.
+    ///
+    /// Unlike [`SendWindow::new`], this checks that `window` is a valid saved position
+    /// (see [`check_restored_window`]),
+    /// so it should be used when restoring a window from saved state.
+    // For now, only deserialization restores windows.
+    #[cfg_attr(not(feature = "serde"), allow(dead_code))]
+    pub(crate) fn restore(window: u16) -> StdResult<SendWindow<P>, RestoreWindowError> {
+        check_restored_window::<P>(window)?;
+        Ok(Self::new(window))
+    }
+
     /// Return true iff the SENDME tag should be recorded.
     pub(crate) fn should_record_tag(&self) -> bool {
         self.window % P::increment() == 0
@@ -204,12 +297,33 @@ where
     /// Remove one item from this window (since we've sent a cell).
     /// If the window was empty, returns an error.
     pub(crate) fn take(&mut self) -> Result<()> {
-        self.window = self.window.checked_sub(1).ok_or(Error::CircProto(
-            "Called SendWindow::take() on empty SendWindow".into(),
-        ))?;
+        if !self.take_saturating() {
+            return Err(Error::CircProto(
+                "Called SendWindow::take() on empty SendWindow".into(),
+            ));
+        }
         Ok(())
     }
 
+    /// Remove one item from this window, saturating at zero.
+    ///
+    /// Returns true if the window was nonzero (so that an item was actually taken),
+    /// and false if it was already empty.
+    ///
+    /// Unlike [`SendWindow::take`], this never fails:
+    /// it is meant for modes in which the window is only advisory,
+    /// where running past the end of the window should not close the circuit.
+    /// Callers that enforce the window must use `take` instead.
+    pub(crate) fn take_saturating(&mut self) -> bool {
+        match self.window.checked_sub(1) {
+            Some(window) => {
+                self.window = window;
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Handle an incoming sendme.
     ///
     /// On failure, return an error: the caller must close the circuit due to a protocol violation.
@@ -232,6 +346,105 @@ where
     pub(crate) fn window(&self) -> u16 {
         self.window
     }
+
+    /// Return how many cells can be sent, starting now,
+    /// before the sender has to stop and wait on a SENDME.
+    ///
+    /// This is the whole current window.
+    /// Sending a cell whose tag must be recorded (see [`SendWindow::should_record_tag`])
+    /// doesn't make the sender wait:
+    /// the SENDME acknowledging that cell can only raise the window,
+    /// and the sender only needs it once the window is empty.
+    pub(crate) fn sendable_before_stall(&self) -> u16 {
+        self.window
+    }
+}
+
+/// A [`SendWindow`] that can be shared between tasks.
+///
+/// This is meant for senders in which one task takes from the window as it sends cells,
+/// while another puts to it as it handles SENDMEs.
+/// The value of the window is kept in an [`AtomicU16`],
+/// so it can be adjusted through a shared reference without a lock.
+///
+/// Only the window itself is synchronized.
+/// Recording and validating SENDME tags (with a [`SendmeValidator`])
+/// still needs external synchronization:
+/// the tag of a cell must be recorded before the SENDME acknowledging that cell
+/// can be validated.
+///
+/// This is the window of a [`FlowControlledWriter`](crate::stream::FlowControlledWriter).
+#[derive(Debug)]
+#[cfg(any(test, feature = "experimental-api"))]
+pub(crate) struct AtomicSendWindow<P>
+where
+    P: WindowParams,
+{
+    /// Current value for this window
+    window: AtomicU16,
+    /// Marker type to tell the compiler that the P type is used.
+    _dummy: std::marker::PhantomData<P>,
+}
+
+// The window is not used to synchronize access to any other memory,
+// so all the atomic operations on it can be relaxed.
+#[cfg(any(test, feature = "experimental-api"))]
+impl<P> AtomicSendWindow<P>
+where
+    P: WindowParams,
+{
+    /// Construct a new AtomicSendWindow.
+    pub(crate) fn new(window: u16) -> AtomicSendWindow<P> {
+        AtomicSendWindow {
+            window: AtomicU16::new(window),
+            _dummy: std::marker::PhantomData,
+        }
+    }
+
+    /// Remove one item from this window (since we've sent a cell).
+    ///
+    /// Returns true if the SENDME tag of the cell should be recorded
+    /// (see [`SendWindow::should_record_tag`]).
+    /// This is determined from the value of the window when the item was taken,
+    /// so it stays accurate even if another task adjusts the window concurrently.
+    ///
+    /// If the window was empty, returns an error.
+    pub(crate) fn take(&self) -> Result<bool> {
+        let old = self
+            .window
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |w| w.checked_sub(1))
+            .map_err(|_| {
+                Error::CircProto("Called AtomicSendWindow::take() on empty SendWindow".into())
+            })?;
+        Ok(old % P::increment() == 0)
+    }
+
+    /// Handle an incoming sendme.
+    ///
+    /// On failure, return an error: the caller must close the circuit due to a protocol violation.
+    #[must_use = "didn't check whether SENDME was expected."]
+    pub(crate) fn put(&self) -> Result<()> {
+        self.window
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |w| {
+                w.checked_add(P::increment())
+                    .filter(|new_window| *new_window <= P::maximum())
+            })
+            .map_err(|w| match w.checked_add(P::increment()) {
+                // Overflow check.
+                None => Error::from(internal!("Overflow on SENDME window")),
+                // We'd have gone above our maximum, so this wasn't expected.
+                Some(_) => Error::CircProto("Unexpected stream SENDME".into()),
+            })?;
+        Ok(())
+    }
+
+    /// Return the current send window value.
+    ///
+    /// Another task may adjust the window at any time,
+    /// so the result may already be out of date by the time it is returned.
+    pub(crate) fn window(&self) -> u16 {
+        self.window.load(Ordering::Relaxed)
+    }
 }
 
 /// Structure to track when we need to send SENDME cells for incoming data.
@@ -253,6 +466,18 @@ impl<P: WindowParams> RecvWindow<P> {
         }
     }
 
+    /// Construct a RecvWindow at a previously saved position.
+    ///
+    /// Unlike [`RecvWindow::new`], this checks that `window` is a valid saved position
+    /// (see [`check_restored_window`]),
+    /// so it should be used when restoring a window from saved state.
+    // For now, only deserialization restores windows.
+    #[cfg_attr(not(feature = "serde"), allow(dead_code))]
+    pub(crate) fn restore(window: u16) -> StdResult<RecvWindow<P>, RestoreWindowError> {
+        check_restored_window::<P>(window)?;
+        Ok(Self::new(window))
+    }
+
     /// Called when we've just received a cell; return true if we need to send
     /// a sendme, and false otherwise.
     ///
@@ -281,12 +506,125 @@ impl<P: WindowParams> RecvWindow<P> {
     }
 
     /// Called when we've just sent a SENDME.
+    ///
+    /// Unlike [`RecvWindow::try_put`], this does not check the window against its maximum.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the window would overflow.
+    /// Use [`RecvWindow::try_put`] unless the caller can guarantee that it won't:
+    /// for example, because [`RecvWindow::take`] has just told it to send a SENDME.
     pub(crate) fn put(&mut self) {
         self.window = self
             .window
             .checked_add(P::increment())
             .expect("Overflow detected while attempting to increment window");
     }
+
+    /// Called when we've just sent a SENDME.
+    ///
+    /// Unlike [`RecvWindow::put`], this never panics:
+    /// it gives an error (and leaves the window unchanged)
+    /// if the window would exceed its maximum.
+    pub(crate) fn try_put(&mut self) -> Result<()> {
+        self.window = self
+            .window
+            .checked_add(P::increment())
+            .filter(|&w| w <= P::maximum())
+            .ok_or_else(|| {
+                Error::CircProto("Tried to raise a receive window beyond its maximum".into())
+            })?;
+        Ok(())
+    }
+}
+
+/// The serialized form of a [`SendWindow`] or a [`RecvWindow`].
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SavedWindow {
+    /// The value of the window.
+    window: u16,
+}
+
+/// Implement `Serialize` and `Deserialize` for a window type.
+///
+/// Only the value of the window is saved.
+/// The [`WindowParams`] are not: they come from the type being deserialized
+/// (for example, [`CircSendWindow`] or [`StreamRecvWindow`]),
+/// so a window must be restored as the same type it was saved as.
+///
+/// Deserialization goes through `restore`,
+/// so it fails unless the saved window is a valid position for that type.
+#[cfg(feature = "serde")]
+macro_rules! impl_window_serde {
+    { $window:ident } => {
+        impl<P: WindowParams> serde::Serialize for $window<P> {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> StdResult<S::Ok, S::Error> {
+                SavedWindow {
+                    window: self.window,
+                }
+                .serialize(serializer)
+            }
+        }
+
+        impl<'de, P: WindowParams> serde::Deserialize<'de> for $window<P> {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> StdResult<Self, D::Error> {
+                let SavedWindow { window } = SavedWindow::deserialize(deserializer)?;
+                Self::restore(window).map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "serde")]
+impl_window_serde!(SendWindow);
+#[cfg(feature = "serde")]
+impl_window_serde!(RecvWindow);
+
+/// An error from restoring a window from saved state.
+#[derive(Clone, Debug, thiserror::Error)]
+pub(crate) enum RestoreWindowError {
+    /// The saved window is larger than any window of this type can be.
+    #[error("Saved window of {window} exceeds the maximum of {maximum}")]
+    AboveMaximum {
+        /// The saved window.
+        window: u16,
+        /// The maximum for this type of window.
+        maximum: u16,
+    },
+    /// The saved window is not a whole number of increments away from the start.
+    #[error("Saved window of {window} is not on an increment of {increment} from {start}")]
+    Misaligned {
+        /// The saved window.
+        window: u16,
+        /// The starting value for this type of window.
+        start: u16,
+        /// The increment for this type of window.
+        increment: u16,
+    },
+}
+
+/// Check whether `window` is an acceptable value for a window restored from saved state.
+///
+/// The window must not exceed the maximum for its type,
+/// and must differ from the starting value by a whole number of increments.
+/// That is, windows can only be saved and restored at a SENDME boundary,
+/// when no partly-acknowledged batch of cells is outstanding.
+fn check_restored_window<P: WindowParams>(window: u16) -> StdResult<(), RestoreWindowError> {
+    if window > P::maximum() {
+        return Err(RestoreWindowError::AboveMaximum {
+            window,
+            maximum: P::maximum(),
+        });
+    }
+    if P::start().abs_diff(window) % P::increment() != 0 {
+        return Err(RestoreWindowError::Misaligned {
+            window,
+            start: P::start(),
+            increment: P::increment(),
+        });
+    }
+    Ok(())
 }
 
 /// Return true if this message type is counted by flow-control windows.
@@ -378,6 +716,209 @@ mod test {
         assert!(w.take().is_err());
     }
 
+    #[test]
+    fn recvwindow_try_put() {
+        let mut w: StreamRecvWindow = RecvWindow::new(400);
+        assert!(w.try_put().is_ok());
+        assert_eq!(w.window, 450);
+        assert!(w.try_put().is_ok());
+        assert_eq!(w.window, 500);
+
+        // We're at the maximum: the window can't grow any further.
+        assert!(matches!(w.try_put(), Err(Error::CircProto(_))));
+        assert_eq!(w.window, 500);
+
+        // Nor can it wrap around.
+        let mut w: StreamRecvWindow = RecvWindow::new(u16::MAX - 10);
+        assert!(matches!(w.try_put(), Err(Error::CircProto(_))));
+        assert_eq!(w.window, u16::MAX - 10);
+    }
+
+    #[test]
+    fn validator_overflow() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // Without a callback, exceeding the limit is an error.
+        let mut v: SendmeValidator<u8> = SendmeValidator::new();
+        v.set_limit(2);
+        assert!(v.record(&1_u8).is_ok());
+        assert!(v.record(&2_u8).is_ok());
+        assert!(v.record(&3_u8).is_err());
+        assert_eq!(v.expected_tags(), vec![1, 2]);
+
+        // With a callback, it fires once, when we cross the limit.
+        let fired = Arc::new(AtomicUsize::new(0));
+        let last_count = Arc::new(AtomicUsize::new(0));
+        let mut v: SendmeValidator<u8> = SendmeValidator::new();
+        v.set_limit(2);
+        v.set_overflow_callback({
+            let fired = Arc::clone(&fired);
+            let last_count = Arc::clone(&last_count);
+            Arc::new(move |n| {
+                fired.fetch_add(1, Ordering::SeqCst);
+                last_count.store(n, Ordering::SeqCst);
+            })
+        });
+        for tag in 1..=2_u8 {
+            v.record(&tag).unwrap();
+        }
+        assert_eq!(fired.load(Ordering::SeqCst), 0);
+        v.record(&3_u8).unwrap();
+        assert_eq!(fired.load(Ordering::SeqCst), 1);
+        assert_eq!(last_count.load(Ordering::SeqCst), 3);
+        v.record(&4_u8).unwrap();
+        assert_eq!(fired.load(Ordering::SeqCst), 1);
+        assert_eq!(v.expected_tags(), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn validator_reset() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let fired = Arc::new(AtomicUsize::new(0));
+        let mut v: SendmeValidator<u8> = SendmeValidator::new();
+        v.set_limit(2);
+        v.set_overflow_callback({
+            let fired = Arc::clone(&fired);
+            Arc::new(move |_| {
+                fired.fetch_add(1, Ordering::SeqCst);
+            })
+        });
+        for tag in 1..=3_u8 {
+            v.record(&tag).unwrap();
+        }
+        assert_eq!(fired.load(Ordering::SeqCst), 1);
+
+        v.reset();
+        assert_eq!(v.n_outstanding(), 0);
+        assert_eq!(v.peek_next(), None);
+        // Like a fresh validator, we don't expect any SENDME...
+        assert!(v.validate(Some(1_u8)).is_err());
+        assert!(v.validate::<u8>(None).is_err());
+
+        // ...and we only validate the tags recorded since the reset.
+        v.record(&7_u8).unwrap();
+        assert_eq!(v.expected_tags(), vec![7]);
+        assert!(v.validate(Some(1_u8)).is_err());
+        v.validate(Some(7_u8)).unwrap();
+        assert_eq!(v.n_outstanding(), 0);
+
+        // The limit and callback still apply.
+        for tag in 1..=3_u8 {
+            v.record(&tag).unwrap();
+        }
+        assert_eq!(fired.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn validator_peek_next() {
+        let mut v: SendmeValidator<u8> = SendmeValidator::new();
+        assert_eq!(v.peek_next(), None);
+
+        for tag in 1..=3_u8 {
+            v.record(&tag).unwrap();
+        }
+        assert_eq!(v.peek_next(), v.expected_tags().first());
+        assert_eq!(v.peek_next(), Some(&1));
+        // Peeking doesn't consume anything.
+        assert_eq!(v.n_outstanding(), 3);
+
+        v.validate(Some(1_u8)).unwrap();
+        assert_eq!(v.peek_next(), v.expected_tags().first());
+        assert_eq!(v.peek_next(), Some(&2));
+
+        v.validate::<u8>(None).unwrap();
+        v.validate(Some(3_u8)).unwrap();
+        assert_eq!(v.peek_next(), None);
+    }
+
+    #[test]
+    fn restore() {
+        let w = CircSendWindow::restore(1000).unwrap();
+        assert_eq!(w.window(), 1000);
+        let w = CircSendWindow::restore(0).unwrap();
+        assert_eq!(w.window(), 0);
+        let w = StreamSendWindow::restore(450).unwrap();
+        assert_eq!(w.window(), 450);
+        assert!(matches!(
+            CircSendWindow::restore(1001),
+            Err(RestoreWindowError::AboveMaximum {
+                window: 1001,
+                maximum: 1000
+            })
+        ));
+        assert!(matches!(
+            StreamSendWindow::restore(550),
+            Err(RestoreWindowError::AboveMaximum { .. })
+        ));
+        // Not at a SENDME boundary.
+        assert!(matches!(
+            StreamSendWindow::restore(437),
+            Err(RestoreWindowError::Misaligned {
+                window: 437,
+                start: 500,
+                increment: 50
+            })
+        ));
+        assert!(matches!(
+            CircSendWindow::restore(999),
+            Err(RestoreWindowError::Misaligned { .. })
+        ));
+
+        let w = CircRecvWindow::restore(900).unwrap();
+        assert_eq!(w.window, 900);
+        let w = StreamRecvWindow::restore(500).unwrap();
+        assert_eq!(w.window, 500);
+        assert!(CircRecvWindow::restore(u16::MAX).is_err());
+        assert!(StreamRecvWindow::restore(550).is_err());
+        assert!(CircRecvWindow::restore(901).is_err());
+    }
+
+    #[test]
+    fn sendwindow_take_saturating() {
+        let mut w = StreamSendWindow::new(3);
+        for _ in 0..3 {
+            assert!(w.take_saturating());
+        }
+        assert_eq!(w.window(), 0);
+
+        // An exhausted window saturates instead of failing.
+        assert!(!w.take_saturating());
+        assert!(!w.take_saturating());
+        assert_eq!(w.window(), 0);
+
+        // The strict version still fails.
+        assert!(w.take().is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_roundtrip() {
+        let mut w = CircSendWindow::new(1000);
+        for _ in 0..300 {
+            w.take().unwrap();
+        }
+        let json = serde_json::to_string(&w).unwrap();
+        assert_eq!(json, r#"{"window":700}"#);
+        let w2: CircSendWindow = serde_json::from_str(&json).unwrap();
+        assert_eq!(w2.window(), 700);
+
+        let mut w = StreamRecvWindow::new(500);
+        w.decrement_n(100).unwrap();
+        let json = serde_json::to_string(&w).unwrap();
+        let w2: StreamRecvWindow = serde_json::from_str(&json).unwrap();
+        assert_eq!(w2.window, 400);
+
+        // A window that is too large for its type can't be restored.
+        assert!(serde_json::from_str::<CircSendWindow>(r#"{"window":1001}"#).is_err());
+        assert!(serde_json::from_str::<StreamSendWindow>(r#"{"window":501}"#).is_err());
+        // Nor can one that was saved between SENDME boundaries.
+        assert!(serde_json::from_str::<CircSendWindow>(r#"{"window":963}"#).is_err());
+        // ...even if it was valid for the type it was saved as.
+        let json = serde_json::to_string(&CircSendWindow::new(1000)).unwrap();
+        assert!(serde_json::from_str::<StreamSendWindow>(&json).is_err());
+    }
+
     fn new_sendwindow() -> SendWindow<CircParams> {
         SendWindow::new(1000)
     }
@@ -414,6 +955,94 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn sendwindow_sendable_before_stall() -> Result<()> {
+        // Count by hand, by taking from a copy of the window until it fails.
+        fn count(w: &SendWindow<CircParams>) -> u16 {
+            let mut w = w.clone();
+            let mut n = 0;
+            while w.take().is_ok() {
+                n += 1;
+            }
+            n
+        }
+
+        for window in [0, 1, 2, 99, 100, 101, 150, 899, 900, 901, 999, 1000] {
+            let w = SendWindow::<CircParams>::new(window);
+            assert_eq!(w.sendable_before_stall(), count(&w), "window={window}");
+        }
+
+        let mut w = new_sendwindow();
+        assert_eq!(w.sendable_before_stall(), 1000);
+        // Taking the tagged cell doesn't make us wait any sooner.
+        assert!(w.should_record_tag());
+        w.take()?;
+        assert_eq!(w.sendable_before_stall(), 999);
+        for _ in 0_usize..100 {
+            w.take()?;
+        }
+        assert_eq!(w.sendable_before_stall(), 899);
+        assert_eq!(w.sendable_before_stall(), count(&w));
+        // A SENDME lets us send another increment of cells.
+        w.put()?;
+        assert_eq!(w.sendable_before_stall(), 999);
+        assert_eq!(w.sendable_before_stall(), count(&w));
+
+        Ok(())
+    }
+
+    #[test]
+    fn atomic_sendwindow() {
+        let w = AtomicSendWindow::<CircParams>::new(1000);
+        // The first cell is tagged, the next 99 aren't.
+        assert!(w.take().unwrap());
+        for _ in 0..99 {
+            assert!(!w.take().unwrap());
+        }
+        assert_eq!(w.window(), 900);
+        w.put().unwrap();
+        assert_eq!(w.window(), 1000);
+
+        // Going above the maximum is an error, and leaves the window alone.
+        assert!(w.put().is_err());
+        assert_eq!(w.window(), 1000);
+
+        for _ in 0..1000 {
+            w.take().unwrap();
+        }
+        // Taking from an empty window is an error, too.
+        assert!(w.take().is_err());
+        assert_eq!(w.window(), 0);
+    }
+
+    #[test]
+    fn atomic_sendwindow_concurrent() {
+        const N_TAKERS: usize = 4;
+        const TAKES_PER_TAKER: usize = 125;
+        const N_PUTS: usize = 5;
+
+        // We never take more than the initial window, nor put more than the room above it,
+        // so every operation succeeds no matter how they are interleaved.
+        let w = AtomicSendWindow::<CircParams>::new(500);
+        let n_tagged = std::thread::scope(|s| {
+            let takers = (0..N_TAKERS)
+                .map(|_| s.spawn(|| (0..TAKES_PER_TAKER).filter(|_| w.take().unwrap()).count()))
+                .collect::<Vec<_>>();
+            s.spawn(|| {
+                for _ in 0..N_PUTS {
+                    w.put().unwrap();
+                    std::thread::yield_now();
+                }
+            });
+            takers.into_iter().map(|t| t.join().unwrap()).sum::<usize>()
+        });
+
+        assert_eq!(w.window(), 500);
+        // Puts don't change the window modulo the increment,
+        // so every hundredth take is tagged, regardless of the interleaving.
+        assert_eq!(n_tagged, N_TAKERS * TAKES_PER_TAKER / 100);
+    }
+
     #[test]
     fn sendwindow_erroring() -> Result<()> {
         let mut w = new_sendwindow();
@@ -300,6 +300,10 @@ impl CongestionControlAlgorithm for Vegas {
         Ok(())
     }
 
+    fn headroom(&self) -> u32 {
+        self.cwnd.get().saturating_sub(self.num_inflight)
+    }
+
     #[cfg(test)]
     fn send_window(&self) -> u32 {
         self.cwnd.get()
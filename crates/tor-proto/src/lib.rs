@@ -64,6 +64,7 @@ pub use util::skew::ClockSkew;
 
 pub use channel::params::ChannelPaddingInstructions;
 pub use congestion::params as ccparams;
+pub use congestion::CircFlowCtrl;
 pub use crypto::cell::{HopNum, HopNumDisplay};
 pub use tunnel::circuit;
 
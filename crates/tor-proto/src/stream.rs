@@ -43,3 +43,6 @@ pub use tor_cell::relaycell::msg::IpVersionPreference;
 pub use {ctrl::ClientStreamCtrl, data::ClientDataStreamCtrl};
 
 pub(crate) use flow_control::StreamSendFlowControl;
+#[cfg(feature = "experimental-api")]
+#[cfg_attr(docsrs, doc(cfg(feature = "experimental-api")))]
+pub use flow_control::{FlowControlledWriter, SharedSendWindow};
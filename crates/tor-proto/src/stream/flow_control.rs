@@ -1,5 +1,17 @@
 //! Code for implementing flow control (stream-level).
 
+#[cfg(any(test, feature = "experimental-api"))]
+use {
+    crate::congestion::sendme::{AtomicSendWindow, StreamParams},
+    futures::task::AtomicWaker,
+    futures::AsyncWrite,
+    pin_project::pin_project,
+    std::io,
+    std::pin::Pin,
+    std::sync::Arc,
+    std::task::{Context, Poll},
+};
+
 use tor_cell::relaycell::RelayMsg;
 
 use crate::congestion::sendme;
@@ -78,3 +90,179 @@ impl StreamSendFlowControl {
 
     // TODO: Add methods for handling incoming xon, xoff.
 }
+
+/// A stream's send window, shared between a [`FlowControlledWriter`]
+/// and the code that handles incoming SENDMEs.
+#[derive(Debug)]
+#[cfg(any(test, feature = "experimental-api"))]
+pub struct SharedSendWindow {
+    /// The window itself.
+    window: AtomicSendWindow<StreamParams>,
+    /// The waker of the writer that found the window empty, if any.
+    waker: AtomicWaker,
+}
+
+#[cfg(any(test, feature = "experimental-api"))]
+impl SharedSendWindow {
+    /// Construct a new `SharedSendWindow`, starting at `window`.
+    pub fn new(window: u16) -> Self {
+        Self {
+            window: AtomicSendWindow::new(window),
+            waker: AtomicWaker::new(),
+        }
+    }
+
+    /// Handle an incoming sendme, and wake the writer if it was waiting for one.
+    ///
+    /// On failure, return an error: the caller should close the stream or
+    /// circuit with a protocol error.
+    pub fn put(&self) -> Result<()> {
+        self.window.put()?;
+        self.waker.wake();
+        Ok(())
+    }
+
+    /// Return the current send window value.
+    pub fn window(&self) -> u16 {
+        self.window.window()
+    }
+}
+
+/// An [`AsyncWrite`] adapter that enforces SENDME-window-based flow control.
+///
+/// Every successful write to the underlying writer `W`
+/// carries at most `cell_len` bytes, and takes one cell from a [`SharedSendWindow`].
+/// While the window is empty, writes return [`Poll::Pending`],
+/// and the writer is woken once [`SharedSendWindow::put`] is called
+/// for an incoming SENDME.
+///
+/// This only tracks the window.
+/// It does not record the SENDME tags of the cells it sends,
+/// so the SENDME validator of a circuit (and its overflow callback) never sees them:
+/// callers that need authenticated SENDMEs must record the tags themselves.
+#[pin_project]
+#[derive(Debug)]
+#[cfg(any(test, feature = "experimental-api"))]
+pub struct FlowControlledWriter<W> {
+    /// The underlying writer.
+    #[pin]
+    inner: W,
+    /// The window we take a cell from for every write.
+    window: Arc<SharedSendWindow>,
+    /// The largest number of bytes we write per cell.
+    cell_len: usize,
+}
+
+#[cfg(any(test, feature = "experimental-api"))]
+impl<W> FlowControlledWriter<W> {
+    /// Wrap `inner`, writing at most `cell_len` bytes for each cell taken from `window`.
+    pub fn new(inner: W, window: Arc<SharedSendWindow>, cell_len: usize) -> Self {
+        Self {
+            inner,
+            window,
+            cell_len,
+        }
+    }
+
+    /// Consume this writer, and return the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+#[cfg(any(test, feature = "experimental-api"))]
+impl<W: AsyncWrite> AsyncWrite for FlowControlledWriter<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.project();
+        if this.window.window() == 0 {
+            this.window.waker.register(cx.waker());
+            // A SENDME may have arrived between our check and our registering the waker.
+            if this.window.window() == 0 {
+                return Poll::Pending;
+            }
+        }
+
+        let len = buf.len().min(*this.cell_len);
+        match this.inner.poll_write(cx, &buf[..len]) {
+            Poll::Ready(Ok(n)) if n > 0 => {
+                // Only we take from the window, so it can't have emptied since we checked.
+                this.window.window.take()?;
+                Poll::Ready(Ok(n))
+            }
+            other => other,
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().inner.poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    // @@ begin test lint list maintained by maint/add_warning @@
+    #![allow(clippy::bool_assert_comparison)]
+    #![allow(clippy::clone_on_copy)]
+    #![allow(clippy::dbg_macro)]
+    #![allow(clippy::mixed_attributes_style)]
+    #![allow(clippy::print_stderr)]
+    #![allow(clippy::print_stdout)]
+    #![allow(clippy::single_char_pattern)]
+    #![allow(clippy::unwrap_used)]
+    #![allow(clippy::unchecked_duration_subtraction)]
+    #![allow(clippy::useless_vec)]
+    #![allow(clippy::needless_pass_by_value)]
+    //! <!-- @@ end test lint list maintained by maint/add_warning @@ -->
+
+    use super::*;
+    use futures::io::Cursor;
+    use futures::task::{waker, ArcWake};
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// A waker that remembers whether it was woken.
+    #[derive(Default)]
+    struct FlagWaker(AtomicBool);
+
+    impl ArcWake for FlagWaker {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn writes_stall_until_sendme() {
+        // Start with room for two cells.
+        let window = Arc::new(SharedSendWindow::new(2));
+        let mut writer = FlowControlledWriter::new(Cursor::new(vec![]), Arc::clone(&window), 4);
+        let flag = Arc::new(FlagWaker::default());
+        let waker = waker(Arc::clone(&flag));
+        let mut cx = Context::from_waker(&waker);
+        let mut write = |buf: &[u8]| Pin::new(&mut writer).poll_write(&mut cx, buf);
+
+        // Each write sends at most one cell's worth of data.
+        assert!(matches!(write(b"hello world"), Poll::Ready(Ok(4))));
+        assert!(matches!(write(b"o world"), Poll::Ready(Ok(4))));
+        assert_eq!(window.window(), 0);
+
+        // The window is exhausted, so we stall...
+        assert!(write(b"rld").is_pending());
+        assert!(!flag.0.load(Ordering::SeqCst));
+
+        // ...until a SENDME arrives.
+        window.put().unwrap();
+        assert!(flag.0.load(Ordering::SeqCst));
+        assert_eq!(window.window(), 52);
+        assert!(matches!(write(b"rld"), Poll::Ready(Ok(3))));
+        assert_eq!(window.window(), 51);
+
+        assert_eq!(writer.into_inner().into_inner(), b"hello world");
+    }
+}
@@ -52,6 +52,7 @@ impl StreamReader {
 
         if sendme::cell_counts_towards_windows(&msg) && self.recv_window.take()? {
             self.target.send_sendme().await?;
+            // `take` just reduced the window, so this can't overflow.
             self.recv_window.put();
         }
 
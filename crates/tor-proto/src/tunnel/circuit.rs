@@ -46,6 +46,7 @@ pub(crate) mod unique_id;
 
 use crate::channel::Channel;
 use crate::congestion::params::CongestionControlParams;
+use crate::congestion::CircFlowCtrl;
 use crate::crypto::cell::HopNum;
 use crate::crypto::handshake::ntor_v3::NtorV3PublicKey;
 use crate::memquota::{CircuitAccount, SpecificAccount as _};
@@ -343,6 +344,22 @@ impl ClientCirc {
         Ok(rx.await.map_err(|_| Error::CircuitClosed)??)
     }
 
+    /// Return a snapshot of the flow-control state of `hop`.
+    ///
+    /// Schedulers can use this to compare the room available on several circuits:
+    /// see [`CircFlowCtrl::more_headroom_than`].
+    ///
+    /// Returns an error if the hop does not exist, or if this is a multi-path tunnel.
+    pub async fn flow_ctrl(&self, hop: HopNum) -> Result<CircFlowCtrl> {
+        let (tx, rx) = oneshot::channel();
+
+        self.command
+            .unbounded_send(CtrlCmd::QueryFlowCtrl { hop, done: tx })
+            .map_err(|_| Error::CircuitClosed)?;
+
+        rx.await.map_err(|_| Error::CircuitClosed)?
+    }
+
     /// Return a reference to this circuit's memory quota account
     pub fn mq_account(&self) -> &CircuitAccount {
         &self.memquota
@@ -5,7 +5,7 @@ pub(super) mod extender;
 
 use crate::channel::{Channel, ChannelSender};
 use crate::congestion::sendme::{self, CircTag};
-use crate::congestion::{CongestionControl, CongestionSignals};
+use crate::congestion::{CircFlowCtrl, CongestionControl, CongestionSignals};
 use crate::crypto::binding::CircuitBinding;
 use crate::crypto::cell::{
     HopNum, InboundClientCrypt, InboundClientLayer, OutboundClientCrypt, OutboundClientLayer,
@@ -1410,6 +1410,11 @@ impl CircHop {
         Ok(None)
     }
 
+    /// Return a snapshot of the flow-control state of this hop.
+    pub(crate) fn flow_ctrl(&self) -> CircFlowCtrl {
+        self.ccontrol.flow_ctrl()
+    }
+
     /// Delegate to CongestionControl, for testing purposes
     #[cfg(test)]
     pub(crate) fn send_window_and_expected_tags(&self) -> (u32, Vec<CircTag>) {
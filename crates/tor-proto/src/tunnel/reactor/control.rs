@@ -5,6 +5,7 @@ use super::{
     CircuitHandshake, CloseStreamBehavior, MetaCellHandler, Reactor, ReactorResultChannel,
     RunOnceCmdInner, SendRelayCell,
 };
+use crate::congestion::CircFlowCtrl;
 use crate::crypto::binding::CircuitBinding;
 use crate::crypto::cell::{HopNum, InboundClientLayer, OutboundClientLayer, Tor1RelayCrypto};
 use crate::crypto::handshake::ntor_v3::{NtorV3Client, NtorV3PublicKey};
@@ -230,6 +231,13 @@ pub(crate) enum CtrlCmd {
         /// Oneshot channel to notify on completion.
         done: ReactorResultChannel<Vec<(LegId, Arc<path::Path>)>>,
     },
+    /// Get a snapshot of the flow-control state of a given hop.
+    QueryFlowCtrl {
+        /// The hop to query.
+        hop: HopNum,
+        /// Oneshot channel to notify on completion.
+        done: ReactorResultChannel<CircFlowCtrl>,
+    },
     /// (tests only) Add a hop to the list of hops on this circuit, with dummy cryptography.
     #[cfg(test)]
     AddFakeHop {
@@ -578,6 +586,27 @@ impl<'a> ControlHandler<'a> {
                 let _ = done.send(Ok(ret));
                 Ok(())
             }
+            CtrlCmd::QueryFlowCtrl { hop, done } => {
+                // Immediately invoked function means that errors will be sent to the channel.
+                let _ = done.send((|| {
+                    let (_id, leg) =
+                        self.reactor
+                            .circuits
+                            .single_leg_mut()
+                            .map_err(into_bad_api_usage!(
+                                "cannot query flow control state of multipath tunnel"
+                            ))?;
+
+                    let hop = leg.hop_mut(hop).ok_or(bad_api_usage!(
+                        "received QueryFlowCtrl for unknown hop {}",
+                        hop.display()
+                    ))?;
+
+                    Ok(hop.flow_ctrl())
+                })());
+
+                Ok(())
+            }
             #[cfg(test)]
             CtrlCmd::AddFakeHop {
                 relay_cell_format,
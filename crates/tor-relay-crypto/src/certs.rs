@@ -21,14 +21,14 @@ pub fn gen_signing_cert(
     kp_relay_id: &RelayIdentityKeypair,
     kp_relaysign_id: &RelaySigningKeypair,
     expiry: SystemTime,
-) -> Result<RelayLinkSigningKeyCert, CertEncodeError> {
+) -> Result<RelaySigningKeyCert, CertEncodeError> {
     Ed25519Cert::constructor()
-        .cert_type(RelayLinkSigningKeyCert::cert_type())
+        .cert_type(RelaySigningKeyCert::cert_type())
         .expiration(expiry)
         .signing_key(kp_relay_id.to_ed25519_id())
         .cert_key(CertifiedKey::Ed25519(kp_relaysign_id.to_ed25519_id()))
         .encode_and_sign(kp_relay_id)
-        .map(RelayLinkSigningKeyCert::from)
+        .map(RelaySigningKeyCert::from)
 }
 
 /// Generate the relay link certificate from the given relay signing keypair and the relay
@@ -171,3 +171,51 @@ fn validate_ed25519_cert(
 
     Ok(cert.into_encoded())
 }
+
+#[cfg(test)]
+mod test {
+    // @@ begin test lint list maintained by maint/add_warning @@
+    #![allow(clippy::bool_assert_comparison)]
+    #![allow(clippy::clone_on_copy)]
+    #![allow(clippy::dbg_macro)]
+    #![allow(clippy::mixed_attributes_style)]
+    #![allow(clippy::print_stderr)]
+    #![allow(clippy::print_stdout)]
+    #![allow(clippy::single_char_pattern)]
+    #![allow(clippy::unwrap_used)]
+    #![allow(clippy::unchecked_duration_subtraction)]
+    #![allow(clippy::useless_vec)]
+    #![allow(clippy::needless_pass_by_value)]
+    //! <!-- @@ end test lint list maintained by maint/add_warning @@ -->
+    use super::*;
+
+    use std::time::Duration;
+
+    use tor_llcrypto::rng::CautiousRng;
+
+    /// Return the `CertType` of the encoded certificate `cert`.
+    fn cert_type_of(cert: &EncodedEd25519Cert) -> CertType {
+        Ed25519Cert::decode(cert).unwrap().peek_cert_type()
+    }
+
+    #[test]
+    fn cert_types() {
+        let mut rng = CautiousRng;
+        let identity = RelayIdentityKeypair::from(ed25519::Keypair::generate(&mut rng));
+        let signing = RelaySigningKeypair::from(ed25519::Keypair::generate(&mut rng));
+        let link = RelayLinkSigningKeypair::from(ed25519::Keypair::generate(&mut rng));
+        let expiry = SystemTime::now() + Duration::from_secs(86400);
+
+        let signing_cert = gen_signing_cert(&identity, &signing, expiry).unwrap();
+        assert_eq!(
+            cert_type_of(&signing_cert.to_encodable_cert()),
+            CertType::IDENTITY_V_SIGNING
+        );
+
+        let link_cert = gen_link_cert(&signing, &link, expiry).unwrap();
+        assert_eq!(
+            cert_type_of(&link_cert.to_encodable_cert()),
+            CertType::SIGNING_V_LINK_AUTH
+        );
+    }
+}
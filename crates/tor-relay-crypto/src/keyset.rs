@@ -0,0 +1,282 @@
+//! Generation of the full set of keys needed by a new relay.
+
+use std::result::Result as StdResult;
+use std::time::{Duration, SystemTime};
+
+use tor_cert::CertEncodeError;
+use tor_error::into_internal;
+use tor_key_forge::{Keygen, KeygenRng, ToEncodableCert, ToEncodableKey};
+use tor_keymgr::{
+    KeyCertificateSpecifier, KeyMgr, KeySpecifier, KeySpecifierComponent, KeystoreSelector, Result,
+};
+use tor_llcrypto::rng::CautiousRng;
+
+use crate::certs::{gen_link_cert, gen_signing_cert, RelayLinkSigningKeyCert, RelaySigningKeyCert};
+use crate::pk::{
+    RelayIdentityKeypair, RelayIdentityKeypairSpecifier, RelayIdentityPublicKey,
+    RelayLinkSigningKeypair, RelayLinkSigningKeypairSpecifier, RelayLinkSigningPublicKey,
+    RelaySigningKeypair, RelaySigningKeypairSpecifier, RelaySigningPublicKey,
+};
+
+/// How long a newly generated medium-term signing key (`KP_relaysign_ed`) is valid for.
+///
+/// This is the default `SigningKeyLifetime` of C Tor.
+const SIGNING_KEY_LIFETIME: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// How long a newly generated link authentication key (`KP_link_ed`) is valid for.
+const LINK_KEY_LIFETIME: Duration = Duration::from_secs(2 * 24 * 60 * 60);
+
+/// The public halves of the keys generated by [`generate_relay_keyset`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct RelayKeySet {
+    /// The long-term identity key (`KP_relayid_ed`).
+    pub identity: RelayIdentityPublicKey,
+    /// The medium-term signing key (`KP_relaysign_ed`).
+    pub signing: RelaySigningPublicKey,
+    /// The expiration time of `signing`, and of its certificate.
+    pub signing_valid_until: SystemTime,
+    /// The short-term link authentication key (`KP_link_ed`).
+    pub link_signing: RelayLinkSigningPublicKey,
+    /// The expiration time of `link_signing`, and of its certificate.
+    pub link_signing_valid_until: SystemTime,
+}
+
+/// Generate all the keys of a new relay, storing them in the primary keystore of `keymgr`.
+///
+/// This generates
+///   * the long-term identity keypair,
+///   * a medium-term signing keypair, along with its certificate,
+///     signed by the identity key, and
+///   * a short-term link authentication keypair, along with its certificate,
+///     signed by the signing key.
+///
+/// The expiration times of the signing and link keys are computed from `now`.
+///
+/// This is meant for bootstrapping a relay that has no keys yet:
+/// it returns [`KeyAlreadyExists`](tor_keymgr::Error::KeyAlreadyExists)
+/// rather than overwrite an existing identity key.
+//
+// TODO: the legacy RSA identity key and the ntor onion key
+// should be generated here too, once we are able to store them.
+pub fn generate_relay_keyset(keymgr: &KeyMgr, now: SystemTime) -> Result<RelayKeySet> {
+    let mut rng = CautiousRng;
+    let selector = KeystoreSelector::Primary;
+
+    let identity: RelayIdentityKeypair = keymgr.generate(
+        &RelayIdentityKeypairSpecifier::new(),
+        selector,
+        &mut rng,
+        false,
+    )?;
+
+    let signing_valid_until = round_up_to_hour(now + SIGNING_KEY_LIFETIME);
+    let signing_spec = CertSpecifier {
+        subject: RelaySigningKeypairSpecifier::new(signing_valid_until.into()),
+        signing: RelayIdentityKeypairSpecifier::new(),
+    };
+    let signing: RelaySigningKeypair = generate_certified_key::<_, RelaySigningKeyCert>(
+        keymgr,
+        &signing_spec,
+        &identity,
+        signing_valid_until,
+        gen_signing_cert,
+        &mut rng,
+    )?;
+
+    let link_signing_valid_until = round_up_to_hour(now + LINK_KEY_LIFETIME);
+    let link_spec = CertSpecifier {
+        subject: RelayLinkSigningKeypairSpecifier::new(link_signing_valid_until.into()),
+        signing: signing_spec.subject,
+    };
+    let link_signing: RelayLinkSigningKeypair = generate_certified_key::<_, RelayLinkSigningKeyCert>(
+        keymgr,
+        &link_spec,
+        &signing,
+        link_signing_valid_until,
+        gen_link_cert,
+        &mut rng,
+    )?;
+
+    Ok(RelayKeySet {
+        identity: identity.public(),
+        signing: signing.public(),
+        signing_valid_until,
+        link_signing: link_signing.public(),
+        link_signing_valid_until,
+    })
+}
+
+/// Generate the subject key of `spec`, and a certificate for it that expires at `expiry`,
+/// signed with `signing_key` by `make_cert`.
+/// Store them both in the primary keystore of `keymgr`, and return the new key.
+///
+/// Returns [`KeyAlreadyExists`](tor_keymgr::Error::KeyAlreadyExists)
+/// if the subject key already exists.
+fn generate_certified_key<K, C>(
+    keymgr: &KeyMgr,
+    spec: &dyn KeyCertificateSpecifier,
+    signing_key: &C::SigningKey,
+    expiry: SystemTime,
+    make_cert: impl FnOnce(&C::SigningKey, &K, SystemTime) -> StdResult<C, CertEncodeError>,
+    rng: &mut dyn KeygenRng,
+) -> Result<K>
+where
+    K: ToEncodableKey,
+    K::Key: Keygen,
+    C: ToEncodableCert<K>,
+{
+    let selector = KeystoreSelector::Primary;
+    let key: K = keymgr.generate(spec.subject_key_specifier(), selector, rng, false)?;
+    let cert = make_cert(signing_key, &key, expiry)
+        .map_err(into_internal!("failed to encode relay certificate"))?;
+
+    // KeyMgr can only store a certificate along with its subject key:
+    // since the key now exists, this just stores the certificate we made.
+    // (We can't make the certificate in the callback, since encoding it can fail.)
+    let (key, _) = keymgr.get_or_generate_key_and_cert::<K, C>(spec, |_, _| cert, selector, rng)?;
+    Ok(key)
+}
+
+/// The [`KeyCertificateSpecifier`] of a certificate stored alongside its subject key.
+struct CertSpecifier<S, K> {
+    /// The specifier of the subject key.
+    subject: S,
+    /// The specifier of the key the certificate is signed with.
+    signing: K,
+}
+
+impl<S: KeySpecifier, K: KeySpecifier> KeyCertificateSpecifier for CertSpecifier<S, K> {
+    fn cert_denotators(&self) -> Vec<&dyn KeySpecifierComponent> {
+        // The subject key specifier already contains the expiration time,
+        // which is all we need to tell apart the different certificates.
+        vec![]
+    }
+
+    fn signing_key_specifier(&self) -> Option<&dyn KeySpecifier> {
+        Some(&self.signing)
+    }
+
+    fn subject_key_specifier(&self) -> &dyn KeySpecifier {
+        &self.subject
+    }
+}
+
+/// Round `t` up to a whole number of hours since the epoch.
+///
+/// Certificate expiration times are encoded in hours,
+/// so this ensures the expiration time we put in a key specifier
+/// matches the one from the key's certificate.
+fn round_up_to_hour(t: SystemTime) -> SystemTime {
+    /// The number of seconds in an hour.
+    const SEC_PER_HOUR: u64 = 3600;
+
+    let secs = t
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let hours = secs.div_ceil(SEC_PER_HOUR);
+    SystemTime::UNIX_EPOCH + Duration::from_secs(hours * SEC_PER_HOUR)
+}
+
+#[cfg(test)]
+mod test {
+    // @@ begin test lint list maintained by maint/add_warning @@
+    #![allow(clippy::bool_assert_comparison)]
+    #![allow(clippy::clone_on_copy)]
+    #![allow(clippy::dbg_macro)]
+    #![allow(clippy::mixed_attributes_style)]
+    #![allow(clippy::print_stderr)]
+    #![allow(clippy::print_stdout)]
+    #![allow(clippy::single_char_pattern)]
+    #![allow(clippy::unwrap_used)]
+    #![allow(clippy::unchecked_duration_subtraction)]
+    #![allow(clippy::useless_vec)]
+    #![allow(clippy::needless_pass_by_value)]
+    //! <!-- @@ end test lint list maintained by maint/add_warning @@ -->
+    use super::*;
+
+    use tor_keymgr::test_utils::TestKeystore;
+    use tor_keymgr::{KeyMgrBuilder, Keystore as _};
+
+    #[test]
+    fn generate_keyset() {
+        let store = TestKeystore::new("test");
+        let keymgr = KeyMgrBuilder::default()
+            .primary_store(Box::new(store.clone()))
+            .build()
+            .unwrap();
+
+        // The certificates are validated against the current time when read back.
+        let now = SystemTime::now();
+        let keyset = generate_relay_keyset(&keymgr, now).unwrap();
+        assert!(keyset.signing_valid_until > keyset.link_signing_valid_until);
+
+        let mut paths = store
+            .list()
+            .unwrap()
+            .into_iter()
+            .map(|(path, _)| path.arti().unwrap().to_string())
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        let signing = RelaySigningKeypairSpecifier::new(keyset.signing_valid_until.into())
+            .arti_path()
+            .unwrap();
+        let link = RelayLinkSigningKeypairSpecifier::new(keyset.link_signing_valid_until.into())
+            .arti_path()
+            .unwrap();
+        let mut expected = vec![
+            "relay/ks_relayid_ed".to_string(),
+            // Each of the signing and link keys is stored along with its certificate.
+            signing.to_string(),
+            signing.to_string(),
+            link.to_string(),
+            link.to_string(),
+        ];
+        expected.sort();
+        assert_eq!(paths, expected);
+
+        // The keys and certificates can be read back, and the certificates are valid.
+        let identity = keymgr
+            .get::<RelayIdentityKeypair>(&RelayIdentityKeypairSpecifier::new())
+            .unwrap()
+            .unwrap();
+        assert_eq!(identity.public(), keyset.identity);
+
+        let signing_spec = CertSpecifier {
+            subject: RelaySigningKeypairSpecifier::new(keyset.signing_valid_until.into()),
+            signing: RelayIdentityKeypairSpecifier::new(),
+        };
+        let (signing, _) = keymgr
+            .get_key_and_cert::<RelaySigningKeypair, RelaySigningKeyCert>(&signing_spec)
+            .unwrap()
+            .unwrap();
+        assert_eq!(signing.public(), keyset.signing);
+
+        let link_spec = CertSpecifier {
+            subject: RelayLinkSigningKeypairSpecifier::new(keyset.link_signing_valid_until.into()),
+            signing: signing_spec.subject,
+        };
+        let (link_signing, _) = keymgr
+            .get_key_and_cert::<RelayLinkSigningKeypair, RelayLinkSigningKeyCert>(&link_spec)
+            .unwrap()
+            .unwrap();
+        assert_eq!(link_signing.public(), keyset.link_signing);
+
+        // We refuse to clobber an existing identity.
+        assert!(matches!(
+            generate_relay_keyset(&keymgr, now),
+            Err(tor_keymgr::Error::KeyAlreadyExists)
+        ));
+    }
+
+    #[test]
+    fn round_up() {
+        let t = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+        assert_eq!(round_up_to_hour(t(0)), t(0));
+        assert_eq!(round_up_to_hour(t(1)), t(3600));
+        assert_eq!(round_up_to_hour(t(3600)), t(3600));
+        assert_eq!(round_up_to_hour(t(3601)), t(7200));
+    }
+}
@@ -44,7 +44,11 @@
 //! <!-- @@ end lint list maintained by maint/add_warning @@ -->
 
 pub mod certs;
+#[cfg(feature = "experimental-api")]
+pub mod keyset;
 pub mod pk;
 
 // Pleasant re-export.
 pub use certs::{gen_link_cert, gen_signing_cert, RelayLinkSigningKeyCert, RelaySigningKeyCert};
+#[cfg(feature = "experimental-api")]
+pub use keyset::{generate_relay_keyset, RelayKeySet};
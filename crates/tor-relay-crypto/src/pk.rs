@@ -9,10 +9,10 @@ use derive_more::derive::{From, Into};
 use derive_more::Constructor;
 
 use tor_error::Bug;
-use tor_key_forge::define_ed25519_keypair;
+use tor_key_forge::{define_ed25519_keypair, ItemType as _, ToEncodableKey};
 use tor_keymgr::{
-    derive_deftly_template_KeySpecifier, InvalidKeyPathComponentValue, KeySpecifier,
-    KeySpecifierComponent,
+    derive_deftly_template_KeySpecifier, InvalidKeyPathComponentValue, KeyMgr, KeyPath,
+    KeySpecifier, KeySpecifierComponent, KeySpecifierPattern as _,
 };
 use tor_persist::slug::{timestamp::Iso8601TimeSlug, Slug};
 
@@ -72,6 +72,34 @@ pub struct RelaySigningKeypairSpecifier {
     pub(crate) valid_until: Timestamp,
 }
 
+impl RelaySigningKeypairSpecifier {
+    /// List the medium-term signing keypairs in `keymgr`, newest first.
+    ///
+    /// Returns the `valid_until` timestamp and the [`KeyPath`] of each keypair,
+    /// sorted by timestamp in descending order.
+    ///
+    /// Entries whose path can't be parsed as a `RelaySigningKeypairSpecifier`
+    /// (for example, because the timestamp is malformed) are ignored.
+    pub fn list_sorted(keymgr: &KeyMgr) -> tor_keymgr::Result<Vec<(Timestamp, KeyPath)>> {
+        let pattern = RelaySigningKeypairSpecifierPattern::new_any().arti_pattern()?;
+        // The certificate of each keypair is stored at the same path.
+        let key_type = <RelaySigningKeypair as ToEncodableKey>::Key::item_type();
+
+        let mut keys = keymgr
+            .list_matching(&pattern)?
+            .into_iter()
+            .filter(|entry| entry.key_type() == &key_type)
+            .filter_map(|entry| {
+                let spec = RelaySigningKeypairSpecifier::try_from(entry.key_path()).ok()?;
+                Some((spec.valid_until, entry.key_path().clone()))
+            })
+            .collect::<Vec<_>>();
+        keys.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+        Ok(keys)
+    }
+}
+
 /// The approximate time when a [`RelaySigningKeypairSpecifier`] was generated.
 ///
 /// Used as a denotator to distinguish between the different signing keypair instances
@@ -113,6 +141,23 @@ define_ed25519_keypair!(
     pub RelayLinkSigning
 );
 
+#[derive(Deftly, PartialEq, Debug, Constructor)]
+#[derive_deftly(KeySpecifier)]
+#[deftly(prefix = "relay")]
+#[deftly(role = "KS_link_ed")]
+#[deftly(summary = "Relay short-term link authentication keypair")]
+/// The key specifier of the relay short-term link authentication key.
+pub struct RelayLinkSigningKeypairSpecifier {
+    /// The expiration time of this key.
+    ///
+    /// Like the `valid_until` of a [`RelaySigningKeypairSpecifier`],
+    /// this **must** be the same as the expiration timestamp from the
+    /// `K_link_ed` certificate of this key,
+    /// and should only be used to tell apart the different instances of this key.
+    #[deftly(denotator)]
+    pub(crate) valid_until: Timestamp,
+}
+
 #[cfg(test)]
 mod test {
     // @@ begin test lint list maintained by maint/add_warning @@
@@ -130,7 +175,11 @@ mod test {
     //! <!-- @@ end test lint list maintained by maint/add_warning @@ -->
     use super::*;
 
-    use tor_keymgr::test_utils::check_key_specifier;
+    use std::time::Duration;
+
+    use tor_keymgr::test_utils::{check_key_specifier, TestKeystore};
+    use tor_keymgr::{KeyMgrBuilder, KeystoreSelector};
+    use tor_llcrypto::rng::CautiousRng;
 
     #[test]
     fn relay_signing_key_specifiers() {
@@ -145,6 +194,49 @@ mod test {
         check_key_specifier(&key_spec, "relay/ks_relaysign_ed+19700101000000");
     }
 
+    #[test]
+    fn list_signing_keys_sorted() {
+        let keymgr = KeyMgrBuilder::default()
+            .primary_store(Box::new(TestKeystore::new("test")))
+            .build()
+            .unwrap();
+        let day = |n| SystemTime::UNIX_EPOCH + Duration::from_secs(n * 24 * 60 * 60);
+
+        assert!(RelaySigningKeypairSpecifier::list_sorted(&keymgr)
+            .unwrap()
+            .is_empty());
+
+        // Insert the keys out of order, along with a key of another kind.
+        let _: RelayIdentityKeypair = keymgr
+            .generate(
+                &RelayIdentityKeypairSpecifier::new(),
+                KeystoreSelector::Primary,
+                &mut CautiousRng,
+                false,
+            )
+            .unwrap();
+        for n in [2, 3, 1] {
+            let _: RelaySigningKeypair = keymgr
+                .generate(
+                    &RelaySigningKeypairSpecifier::new(day(n).into()),
+                    KeystoreSelector::Primary,
+                    &mut CautiousRng,
+                    false,
+                )
+                .unwrap();
+        }
+
+        let keys = RelaySigningKeypairSpecifier::list_sorted(&keymgr).unwrap();
+        let expected = [3, 2, 1]
+            .into_iter()
+            .map(|n| {
+                let spec = RelaySigningKeypairSpecifier::new(day(n).into());
+                (spec.valid_until, KeyPath::from(spec.arti_path().unwrap()))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(keys, expected);
+    }
+
     #[test]
     fn relay_identity_key_specifiers() {
         let key_spec = RelayIdentityKeypairSpecifier::new();
@@ -165,4 +257,17 @@ mod test {
 
         check_key_specifier(&key_spec, "relay/kp_relayid_ed");
     }
+
+    #[test]
+    fn relay_link_signing_key_specifiers() {
+        let ts = SystemTime::UNIX_EPOCH;
+        let key_spec = RelayLinkSigningKeypairSpecifier::new(ts.into());
+
+        assert_eq!(
+            key_spec.arti_path().unwrap().as_str(),
+            "relay/ks_link_ed+19700101000000"
+        );
+
+        check_key_specifier(&key_spec, "relay/ks_link_ed+19700101000000");
+    }
 }
@@ -18,8 +18,44 @@
 //! { ... }
 //! ```
 //!
+//! The context is passed as an `Arc`,
+//! so the function can keep its own reference to it
+//! (for example, to use from a background task) after it returns.
+//! There is no separate form taking a `Box<dyn rpc::Context>`.
+//!
 //! If the "updates" argument is present,
 //! then you will need to use the `[Updates]` flag when registering this function.
+//! Once registered, you can tell whether a function takes an update sink
+//! with [`Invocable::supports_updates`].
+//!
+//! If sending an update fails with [`SendUpdateError::PeerGone`],
+//! the client that made the request is gone,
+//! and a function that is streaming updates should stop work and return:
+//! ```rust,ignore
+//! if let Err(SendUpdateError::PeerGone) = updates.send(progress).await {
+//!     return Err(MyError::Cancelled);
+//! }
+//! ```
+//!
+//! A function that is done sending updates,
+//! but still has work to do before it can return its output,
+//! can say so with [`UpdateSinkExt::finish_updates`]:
+//! ```rust,ignore
+//! updates.send(Progress::Done).await?;
+//! updates.finish_updates().await?;
+//! let output = compute_the_output().await?;
+//! ```
+//! The RPC server then tells the client that no more updates will follow,
+//! so that it can show that the updates are finished
+//! while it waits for the final response.
+//!
+//! If an RPC function panics while it is running,
+//! the panic is caught at the dispatch boundary,
+//! and the client receives an internal error in reply to its request.
+//! (This only applies to invocations over RPC, via [`RpcInvocable::invoke`].
+//! Special methods and local invocations are not isolated in this way.)
+//! Even so, RPC functions should not panic:
+//! any panic is a bug.
 //!
 //! ## Registering RPC functions statically
 //!
@@ -54,6 +90,7 @@ use std::sync::Arc;
 
 use futures::future::BoxFuture;
 use futures::Sink;
+use static_assertions::assert_impl_all;
 
 use tor_error::internal;
 use void::Void;
@@ -85,6 +122,9 @@ pub type RpcSendResult = Result<RpcValue, SendUpdateError>;
 
 /// A boxed future holding the result of an RPC method.
 pub type RpcResultFuture = BoxFuture<'static, RpcResult>;
+// Embedders run these futures on multithreaded executors,
+// which require spawned futures to be `Send`.
+assert_impl_all! { RpcResultFuture: Send }
 
 /// A boxed sink on which updates can be sent.
 pub type BoxedUpdateSink = Pin<Box<dyn Sink<RpcValue, Error = SendUpdateError> + Send>>;
@@ -97,6 +137,25 @@ pub type BoxedUpdateSink = Pin<Box<dyn Sink<RpcValue, Error = SendUpdateError> +
 // extra boxing in this case ever matters.
 pub type UpdateSink<U> = Pin<Box<dyn Sink<U, Error = SendUpdateError> + Send + 'static>>;
 
+/// Extension trait for [`UpdateSink`].
+pub trait UpdateSinkExt<U> {
+    /// Declare that no more updates will be sent on this sink.
+    ///
+    /// This closes the sink, so that whoever is receiving the updates
+    /// can stop waiting for them,
+    /// even though the method has not yet returned its final output.
+    /// Any attempt to send an update afterwards will fail.
+    ///
+    /// See the [module documentation](self) for an example.
+    fn finish_updates(&mut self) -> futures::sink::Close<'_, Self, U>;
+}
+
+impl<U> UpdateSinkExt<U> for UpdateSink<U> {
+    fn finish_updates(&mut self) -> futures::sink::Close<'_, Self, U> {
+        futures::SinkExt::close(self)
+    }
+}
+
 /// Type returned by DispatchTable::invoke_special, to represent a future containing
 /// a type-erased type.
 type SpecialResultFuture = BoxFuture<'static, Box<dyn any::Any>>;
@@ -117,6 +176,32 @@ pub trait Invocable: Send + Sync + 'static {
     ///
     /// Caveats apply as for [`any::type_name`].
     fn object_and_method_type_names(&self) -> (&'static str, &'static str);
+    /// Return true if the function behind this Invocable takes an [`UpdateSink`],
+    /// and can therefore send incremental updates.
+    fn supports_updates(&self) -> bool {
+        false
+    }
+    /// Return the name of the object type this Invocable will accept,
+    /// without any generic type parameters.
+    ///
+    /// All of the instantiations of a single generic handler
+    /// (for example, on `GenericObj<u32, u32>` and on `GenericObj<String, f32>`)
+    /// have the same base type name (in this case, the path of `GenericObj`),
+    /// so this can be used to group them together.
+    ///
+    /// Caveats apply as for [`any::type_name`].
+    fn object_base_type_name(&self) -> &'static str {
+        let (object_name, _) = self.object_and_method_type_names();
+        object_name
+            .split_once('<')
+            .map_or(object_name, |(base, _)| base)
+    }
+    /// Return true if the object type this Invocable will accept
+    /// is an instantiation of a generic type.
+    fn is_generic_instantiation(&self) -> bool {
+        let (object_name, _) = self.object_and_method_type_names();
+        self.object_base_type_name() != object_name
+    }
     /// Describe the types for this Invocable.  Used for debugging.
     fn describe_invocable(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let (object_name, method_name) = self.object_and_method_type_names();
@@ -144,6 +229,24 @@ pub trait Invocable: Send + Sync + 'static {
         method: Box<dyn DynMethod>,
         ctx: Arc<dyn Context>,
     ) -> Result<SpecialResultFuture, InvokeError>;
+
+    /// Invoke this method on an object, sending any updates to `sink`.
+    ///
+    /// As `invoke_special()`, except that if the function behind this Invocable
+    /// [supports updates](Invocable::supports_updates),
+    /// they are sent to `sink` rather than discarded.
+    ///
+    /// The default implementation discards `sink` and calls `invoke_special()`.
+    fn invoke_special_with_updates(
+        &self,
+        obj: Arc<dyn Object>,
+        method: Box<dyn DynMethod>,
+        ctx: Arc<dyn Context>,
+        sink: BoxedUpdateSink,
+    ) -> Result<SpecialResultFuture, InvokeError> {
+        drop(sink);
+        self.invoke_special(obj, method, ctx)
+    }
 }
 
 /// Subtrait of `Invocable` that requires its outputs to be serializable as RPC replies.
@@ -152,6 +255,11 @@ pub trait RpcInvocable: Invocable {
     ///
     /// Requires that `obj` has the type `self.object_type()`,
     /// and that `method` has the type `self.method_type()`.
+    ///
+    /// If the function behind this `RpcInvocable` panics while its future is being polled,
+    /// the panic does not propagate to the caller:
+    /// instead, the future resolves to an internal error
+    /// (as from [`InvokeError::Bug`]).
     fn invoke(
         &self,
         obj: Arc<dyn Object>,
@@ -159,6 +267,42 @@ pub trait RpcInvocable: Invocable {
         ctx: Arc<dyn Context>,
         sink: BoxedUpdateSink,
     ) -> Result<RpcResultFuture, InvokeError>;
+
+    /// Invoke a method that [yields a stream](crate::stream::yields_stream) on an object,
+    /// and return the resulting [`StreamHandle`](crate::stream::StreamHandle).
+    ///
+    /// Requires that `obj` has the type `self.object_type()`,
+    /// and that `method` has the type `self.method_type()`.
+    /// Returns an error if the method's output is not a `StreamHandle`.
+    ///
+    /// Panics are handled as in [`RpcInvocable::invoke`].
+    ///
+    /// The default implementation always returns an error.
+    fn invoke_stream(
+        &self,
+        obj: Arc<dyn Object>,
+        method: Box<dyn DynMethod>,
+        ctx: Arc<dyn Context>,
+        sink: BoxedUpdateSink,
+    ) -> Result<crate::stream::StreamResultFuture, InvokeError> {
+        let _ = (obj, method, ctx, sink);
+        Err(InvokeError::Bug(tor_error::bad_api_usage!(
+            "This RpcInvocable does not support stream methods"
+        )))
+    }
+}
+
+/// Helper: Return an `RpcError` describing a panic with payload `panic`
+/// from the future of an RPC function.
+fn handler_panicked(panic: &(dyn any::Any + Send)) -> RpcError {
+    let msg = if let Some(s) = panic.downcast_ref::<&str>() {
+        s
+    } else if let Some(s) = panic.downcast_ref::<String>() {
+        s.as_str()
+    } else {
+        "(no message)"
+    };
+    InvokeError::Bug(internal!("RPC method implementation panicked: {msg}")).into()
 }
 
 /// Helper: Declare a blanket implementation for Invocable.
@@ -168,9 +312,11 @@ pub trait RpcInvocable: Invocable {
 /// and once over a fn() not taking an update sink.
 macro_rules! declare_invocable_impl {
     {
+      // Whether the function takes an update sink.
+      supports_updates: $supports_updates:literal
       // These arguments are used to fill in some blanks that we need to use
       // when handling an update sink.
-      $( update_gen: $update_gen:ident,
+      $( , update_gen: $update_gen:ident,
          update_arg: { $sink:ident: $update_arg:ty } ,
          update_arg_where: { $($update_arg_where:tt)+ } ,
          sink_fn: $sink_fn:expr
@@ -202,6 +348,10 @@ macro_rules! declare_invocable_impl {
                 )
             }
 
+            fn supports_updates(&self) -> bool {
+                $supports_updates
+            }
+
             fn invoke_special(
                 &self,
                 obj: Arc<dyn Object>,
@@ -229,6 +379,38 @@ macro_rules! declare_invocable_impl {
                         .boxed()
                  )
             }
+
+            fn invoke_special_with_updates(
+                &self,
+                obj: Arc<dyn Object>,
+                method: Box<dyn DynMethod>,
+                ctx: Arc<dyn Context>,
+                #[allow(unused)]
+                sink: BoxedUpdateSink,
+            ) -> Result<SpecialResultFuture, $crate::InvokeError> {
+                use futures::FutureExt;
+                #[allow(unused)]
+                use tor_async_utils::SinkExt as _;
+
+                let Ok(obj) = obj.downcast_arc::<OBJ>() else {
+                    return Err(InvokeError::Bug($crate::internal!("Wrong object type")));
+                };
+                let Ok(method) = method.downcast::<M>() else {
+                    return Err(InvokeError::Bug($crate::internal!("Wrong method type")));
+                };
+                $(
+                #[allow(clippy::redundant_closure_call)]
+                let $sink = {
+                    ($sink_fn)(sink)
+                };
+                )?
+
+                Ok(
+                    (self)(obj, method, ctx $(, $sink )? )
+                        .map(|r| Box::new(r) as Box<dyn any::Any>)
+                        .boxed()
+                )
+            }
         }
 
         impl<M, OBJ, Fut, S, E, $($update_gen)?> RpcInvocable
@@ -269,25 +451,81 @@ macro_rules! declare_invocable_impl {
                 };
                 )?
 
+                // We catch any panic from the function's future here,
+                // so that it can't unwind across the dispatch boundary.
                 Ok(
-                    (self)(obj, method, ctx $(, $sink)? )
+                    std::panic::AssertUnwindSafe((self)(obj, method, ctx $(, $sink)? ))
+                        .catch_unwind()
                         .map(|r| {
                             let r: RpcResult = match r {
-                                Ok(v) => Ok(Box::new(M::Output::from(v))),
-                                Err(e) => Err(RpcError::from(e)),
+                                Ok(Ok(v)) => Ok(Box::new(M::Output::from(v))),
+                                Ok(Err(e)) => Err(RpcError::from(e)),
+                                Err(panic) => Err(handler_panicked(&*panic)),
                             };
                             r
                         })
                         .boxed()
                 )
             }
+
+            fn invoke_stream(
+                &self,
+                obj: Arc<dyn Object>,
+                method: Box<dyn DynMethod>,
+                ctx: Arc<dyn Context>,
+                #[allow(unused)]
+                sink: BoxedUpdateSink,
+            ) -> Result<$crate::stream::StreamResultFuture, $crate::InvokeError> {
+                use futures::FutureExt;
+                use $crate::stream::StreamHandle;
+                #[allow(unused)]
+                use tor_async_utils::SinkExt as _;
+
+                if any::TypeId::of::<M::Output>() != any::TypeId::of::<StreamHandle>() {
+                    return Err(InvokeError::Bug(tor_error::bad_api_usage!(
+                        "Tried to invoke {} as a stream method",
+                        any::type_name::<M>()
+                    )));
+                }
+                let Ok(obj) = obj.downcast_arc::<OBJ>() else {
+                   return Err(InvokeError::Bug($crate::internal!("Wrong object type")));
+                };
+                let Ok(method) = method.downcast::<M>() else {
+                    return Err(InvokeError::Bug($crate::internal!("Wrong method type")));
+                };
+                $(
+                #[allow(clippy::redundant_closure_call)]
+                let $sink = {
+                    ($sink_fn)(sink)
+                };
+                )?
+
+                Ok(
+                    std::panic::AssertUnwindSafe((self)(obj, method, ctx $(, $sink)? ))
+                        .catch_unwind()
+                        .map(|r| match r {
+                            Ok(Ok(v)) => {
+                                let output: Box<dyn any::Any + Send> = Box::new(M::Output::from(v));
+                                output.downcast::<StreamHandle>().map(|h| *h).map_err(|_| {
+                                    InvokeError::Bug($crate::internal!("Stream method output had wrong type")).into()
+                                })
+                            }
+                            Ok(Err(e)) => Err(RpcError::from(e)),
+                            Err(panic) => Err(handler_panicked(&*panic)),
+                        })
+                        .boxed()
+                )
+            }
         }
     }
 }
 
-declare_invocable_impl! {}
+declare_invocable_impl! {
+    supports_updates: false
+}
 
 declare_invocable_impl! {
+    supports_updates: true,
     update_gen: U,
     update_arg: { sink: UpdateSink<U> },
     update_arg_where: {
@@ -333,6 +571,9 @@ pub struct InvokerEnt {
     #[doc(hidden)]
     pub function: &'static str,
 }
+// Every entry of a DispatchTable is one of these,
+// so the table can only be shared between threads if they can.
+assert_impl_all! { InvokerEnt: Send, Sync }
 impl InvokerEnt {
     /// Return true if these two entries appear to be the same declaration
     /// for the same function.
@@ -345,6 +586,13 @@ impl InvokerEnt {
     fn same_decl(&self, other: &Self) -> bool {
         self.file == other.file && self.line == other.line && self.function == other.function
     }
+
+    /// Return the RPC method name (such as "arti:foo") of the method this entry implements.
+    ///
+    /// Returns `None` if the method is not an [`RpcMethod`](crate::RpcMethod).
+    pub fn method_name(&self) -> Option<&'static str> {
+        crate::method::method_info_by_typeid(self.invoker.method_type()).map(|mi| mi.method_name)
+    }
 }
 
 /// Create an [`InvokerEnt`] around a single function.
@@ -599,18 +847,52 @@ struct FuncType {
     method_id: any::TypeId,
 }
 
+impl FuncType {
+    /// Return the `FuncType` that `ent` implements.
+    fn of(ent: &InvokerEnt) -> Self {
+        FuncType {
+            obj_id: ent.invoker.object_type(),
+            method_id: ent.invoker.method_type(),
+        }
+    }
+}
+
 /// A collection of method implementations for different method and object types.
 ///
 /// A DispatchTable is constructed at run-time from entries registered with
 /// [`static_rpc_invoke_fn!`].
 ///
 /// There is one for each `arti-rpcserver::RpcMgr`, shared with each `arti-rpcserver::Connection`.
+///
+/// A table that needs its own additional entries
+/// (for example, to override some methods on a single connection)
+/// can be derived cheaply from another one with [`overlay`](DispatchTable::overlay).
+/// The entries of the original table are shared, not copied:
+/// the new table only stores the entries that are inserted into it afterwards.
+/// Those entries may replace entries inherited from the original table,
+/// without affecting the original table.
 #[derive(Debug, Clone)]
 pub struct DispatchTable {
     /// An internal HashMap used to look up the correct function for a given
     /// method/object pair.
-    map: HashMap<FuncType, InvokerEnt>,
+    ///
+    /// This is shared with every table derived from this one.
+    base: Arc<HashMap<FuncType, InvokerEnt>>,
+    /// Entries that were added to this table after its `base` was shared.
+    ///
+    /// These take precedence over the entries in `base`.
+    overlay: HashMap<FuncType, InvokerEnt>,
+    /// Whether the entries in `base` were inherited from another table
+    /// via [`overlay`](DispatchTable::overlay).
+    ///
+    /// If so, they may be replaced by [`insert`](DispatchTable::insert)
+    /// with a different implementation;
+    /// if not, they are our own, and replacing them is an error.
+    inherited: bool,
 }
+// A DispatchTable is shared by every connection of an RPC manager
+// (behind an `Arc<RwLock<_>>`), and those connections may run on any thread.
+assert_impl_all! { DispatchTable: Send, Sync }
 
 impl DispatchTable {
     /// Construct a `DispatchTable` from the entries registered statically via
@@ -620,11 +902,25 @@ impl DispatchTable {
     ///
     /// Panics if two entries are found for the same (method,object) types.
     pub fn from_inventory() -> Self {
+        Self::from_inventory_filtered(|_| true)
+    }
+
+    /// Construct a `DispatchTable` from those entries registered statically via
+    /// [`static_rpc_invoke_fn!`] for which `pred` returns true.
+    ///
+    /// This can be used to expose only a subset of the available methods.
+    ///
+    /// # Panics
+    ///
+    /// Panics if two accepted entries are found for the same (method,object) types.
+    pub fn from_inventory_filtered(pred: impl Fn(&InvokerEnt) -> bool) -> Self {
         // We want to assert that there are no duplicates, so we can't use "collect"
         let mut this = Self {
-            map: HashMap::new(),
+            base: Arc::new(HashMap::new()),
+            overlay: HashMap::new(),
+            inherited: false,
         };
-        for ent in inventory::iter::<InvokerEnt>() {
+        for ent in inventory::iter::<InvokerEnt>().filter(|ent| pred(ent)) {
             let old_val = this.insert_inner(*ent);
             if old_val.is_some() {
                 panic!("Tried to insert duplicate entry for {:?}", ent);
@@ -633,28 +929,143 @@ impl DispatchTable {
         this
     }
 
+    /// Return a new `DispatchTable` with the same entries as this one.
+    ///
+    /// This does not copy the entries of this table:
+    /// they are shared between the two tables,
+    /// and each table keeps track of the entries added to it afterwards
+    /// separately, without affecting the other.
+    /// Lookups in the new table consult its own entries first,
+    /// and then the shared ones.
+    ///
+    /// Unlike in the original table, entries inserted into the new table
+    /// may override the ones it shares with the original table:
+    /// see [`insert`](DispatchTable::insert).
+    ///
+    /// (If this table is itself an overlay with entries of its own,
+    /// those entries are copied into the set of entries that the new table shares.)
+    pub fn overlay(&self) -> Self {
+        let base = if self.overlay.is_empty() {
+            Arc::clone(&self.base)
+        } else {
+            Arc::new(
+                self.typed_entries()
+                    .map(|(func_type, ent)| (func_type.clone(), *ent))
+                    .collect(),
+            )
+        };
+        Self {
+            base,
+            overlay: HashMap::new(),
+            inherited: true,
+        }
+    }
+
     /// Add a new entry to this DispatchTable, and return the old value if any.
     fn insert_inner(&mut self, ent: InvokerEnt) -> Option<InvokerEnt> {
-        self.map.insert(
-            FuncType {
-                obj_id: ent.invoker.object_type(),
-                method_id: ent.invoker.method_type(),
-            },
-            ent,
+        let func_type = FuncType::of(&ent);
+        if self.overlay.is_empty() && !self.inherited {
+            if let Some(base) = Arc::get_mut(&mut self.base) {
+                // Nobody else is using our base, so we can modify it in place.
+                return base.insert(func_type, ent);
+            }
+        }
+        let old_val = self.get(&func_type).copied();
+        self.overlay.insert(func_type, ent);
+        old_val
+    }
+
+    /// Return the entry for `func_type`, if there is one.
+    fn get(&self, func_type: &FuncType) -> Option<&InvokerEnt> {
+        self.overlay
+            .get(func_type)
+            .or_else(|| self.base.get(func_type))
+    }
+
+    /// Return the number of method implementations in this table.
+    pub fn len(&self) -> usize {
+        self.overlay.len()
+            + self
+                .base
+                .keys()
+                .filter(|func_type| !self.overlay.contains_key(func_type))
+                .count()
+    }
+
+    /// Return true if this table has no method implementations.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return an iterator over all the entries in this table, along with their types.
+    fn typed_entries(&self) -> impl Iterator<Item = (&FuncType, &InvokerEnt)> {
+        self.overlay.iter().chain(
+            self.base
+                .iter()
+                .filter(|(func_type, _)| !self.overlay.contains_key(func_type)),
         )
     }
 
+    /// Return an iterator over all the entries in this table.
+    fn entries(&self) -> impl Iterator<Item = &InvokerEnt> {
+        self.typed_entries().map(|(_, ent)| ent)
+    }
+
     /// Add a new entry to this DispatchTable.
     ///
     /// # Panics
     ///
     /// Panics if there was a previous entry inserted with the same (Object,Method) pair,
     /// but (apparently) with a different implementation function, or from a macro invocation.
+    ///
+    /// This does not apply to entries that this table inherited from another table
+    /// via [`overlay`](DispatchTable::overlay):
+    /// those are replaced (in this table only) by the new entry.
     pub fn insert(&mut self, ent: InvokerEnt) {
+        let overrides_inherited = self.inherited && !self.overlay.contains_key(&FuncType::of(&ent));
         if let Some(old_ent) = self.insert_inner(ent) {
             // This is not a perfect check by any means; see `same_decl`.
-            assert!(old_ent.same_decl(&ent));
+            assert!(overrides_inherited || old_ent.same_decl(&ent));
+        }
+    }
+
+    /// Remove every entry for the RPC method called `method_name` (such as "arti:foo"),
+    /// on any type of object.
+    ///
+    /// Returns the number of entries that were removed.
+    ///
+    /// Tables that were derived from this one (or that this one was derived from)
+    /// with [`overlay`](DispatchTable::overlay) are not affected.
+    /// If any of the shared entries are removed, this table stops sharing its entries with them,
+    /// and makes its own copy instead.
+    pub fn remove_by_name(&mut self, method_name: &str) -> usize {
+        let n_removed = self
+            .entries()
+            .filter(|ent| ent.method_name() == Some(method_name))
+            .count();
+        if n_removed == 0 {
+            return 0;
+        }
+
+        // We keep our own entries separate from the inherited ones,
+        // so that the inherited ones can still be overridden afterwards.
+        self.overlay
+            .retain(|_, ent| ent.method_name() != Some(method_name));
+        if self
+            .base
+            .values()
+            .any(|ent| ent.method_name() == Some(method_name))
+        {
+            let remaining = self
+                .base
+                .iter()
+                .filter(|(_, ent)| ent.method_name() != Some(method_name))
+                .map(|(func_type, ent)| (func_type.clone(), *ent))
+                .collect();
+            self.base = Arc::new(remaining);
         }
+
+        n_removed
     }
 
     /// Add multiple new entries to this DispatchTable.
@@ -669,6 +1080,64 @@ impl DispatchTable {
         ents.into_iter().for_each(|e| self.insert(e));
     }
 
+    /// Invoke the method `method` on the object `obj`, and return its result,
+    /// without erasing any of their types.
+    ///
+    /// Unlike regular method invocation, this does not perform any delegation:
+    /// the implementation is looked up using the concrete types `O` and `M`,
+    /// and [`InvokeError::NoImpl`] is returned if there isn't one.
+    /// Updates are sent to `sink`, if the implementation sends any.
+    ///
+    /// This is mainly useful for testing an implementation in isolation.
+    /// It only works for implementations that return
+    /// exactly `Result<M::Output, RpcError>`:
+    /// if the implementation returns any other type
+    /// (even one that can be converted into `M::Output` or `RpcError`),
+    /// the future resolves to an internal error.
+    pub fn invoke_typed<O: Object, M: crate::RpcMethod>(
+        &self,
+        obj: Arc<O>,
+        method: Box<M>,
+        ctx: Arc<dyn Context>,
+        sink: BoxedUpdateSink,
+    ) -> Result<BoxFuture<'static, Result<M::Output, RpcError>>, InvokeError> {
+        let func_type = FuncType {
+            obj_id: any::TypeId::of::<O>(),
+            method_id: any::TypeId::of::<M>(),
+        };
+        let ent = self.get(&func_type).ok_or(InvokeError::NoImpl)?;
+        let fut = ent
+            .invoker
+            .invoke_special_with_updates(obj, method, ctx, sink)?;
+
+        Ok(downcast_rpc_result::<M>(fut))
+    }
+
+    /// Invoke the RPC method `method` on `obj` within this process,
+    /// and return its result as an `M::Output`, without serializing it.
+    ///
+    /// Unlike [`DispatchTable::invoke_typed`], this takes a type-erased object,
+    /// and performs delegation just as an invocation over RPC would.
+    /// Updates are sent to `sink`, if the implementation sends any.
+    ///
+    /// Like `invoke_typed`, this only works for implementations that return
+    /// exactly `Result<M::Output, RpcError>`:
+    /// for any other implementation, the future resolves to an internal error.
+    pub fn invoke_local<M: crate::RpcMethod>(
+        &self,
+        obj: Arc<dyn Object>,
+        method: Box<M>,
+        ctx: Arc<dyn Context>,
+        sink: BoxedUpdateSink,
+    ) -> Result<BoxFuture<'static, Result<M::Output, RpcError>>, InvokeError> {
+        let (obj, ent) = self.resolve_entry(obj, any::TypeId::of::<M>())?;
+        let fut = ent
+            .invoker
+            .invoke_special_with_updates(obj, method, ctx, sink)?;
+
+        Ok(downcast_rpc_result::<M>(fut))
+    }
+
     /// Helper: Look up the `InvokerEnt` for a given method on a given object,
     /// performing delegation as necessary.
     ///
@@ -685,7 +1154,7 @@ impl DispatchTable {
                 dyn_obj.type_id()
             };
             let func_type = FuncType { obj_id, method_id };
-            if let Some(ent) = self.map.get(&func_type) {
+            if let Some(ent) = self.get(&func_type) {
                 return Ok((obj, ent));
             } else if let Some(delegation) = obj.delegate() {
                 obj = delegation;
@@ -728,6 +1197,52 @@ impl DispatchTable {
     }
 }
 
+/// Helper: Convert the type-erased result of invoking an implementation of `M`
+/// back into the `Result<M::Output, RpcError>` that the implementation returned.
+///
+/// If the implementation returned some other type, the future resolves to an internal error.
+fn downcast_rpc_result<M: crate::RpcMethod>(
+    fut: SpecialResultFuture,
+) -> BoxFuture<'static, Result<M::Output, RpcError>> {
+    use futures::FutureExt as _;
+
+    fut.map(|r| match r.downcast::<Result<M::Output, RpcError>>() {
+        Ok(r) => *r,
+        Err(_) => Err(InvokeError::Bug(internal!("Downcast to wrong type")).into()),
+    })
+    .boxed()
+}
+
+/// Displays a concise summary of the table, suitable for logging.
+///
+/// The plain form (`{}`) gives only the number of method implementations.
+/// The alternate form (`{:#}`) also lists each of them,
+/// as `method on object type`, sorted.
+/// RPC methods are listed by their RPC method name;
+/// special methods, which have none, are listed by their type name.
+impl std::fmt::Display for DispatchTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} method implementations", self.len())?;
+        if f.alternate() {
+            let mut names: Vec<_> = self
+                .entries()
+                .map(|ent| {
+                    let (object, method) = ent.invoker.object_and_method_type_names();
+                    let method = crate::method::method_info_by_typeid(ent.invoker.method_type())
+                        .map_or(method, |info| info.method_name);
+                    (method, object)
+                })
+                .collect();
+            names.sort_unstable();
+            for (i, (method, object)) in names.into_iter().enumerate() {
+                let sep = if i == 0 { ": " } else { ", " };
+                write!(f, "{sep}{method} on {object}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
 /// An error that occurred while trying to invoke a method on an object.
 #[derive(Debug, Clone, thiserror::Error)]
 #[non_exhaustive]
@@ -781,7 +1296,7 @@ pub(crate) mod test {
     use futures_await_test::async_test;
     use std::sync::{Arc, RwLock};
 
-    use super::UpdateSink;
+    use super::{FuncType, UpdateSink};
 
     // Define 3 animals and one brick.
     #[derive(Clone, Deftly)]
@@ -1121,6 +1636,218 @@ pub(crate) mod test {
         ));
     }
 
+    #[derive(Clone, Deftly)]
+    #[derive_deftly(Object)]
+    struct Platypus;
+
+    /// Return the address of the dispatch table in `ctx`,
+    /// after holding on to `ctx` across an await point.
+    async fn getname_platypus(
+        _obj: Arc<Platypus>,
+        _method: Box<GetName>,
+        ctx: Arc<dyn crate::Context>,
+    ) -> Result<Outcome, crate::RpcError> {
+        let kept: Arc<dyn crate::Context> = Arc::clone(&ctx);
+        drop(ctx);
+        futures::future::ready(()).await;
+        Ok(Outcome {
+            v: format!("{:p}", Arc::as_ptr(kept.dispatch_table())),
+        })
+    }
+    static_rpc_invoke_fn! {
+        getname_platypus;
+    }
+
+    #[async_test]
+    async fn invoke_keeps_context() {
+        let ctx: Arc<dyn crate::Context> = Arc::new(Ctx::from(DispatchTable::from_inventory()));
+        let obj: Arc<dyn crate::Object> = Arc::new(Platypus);
+        let discard = Box::pin(futures::sink::drain().sink_err_into());
+        let res = crate::invoke_rpc_method(
+            Arc::clone(&ctx),
+            &crate::ObjectId::from("Platypus"),
+            obj,
+            Box::new(GetName),
+            discard,
+        )
+        .unwrap()
+        .await
+        .unwrap();
+
+        // The function saw the same context that we passed in.
+        let expected = format!("{:p}", Arc::as_ptr(ctx.dispatch_table()));
+        assert_eq!(
+            serde_json::to_string(&res).unwrap(),
+            format!(r#"{{"v":"{expected}"}}"#)
+        );
+    }
+
+    #[derive(Clone, Deftly)]
+    #[derive_deftly(Object)]
+    struct Kangaroo;
+
+    async fn getname_kangaroo(
+        _obj: Arc<Kangaroo>,
+        _method: Box<GetName>,
+        _ctx: Arc<dyn crate::Context>,
+    ) -> Result<Outcome, crate::RpcError> {
+        futures::future::ready(()).await;
+        panic!("Kangaroos don't have names");
+    }
+    static_rpc_invoke_fn! {
+        getname_kangaroo;
+    }
+
+    #[async_test]
+    async fn invoke_panics() {
+        let ctx: Arc<dyn crate::Context> = Arc::new(Ctx::from(DispatchTable::from_inventory()));
+        let obj: Arc<dyn crate::Object> = Arc::new(Kangaroo);
+        let discard = Box::pin(futures::sink::drain().sink_err_into());
+        let fut = crate::invoke_rpc_method(
+            Arc::clone(&ctx),
+            &crate::ObjectId::from("Kangaroo"),
+            obj,
+            Box::new(GetName),
+            discard,
+        )
+        .unwrap();
+
+        // The panic turns into an error, rather than unwinding into our task.
+        let err = fut.await.err().unwrap();
+        let err = serde_json::to_value(&err).unwrap();
+        assert_eq!(err["message"], "Internal error");
+        assert_eq!(err["code"], -32603);
+    }
+
+    #[derive(Clone, Deftly)]
+    #[derive_deftly(Object)]
+    struct Echidna;
+
+    async fn getkids_echidna(
+        _obj: Arc<Echidna>,
+        _method: Box<GetKids>,
+        _ctx: Arc<dyn crate::Context>,
+        mut sink: UpdateSink<String>,
+    ) -> Result<Outcome, crate::RpcError> {
+        let v = match sink.send("laying eggs".to_string()).await {
+            Ok(()) => "puggles",
+            Err(crate::SendUpdateError::PeerGone) => "nobody to tell",
+            Err(e) => panic!("unexpected error {e:?}"),
+        };
+        Ok(Outcome { v: v.to_string() })
+    }
+    static_rpc_invoke_fn! {
+        getkids_echidna;
+    }
+
+    #[async_test]
+    async fn update_peer_gone() {
+        let ctx: Arc<dyn crate::Context> = Arc::new(Ctx::from(DispatchTable::from_inventory()));
+        let invoke = |sink: super::BoxedUpdateSink| {
+            crate::invoke_rpc_method(
+                Arc::clone(&ctx),
+                &crate::ObjectId::from("Echidna"),
+                Arc::new(Echidna),
+                Box::new(GetKids),
+                sink,
+            )
+            .unwrap()
+        };
+
+        let (tx, mut rx) = futures::channel::mpsc::channel(4);
+        let res = invoke(Box::pin(tx.sink_err_into())).await.unwrap();
+        assert_eq!(serde_json::to_string(&res).unwrap(), r#"{"v":"puggles"}"#);
+        let update = futures::StreamExt::next(&mut rx).await.unwrap();
+        assert_eq!(serde_json::to_string(&update).unwrap(), r#""laying eggs""#);
+
+        // Once the receiver is gone, the method sees PeerGone.
+        let (tx, rx) = futures::channel::mpsc::channel(4);
+        drop(rx);
+        let res = invoke(Box::pin(tx.sink_err_into())).await.unwrap();
+        assert_eq!(
+            serde_json::to_string(&res).unwrap(),
+            r#"{"v":"nobody to tell"}"#
+        );
+    }
+
+    #[test]
+    fn update_send_errors() {
+        use crate::SendUpdateError;
+
+        // A channel with no buffer has room for one message from each sender.
+        let (mut tx, rx) = futures::channel::mpsc::channel::<u8>(0);
+        tx.try_send(1).unwrap();
+        let err = SendUpdateError::from(tx.try_send(2).unwrap_err().into_send_error());
+        assert!(matches!(err, SendUpdateError::Busy), "{err:?}");
+
+        drop(rx);
+        let err = SendUpdateError::from(tx.try_send(3).unwrap_err().into_send_error());
+        assert!(matches!(err, SendUpdateError::PeerGone), "{err:?}");
+    }
+
+    #[derive(Deftly)]
+    #[derive_deftly(Object)]
+    struct Mason {
+        /// Receives a message once the test has seen all the updates.
+        go_ahead: std::sync::Mutex<Option<tor_async_utils::oneshot::Receiver<()>>>,
+    }
+
+    async fn getkids_mason(
+        obj: Arc<Mason>,
+        _method: Box<GetKids>,
+        _ctx: Arc<dyn crate::Context>,
+        mut sink: UpdateSink<String>,
+    ) -> Result<Outcome, crate::RpcError> {
+        use super::UpdateSinkExt as _;
+
+        sink.send("mixing mortar".to_string()).await?;
+        sink.finish_updates().await?;
+        assert!(sink.send("too late".to_string()).await.is_err());
+
+        // Keep working until the test has seen the end of our updates.
+        let go_ahead = obj.go_ahead.lock().unwrap().take().unwrap();
+        go_ahead.await.unwrap();
+        Ok(Outcome {
+            v: "bricklayers".to_string(),
+        })
+    }
+    static_rpc_invoke_fn! {
+        getkids_mason;
+    }
+
+    #[async_test]
+    async fn finish_updates() {
+        use futures::StreamExt as _;
+
+        let table = DispatchTable::from_inventory();
+        let ctx: Arc<dyn crate::Context> = Arc::new(Ctx::from(table.clone()));
+        let (tx_go_ahead, rx_go_ahead) = tor_async_utils::oneshot::channel();
+        let mason = Arc::new(Mason {
+            go_ahead: std::sync::Mutex::new(Some(rx_go_ahead)),
+        });
+
+        let (tx, rx) = futures::channel::mpsc::unbounded();
+        let sink = Box::pin(tx.sink_map_err(|_| crate::SendUpdateError::ConnectionClosed));
+        let output = table
+            .invoke_typed(mason, Box::new(GetKids), ctx, sink)
+            .unwrap();
+
+        let updates = async {
+            // This only finishes once the update sink is closed.
+            let updates: Vec<_> = rx
+                .map(|u| serde_json::to_string(&u).unwrap())
+                .collect()
+                .await;
+            // Only now do we let the method return its output.
+            tx_go_ahead.send(()).unwrap();
+            updates
+        };
+
+        let (output, updates) = futures::join!(output, updates);
+        assert_eq!(updates, vec![r#""mixing mortar""#]);
+        assert_eq!(output.unwrap().v, "bricklayers");
+    }
+
     // Doesn't implement Deserialize.
     #[derive(Debug)]
     struct MyObject {}
@@ -1143,6 +1870,160 @@ pub(crate) mod test {
     }
     static_rpc_invoke_fn! { @special specialonly_swan; }
 
+    #[async_test]
+    async fn try_invoke_typed() {
+        use futures::StreamExt as _;
+
+        let table = crate::DispatchTable::from_inventory();
+        let ctx: Arc<dyn crate::Context> = Arc::new(Ctx::from(table.clone()));
+        let discard = || Box::pin(futures::sink::drain().sink_err_into());
+
+        let res: Outcome = table
+            .invoke_typed(
+                Arc::new(Swan),
+                Box::new(GetName),
+                Arc::clone(&ctx),
+                discard(),
+            )
+            .unwrap()
+            .await
+            .unwrap();
+        assert_eq!(res.v, "swan");
+
+        // Updates are sent to our sink.
+        let (tx, rx) = futures::channel::mpsc::unbounded();
+        let sink = Box::pin(tx.sink_map_err(|_| crate::SendUpdateError::ConnectionClosed));
+        let res = table
+            .invoke_typed(Arc::new(Wombat), Box::new(GetKids), Arc::clone(&ctx), sink)
+            .unwrap()
+            .await
+            .unwrap();
+        assert_eq!(res.v, "joeys");
+        let updates: Vec<_> = rx
+            .map(|u| serde_json::to_string(&u).unwrap())
+            .collect()
+            .await;
+        assert_eq!(updates, vec![r#""brb, burrowing""#]);
+
+        // No delegation is performed.
+        assert!(matches!(
+            table.invoke_typed(Arc::new(Brick), Box::new(GetKids), ctx, discard()),
+            Err(InvokeError::NoImpl)
+        ));
+    }
+
+    #[async_test]
+    async fn try_invoke_local() {
+        let table = crate::DispatchTable::from_inventory();
+        let ctx: Arc<dyn crate::Context> = Arc::new(Ctx::from(table.clone()));
+        let discard = || Box::pin(futures::sink::drain().sink_err_into());
+
+        let swan: Arc<dyn crate::Object> = Arc::new(Swan);
+        let res: Outcome = table
+            .invoke_local(swan, Box::new(GetName), Arc::clone(&ctx), discard())
+            .unwrap()
+            .await
+            .unwrap();
+        assert_eq!(res.v, "swan");
+
+        // Delegation is performed.
+        let carrier: Arc<dyn crate::Object> = Arc::new(CatCarrier {
+            contents: Some(Arc::new(Swan)),
+        });
+        let res: Outcome = table
+            .invoke_local(carrier, Box::new(GetName), Arc::clone(&ctx), discard())
+            .unwrap()
+            .await
+            .unwrap();
+        assert_eq!(res.v, "swan");
+
+        let empty: Arc<dyn crate::Object> = Arc::new(CatCarrier { contents: None });
+        assert!(matches!(
+            table.invoke_local(empty, Box::new(GetName), ctx, discard()),
+            Err(InvokeError::NoImpl)
+        ));
+    }
+
+    #[async_test]
+    async fn filtered_inventory() {
+        let table = crate::DispatchTable::from_inventory_filtered(|ent| {
+            ent.method_name() != Some("x-test:getkids")
+        });
+        assert!(table.len() < crate::DispatchTable::from_inventory().len());
+        let ctx: Arc<dyn crate::Context> = Arc::new(Ctx::from(table.clone()));
+        let discard = || Box::pin(futures::sink::drain().sink_err_into());
+
+        let res = table
+            .invoke_typed(
+                Arc::new(Swan),
+                Box::new(GetName),
+                Arc::clone(&ctx),
+                discard(),
+            )
+            .unwrap()
+            .await
+            .unwrap();
+        assert_eq!(res.v, "swan");
+
+        assert!(matches!(
+            table.invoke_typed(Arc::new(Swan), Box::new(GetKids), ctx, discard()),
+            Err(InvokeError::NoImpl)
+        ));
+    }
+
+    #[async_test]
+    async fn remove_by_name() {
+        let orig = crate::DispatchTable::from_inventory();
+        let mut table = orig.overlay();
+        let n_getkids = table
+            .entries()
+            .filter(|ent| ent.method_name() == Some("x-test:getkids"))
+            .count();
+        assert!(n_getkids >= 4);
+
+        assert_eq!(table.remove_by_name("x-test:getkids"), n_getkids);
+        assert_eq!(table.len(), orig.len() - n_getkids);
+        // Nothing is left to remove.
+        assert_eq!(table.remove_by_name("x-test:getkids"), 0);
+        assert_eq!(table.remove_by_name("x-test:no-such-method"), 0);
+
+        let ctx: Arc<dyn crate::Context> = Arc::new(Ctx::from(table.clone()));
+        let discard = || Box::pin(futures::sink::drain().sink_err_into());
+        let objects: Vec<Arc<dyn crate::Object>> = vec![
+            Arc::new(Swan),
+            Arc::new(Sheep),
+            Arc::new(Wombat),
+            Arc::new(Echidna),
+            Arc::new(GenericObj {
+                name: "nuncle",
+                kids: "niblings",
+            }),
+        ];
+        for obj in objects {
+            assert!(matches!(
+                table.invoke_local(
+                    Arc::clone(&obj),
+                    Box::new(GetKids),
+                    Arc::clone(&ctx),
+                    discard()
+                ),
+                Err(InvokeError::NoImpl)
+            ));
+            // The table we derived it from still has the method.
+            assert!(orig
+                .invoke_local(obj, Box::new(GetKids), Arc::clone(&ctx), discard())
+                .is_ok());
+        }
+
+        // Other methods are unaffected.
+        let res = table
+            .invoke_typed(Arc::new(Swan), Box::new(GetName), ctx, discard())
+            .unwrap()
+            .await
+            .unwrap();
+        assert_eq!(res.v, "swan");
+    }
+
     #[async_test]
     async fn try_invoke_special() {
         let table = crate::DispatchTable::from_inventory();
@@ -1166,6 +2047,32 @@ pub(crate) mod test {
         .unwrap();
     }
 
+    #[test]
+    fn send_across_threads() {
+        // An embedder may look up and invoke a method on one thread,
+        // and run the resulting future on another.
+        let table = DispatchTable::from_inventory();
+        let fut = std::thread::spawn(move || {
+            let ctx: Arc<dyn crate::Context> = Arc::new(Ctx::from(table));
+            crate::invoke_rpc_method(
+                ctx,
+                &crate::ObjectId::from("Odile"),
+                Arc::new(Swan),
+                Box::new(GetName),
+                Box::pin(futures::sink::drain().sink_err_into()),
+            )
+            .unwrap()
+        })
+        .join()
+        .unwrap();
+
+        let res = std::thread::spawn(move || futures::executor::block_on(fut))
+            .join()
+            .unwrap()
+            .unwrap();
+        assert_eq!(serde_json::to_string(&res).unwrap(), r#"{"v":"swan"}"#);
+    }
+
     #[test]
     fn invoke_poorly() {
         fn is_internal_invoke_err<T>(val: Result<T, InvokeError>) -> bool {
@@ -1229,6 +2136,64 @@ pub(crate) mod test {
         assert!(re.is_match(&debug_fmt));
     }
 
+    #[test]
+    fn invoker_generic_grouping() {
+        let ent_u32 = invoker_ent!(getname_generic::<u32, u32>);
+        let ent_str = invoker_ent!(getname_generic::<&'static str, &'static str>);
+        let ent_swan = invoker_ent!(getname_swan);
+
+        assert_eq!(ent_u32.invoker.is_generic_instantiation(), true);
+        assert_eq!(ent_str.invoker.is_generic_instantiation(), true);
+        assert_eq!(ent_swan.invoker.is_generic_instantiation(), false);
+
+        // Both instantiations share a grouping key...
+        let base = ent_u32.invoker.object_base_type_name();
+        assert_eq!(base, ent_str.invoker.object_base_type_name());
+        assert!(base.ends_with("::GenericObj"));
+        // ...even though their full object types differ.
+        assert_ne!(
+            ent_u32.invoker.object_and_method_type_names(),
+            ent_str.invoker.object_and_method_type_names()
+        );
+
+        // A non-generic object type is its own base.
+        assert_eq!(
+            ent_swan.invoker.object_base_type_name(),
+            std::any::type_name::<Swan>()
+        );
+    }
+
+    #[test]
+    fn invoker_supports_updates() {
+        let table = DispatchTable::from_inventory();
+
+        // getkids_wombat takes an UpdateSink.
+        let (_, ent) = table
+            .resolve_rpc_invoker(Arc::new(Wombat), &GetKids)
+            .unwrap();
+        assert_eq!(ent.supports_updates(), true);
+
+        // getkids_swan and getname_wombat do not.
+        let (_, ent) = table.resolve_rpc_invoker(Arc::new(Swan), &GetKids).unwrap();
+        assert_eq!(ent.supports_updates(), false);
+        let (_, ent) = table
+            .resolve_rpc_invoker(Arc::new(Wombat), &GetName)
+            .unwrap();
+        assert_eq!(ent.supports_updates(), false);
+
+        // The same holds for the special invokers.
+        assert_eq!(
+            invoker_ent!(getkids_wombat).invoker.supports_updates(),
+            true
+        );
+        assert_eq!(
+            invoker_ent!(@special specialonly_swan)
+                .invoker
+                .supports_updates(),
+            false
+        );
+    }
+
     #[test]
     fn redundant_invoker_ents() {
         let ent = invoker_ent!(getname_generic::<String, String>);
@@ -1239,6 +2204,134 @@ pub(crate) mod test {
         table.insert(ent);
     }
 
+    #[test]
+    fn overlays() {
+        let table = DispatchTable::from_inventory();
+        let base_len = table.base.len();
+
+        // Creating many overlays doesn't copy the base table.
+        let mut overlays: Vec<_> = (0..100).map(|_| table.overlay()).collect();
+        assert_eq!(Arc::strong_count(&table.base), 101);
+        assert!(overlays.iter().all(|o| Arc::ptr_eq(&o.base, &table.base)));
+        assert!(overlays.iter().all(|o| o.overlay.is_empty()));
+
+        // Inserting into an overlay only affects that overlay,
+        // and still doesn't copy the base table.
+        let obj = Arc::new(GenericObj {
+            name: 17_u64,
+            kids: 6_u64,
+        });
+        GenericObj::<u64, u64>::install_rpc_functions(&mut overlays[0]);
+        assert_eq!(overlays[0].overlay.len(), 2);
+        assert!(Arc::ptr_eq(&overlays[0].base, &table.base));
+        assert_eq!(table.base.len(), base_len);
+        assert!(overlays[0]
+            .resolve_rpc_invoker(obj.clone(), &GetName)
+            .is_ok());
+        assert!(matches!(
+            overlays[1].resolve_rpc_invoker(obj.clone(), &GetName),
+            Err(InvokeError::NoImpl)
+        ));
+        assert!(matches!(
+            table.resolve_rpc_invoker(obj.clone(), &GetName),
+            Err(InvokeError::NoImpl)
+        ));
+        // Entries from the base are still visible through the overlay.
+        assert!(overlays[0]
+            .resolve_rpc_invoker(Arc::new(Swan), &GetName)
+            .is_ok());
+
+        // Once nothing else shares it, the base is modified in place.
+        drop(overlays);
+        let mut table = table;
+        assert_eq!(Arc::strong_count(&table.base), 1);
+        GenericObj::<u64, u64>::install_rpc_functions(&mut table);
+        assert!(table.overlay.is_empty());
+        assert_eq!(table.base.len(), base_len + 2);
+    }
+
+    #[test]
+    fn overlay_overrides() {
+        let ent = invoker_ent!(getname_generic::<String, String>);
+        let ent2 = invoker_ent!(getname_generic::<String, String>);
+        let func_type = FuncType::of(&ent);
+
+        let mut table = DispatchTable::from_inventory();
+        table.insert(ent);
+
+        // An overlay can replace an entry it inherited...
+        let mut overlay = table.overlay();
+        overlay.insert(ent2);
+        assert!(overlay.get(&func_type).unwrap().same_decl(&ent2));
+        assert_eq!(overlay.len(), table.len());
+        // ...without affecting the table it was derived from.
+        assert!(table.get(&func_type).unwrap().same_decl(&ent));
+
+        // Reinstalling the same entry is still fine.
+        overlay.insert(ent2);
+        assert!(overlay.get(&func_type).unwrap().same_decl(&ent2));
+
+        // An overlay of an overlay can override the entries of both.
+        let mut overlay2 = overlay.overlay();
+        overlay2.insert(ent);
+        assert!(overlay2.get(&func_type).unwrap().same_decl(&ent));
+        assert!(overlay.get(&func_type).unwrap().same_decl(&ent2));
+
+        // Removing methods doesn't turn our own entries into inherited ones.
+        assert!(overlay.remove_by_name("x-test:getkids") > 0);
+        assert!(overlay.overlay.contains_key(&func_type));
+    }
+
+    #[test]
+    #[should_panic]
+    fn conflicting_invoker_ents_in_overlay() {
+        let ent = invoker_ent!(getname_generic::<String, String>);
+        let ent2 = invoker_ent!(getname_generic::<String, String>);
+        let mut overlay = DispatchTable::from_inventory().overlay();
+        overlay.insert(ent);
+        overlay.insert(ent2);
+    }
+
+    #[test]
+    fn summary() {
+        let table = DispatchTable::from_inventory();
+        assert!(!table.is_empty());
+        assert_eq!(
+            table.to_string(),
+            format!("{} method implementations", table.len())
+        );
+
+        let full = format!("{:#}", table);
+        assert!(full.starts_with(&table.to_string()));
+        assert!(full.contains(&format!(
+            "x-test:getname on {}",
+            std::any::type_name::<Swan>()
+        )));
+        assert_eq!(full.matches(" on ").count(), table.len());
+
+        // Entries in an overlay are counted along with those in the base,
+        // and reinstalling the same functions changes nothing.
+        let mut overlay = table.overlay();
+        GenericObj::<u64, u64>::install_rpc_functions(&mut overlay);
+        assert_eq!(overlay.len(), table.len() + 2);
+        GenericObj::<u64, u64>::install_rpc_functions(&mut overlay);
+        assert_eq!(overlay.len(), table.len() + 2);
+        assert_eq!(
+            overlay.to_string(),
+            format!("{} method implementations", overlay.len())
+        );
+    }
+
+    #[test]
+    fn method_name() {
+        let method: Box<dyn crate::DynMethod> = Box::new(GetKids);
+        assert_eq!(method.method_name(), Some("x-test:getkids"));
+
+        // Methods without a name can't be invoked over RPC, and have no name to report.
+        let method: Box<dyn crate::DynMethod> = Box::new(SpecialOnly {});
+        assert_eq!(method.method_name(), None);
+    }
+
     #[test]
     #[should_panic]
     fn conflicting_invoker_ents() {
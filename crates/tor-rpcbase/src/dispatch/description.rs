@@ -54,7 +54,7 @@ impl super::DispatchTable {
     /// Currently, the resulting object is good for nothing but serialization.
     pub fn dispatch_information(&self) -> RpcDispatchInformation {
         let mut methods = BTreeMap::new();
-        for invoker_ent in self.map.values() {
+        for invoker_ent in self.entries() {
             let Some(method_info) = method_info_by_typeid(invoker_ent.invoker.method_type()) else {
                 continue; // This isn't an RpcMethod.
             };
@@ -81,6 +81,50 @@ impl super::DispatchTable {
             delegations,
         }
     }
+
+    /// Return a JSON description of every RPC method implementation in this table.
+    ///
+    /// The result is an array with one entry for each (method, object type) pair,
+    /// of the form `{ "method": ..., "object": ..., "object_base": ..., "updates": ... }`,
+    /// where `method` is the RPC method name,
+    /// `object` is the name of the Rust type of the object,
+    /// `object_base` is that name without any generic parameters
+    /// (so that every instantiation of a generic handler shares it),
+    /// and `updates` is true if the implementation can send incremental updates.
+    /// Entries are sorted by method name, then by object type.
+    ///
+    /// Special methods (those which cannot be invoked over RPC) are not included.
+    ///
+    /// See [`RpcDispatchInformation`] for caveats about type names.
+    pub fn to_schema(&self) -> serde_json::Value {
+        let mut entries: Vec<_> = self
+            .entries()
+            .filter(|invoker_ent| invoker_ent.rpc_invoker.is_some())
+            .filter_map(|invoker_ent| {
+                let method_info = method_info_by_typeid(invoker_ent.invoker.method_type())?;
+                let (object_type_name, _) = invoker_ent.invoker.object_and_method_type_names();
+                Some((
+                    method_info.method_name,
+                    object_type_name,
+                    invoker_ent.invoker.object_base_type_name(),
+                    invoker_ent.invoker.supports_updates(),
+                ))
+            })
+            .collect();
+        entries.sort();
+
+        entries
+            .into_iter()
+            .map(|(method, object, object_base, updates)| {
+                serde_json::json!({
+                    "method": method,
+                    "object": object,
+                    "object_base": object_base,
+                    "updates": updates,
+                })
+            })
+            .collect()
+    }
 }
 
 impl MethodDescription {
@@ -140,3 +184,54 @@ macro_rules! register_delegation_note {
         }}
     }
 }
+
+#[cfg(test)]
+mod test {
+    // @@ begin test lint list maintained by maint/add_warning @@
+    #![allow(clippy::bool_assert_comparison)]
+    #![allow(clippy::clone_on_copy)]
+    #![allow(clippy::dbg_macro)]
+    #![allow(clippy::mixed_attributes_style)]
+    #![allow(clippy::print_stderr)]
+    #![allow(clippy::print_stdout)]
+    #![allow(clippy::single_char_pattern)]
+    #![allow(clippy::unwrap_used)]
+    #![allow(clippy::unchecked_duration_subtraction)]
+    #![allow(clippy::useless_vec)]
+    #![allow(clippy::needless_pass_by_value)]
+    //! <!-- @@ end test lint list maintained by maint/add_warning @@ -->
+
+    use crate::dispatch::test::{Swan, Wombat};
+    use crate::DispatchTable;
+
+    #[test]
+    fn schema() {
+        let schema = DispatchTable::from_inventory().to_schema();
+        let entries = schema.as_array().unwrap();
+
+        let find = |method: &str, object: &str| {
+            entries
+                .iter()
+                .find(|e| e["method"] == method && e["object"] == object)
+                .unwrap_or_else(|| panic!("No entry for {method} on {object}"))
+        };
+        let swan = std::any::type_name::<Swan>();
+        let wombat = std::any::type_name::<Wombat>();
+
+        assert_eq!(find("x-test:getname", swan)["updates"], false);
+        assert_eq!(find("x-test:getkids", swan)["updates"], false);
+        // Only the wombat's implementation of getkids takes an UpdateSink.
+        assert_eq!(find("x-test:getkids", wombat)["updates"], true);
+        assert_eq!(find("x-test:getkids", wombat)["object_base"], wombat);
+
+        // Every instantiation of the generic getname handler has the same object_base.
+        let generic_bases: Vec<_> = entries
+            .iter()
+            .filter(|e| e["method"] == "x-test:getname")
+            .filter(|e| e["object"].as_str().unwrap().contains("GenericObj<"))
+            .map(|e| e["object_base"].as_str().unwrap())
+            .collect();
+        assert!(generic_bases.len() >= 2);
+        assert!(generic_bases.iter().all(|b| b.ends_with("::GenericObj")));
+    }
+}
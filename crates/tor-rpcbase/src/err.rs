@@ -144,6 +144,10 @@ pub enum RpcErrorKind {
     RequestCancelled = 4,
     /// This request listed a required feature that doesn't exist.
     FeatureNotPresent = 5,
+    /// This request was refused because too many other requests were in progress.
+    ///
+    /// The client may send it again once some of its other requests have finished.
+    TooManyRequests = 6,
 }
 
 /// Helper: Return an error code (for backward compat with json-rpc) for an
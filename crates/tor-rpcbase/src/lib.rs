@@ -47,16 +47,18 @@ pub mod dispatch;
 mod err;
 mod method;
 mod obj;
+pub mod stream;
 
 use std::{collections::HashSet, convert::Infallible, sync::Arc};
 
-pub use dispatch::{DispatchTable, InvokeError, UpdateSink};
+pub use dispatch::{DispatchTable, InvokeError, UpdateSink, UpdateSinkExt};
 pub use err::{RpcError, RpcErrorKind};
 pub use method::{
-    check_method_names, is_method_name, iter_method_names, DeserMethod, DynMethod, Method,
-    NoUpdates, RpcMethod,
+    check_method_names, find_duplicate_method_names, is_method_name, iter_method_names,
+    DeserMethod, DynMethod, Method, NoUpdates, RpcMethod,
 };
 pub use obj::{Object, ObjectArcExt, ObjectId};
+pub use stream::{yields_stream, StreamHandle};
 
 #[cfg(feature = "describe-methods")]
 #[cfg_attr(docsrs, doc(cfg(feature = "describe-methods")))]
@@ -132,26 +134,42 @@ pub trait Context: Send + Sync {
 
 /// An error caused while trying to send an update to a method.
 ///
-/// These errors should be impossible in our current implementation, since they
-/// can only happen if the `mpsc::Receiver` is closed—which can only happen
+/// In our current implementation, these errors can only happen if the
+/// `mpsc::Receiver` is closed—which can only happen
 /// when the session loop drops it, which only happens when the session loop has
-/// stopped polling its `FuturesUnordered` full of RPC request futures. Thus, any
-/// `send` that would encounter this error should be in a future that is never
-/// polled under circumstances when the error could happen.
+/// stopped polling its `FuturesUnordered` full of RPC request futures.
+/// Usually, this means that any `send` that would encounter this error
+/// is in a future that is never polled again.
 ///
-/// Still, programming errors are real, so we are handling this rather than
-/// declaring it a panic or something.
+/// Still, a method that does expensive work between updates
+/// (for example, in a separate task)
+/// should stop when it gets [`SendUpdateError::PeerGone`]:
+/// nobody will ever see its output.
 #[derive(Debug, Clone, thiserror::Error)]
 #[non_exhaustive]
 pub enum SendUpdateError {
     /// The request was cancelled, or the connection was closed.
     #[error("Unable to send on MPSC connection")]
     ConnectionClosed,
+    /// The peer that made the request has gone away,
+    /// so no further updates can be delivered.
+    #[error("RPC peer has gone away")]
+    PeerGone,
+    /// There was no room for the update, so it could not be sent right now.
+    ///
+    /// This can only happen when trying to send without waiting for room:
+    /// the update may be sent again later.
+    #[error("RPC update channel is full")]
+    Busy,
 }
 
 impl tor_error::HasKind for SendUpdateError {
     fn kind(&self) -> tor_error::ErrorKind {
-        tor_error::ErrorKind::Internal
+        use tor_error::ErrorKind as EK;
+        match self {
+            SendUpdateError::ConnectionClosed | SendUpdateError::PeerGone => EK::Internal,
+            SendUpdateError::Busy => EK::TransientFailure,
+        }
     }
 }
 
@@ -161,8 +179,14 @@ impl From<Infallible> for SendUpdateError {
     }
 }
 impl From<futures::channel::mpsc::SendError> for SendUpdateError {
-    fn from(_: futures::channel::mpsc::SendError) -> Self {
-        SendUpdateError::ConnectionClosed
+    fn from(e: futures::channel::mpsc::SendError) -> Self {
+        if e.is_disconnected() {
+            SendUpdateError::PeerGone
+        } else if e.is_full() {
+            SendUpdateError::Busy
+        } else {
+            SendUpdateError::ConnectionClosed
+        }
     }
 }
 
@@ -212,6 +236,36 @@ pub fn invoke_rpc_method(
     invocable.invoke(obj, method, ctx, sink)
 }
 
+/// Try to find an appropriate function for calling a given RPC method
+/// that [yields a stream](yields_stream) on a given RPC-visible object.
+///
+/// On success, return a Future that resolves to the [`StreamHandle`]
+/// that the method produced, to be delivered to the transport layer.
+/// Any updates that the method sends before then are sent to `sink`,
+/// as with [`invoke_rpc_method`].
+///
+/// Returns an error if `method` does not yield a stream.
+///
+/// Like `invoke_rpc_method`, this drops its lock
+/// on the dispatch table before invoking the method.
+///
+/// Unlike `invoke_rpc_method`, this takes no object ID:
+/// methods that [bypass dispatch](DynMethod::invoke_without_dispatch) never yield streams.
+pub fn invoke_stream_method(
+    ctx: Arc<dyn Context>,
+    obj: Arc<dyn Object>,
+    method: Box<dyn DynMethod>,
+    sink: dispatch::BoxedUpdateSink,
+) -> Result<stream::StreamResultFuture, InvokeError> {
+    let (obj, invocable) = ctx
+        .dispatch_table()
+        .read()
+        .expect("poisoned lock")
+        .resolve_rpc_invoker(obj, method.as_ref())?;
+
+    invocable.invoke_stream(obj, method, ctx, sink)
+}
+
 /// Invoke the given `method` on `obj` within `ctx`, and return its
 /// actual result type.
 ///
@@ -57,6 +57,23 @@ pub trait DynMethod: std::fmt::Debug + Send + Downcast {
     fn is_cancellable(&self) -> bool {
         true
     }
+
+    /// Return the name of this method, as used on the wire.
+    ///
+    /// This lets code that only has a `dyn DynMethod`
+    /// (for example, to log a request before dispatching it)
+    /// find out which method it is.
+    ///
+    /// Returns `None` if the method has no name
+    /// (as with `#[deftly(rpc(no_method_name))]`),
+    /// since such a method can't be invoked over RPC.
+    ///
+    /// [`derive_deftly(DynMethod)`](derive_deftly_template_DynMethod) implements this
+    /// to return the name given with `#[deftly(rpc(method_name = ...))]`.
+    /// The default implementation looks up the name registered for this type.
+    fn method_name(&self) -> Option<&'static str> {
+        method_info_by_typeid(any::TypeId::of::<Self>()).map(|mi| mi.method_name)
+    }
 }
 downcast_rs::impl_downcast!(DynMethod);
 
@@ -139,6 +156,8 @@ pub struct MethodInfo_ {
     pub typeid: fn() -> any::TypeId,
     /// A function returning the name for this method's output type.
     pub output_name: fn() -> &'static str,
+    /// A function returning the TypeId for this method's output type.
+    pub output_typeid: fn() -> any::TypeId,
     /// A function returning the name for this method's update type.
     pub update_name: fn() -> &'static str,
 }
@@ -178,6 +197,11 @@ define_derive_deftly! {
                         false
                     }
                 }}
+                ${if tmeta(rpc(method_name)) {
+                    fn method_name(&self) -> Option<&'static str> {
+                        Some(${tmeta(rpc(method_name)) as str})
+                    }
+                }}
             }
         } else if tmeta(rpc(no_method_name)) {
             ${error "no_method_name is incompatible with bypass_method_dispatch."}
@@ -199,6 +223,7 @@ define_derive_deftly! {
                     method_name : ${tmeta(rpc(method_name)) as str},
                     typeid : std::any::TypeId::of::<$ttype>,
                     output_name: std::any::type_name::<<$ttype as $crate::RpcMethod>::Output>,
+                    output_typeid: std::any::TypeId::of::<<$ttype as $crate::RpcMethod>::Output>,
                     update_name: std::any::type_name::<<$ttype as $crate::RpcMethod>::Update>,
                 }
             }
@@ -238,6 +263,28 @@ pub(crate) fn method_info_by_typeid(typeid: any::TypeId) -> Option<&'static Meth
     METHOD_INFO_BY_TYPEID.get(&typeid).copied()
 }
 
+/// Return every method name that has been registered for more than one method type.
+///
+/// Two distinct method types with the same name would make the RPC protocol ambiguous:
+/// we could not tell which of them a request was meant to invoke.
+///
+/// The returned names are sorted, and each appears only once.
+pub fn find_duplicate_method_names() -> Vec<&'static str> {
+    let mut types_by_name: HashMap<&'static str, HashSet<any::TypeId>> = HashMap::new();
+    for mi in inventory::iter::<MethodInfo_>() {
+        types_by_name
+            .entry(mi.method_name)
+            .or_default()
+            .insert((mi.typeid)());
+    }
+    let mut duplicates: Vec<_> = types_by_name
+        .into_iter()
+        .filter_map(|(name, types)| (types.len() > 1).then_some(name))
+        .collect();
+    duplicates.sort_unstable();
+    duplicates
+}
+
 /// Check whether we have any method names that do not conform to our conventions.
 ///
 /// Violations of these conventions won't stop the RPC system from working, but they may result in
@@ -261,3 +308,47 @@ pub fn check_method_names<'a>(
         })
         .collect()
 }
+
+#[cfg(test)]
+mod test {
+    // @@ begin test lint list maintained by maint/add_warning @@
+    #![allow(clippy::bool_assert_comparison)]
+    #![allow(clippy::clone_on_copy)]
+    #![allow(clippy::dbg_macro)]
+    #![allow(clippy::mixed_attributes_style)]
+    #![allow(clippy::print_stderr)]
+    #![allow(clippy::print_stdout)]
+    #![allow(clippy::single_char_pattern)]
+    #![allow(clippy::unwrap_used)]
+    #![allow(clippy::unchecked_duration_subtraction)]
+    #![allow(clippy::useless_vec)]
+    #![allow(clippy::needless_pass_by_value)]
+    //! <!-- @@ end test lint list maintained by maint/add_warning @@ -->
+
+    use super::*;
+    use derive_deftly::Deftly;
+
+    #[derive(Debug, serde::Deserialize, Deftly)]
+    #[derive_deftly(DynMethod)]
+    #[deftly(rpc(method_name = "x-test:duplicated"))]
+    struct Original;
+    impl RpcMethod for Original {
+        type Output = crate::Nil;
+        type Update = crate::NoUpdates;
+    }
+
+    #[derive(Debug, serde::Deserialize, Deftly)]
+    #[derive_deftly(DynMethod)]
+    #[deftly(rpc(method_name = "x-test:duplicated"))]
+    struct Impostor;
+    impl RpcMethod for Impostor {
+        type Output = crate::Nil;
+        type Update = crate::NoUpdates;
+    }
+
+    #[test]
+    fn duplicate_names() {
+        assert!(is_method_name("x-test:duplicated"));
+        assert_eq!(find_duplicate_method_names(), vec!["x-test:duplicated"]);
+    }
+}
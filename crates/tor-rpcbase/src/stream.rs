@@ -0,0 +1,188 @@
+//! Support for RPC methods that yield a byte stream.
+//!
+//! Most RPC methods return a value that is serialized and sent to the client
+//! as the final response to its request.
+//! Some methods, however, exist to open a data stream
+//! (for example, a connection through Tor),
+//! and their real result is that stream itself:
+//! the transport layer should stop treating the connection (or some part of it)
+//! as a sequence of RPC messages,
+//! and start relaying bytes to and from the stream instead.
+//!
+//! Such a method declares [`StreamHandle`] as its [`RpcMethod::Output`](crate::RpcMethod::Output).
+//! A transport that knows how to relay data streams
+//! checks whether a method [yields a stream](yields_stream) before running it,
+//! and if so, runs it with [`invoke_stream_method`](crate::invoke_stream_method),
+//! which returns the `StreamHandle` itself rather than a serializable value.
+//!
+//! ## Streams and updates
+//!
+//! A stream is not a kind of [update](crate::Method::Update).
+//! Updates are serializable values, sent to the client as RPC messages
+//! _before_ the final response to a request;
+//! a method yielding a stream may send updates too
+//! (for example, to report progress while it is connecting).
+//! The stream, on the other hand, _is_ the final response:
+//! once it has been delivered, the request is finished,
+//! and any further data is exchanged over the stream, outside of the RPC protocol.
+
+use std::any::TypeId;
+use std::fmt;
+use std::pin::Pin;
+
+use futures::future::BoxFuture;
+use futures::{AsyncRead, AsyncWrite};
+
+use crate::method::method_info_by_typeid;
+use crate::{DynMethod, RpcError};
+
+/// A byte stream that can be carried by a [`StreamHandle`].
+///
+/// This trait is implemented for every type with the required bounds.
+pub trait DataStream: AsyncRead + AsyncWrite + Send + Unpin + 'static {}
+
+impl<T> DataStream for T where T: AsyncRead + AsyncWrite + Send + Unpin + 'static {}
+
+/// A byte stream negotiated by an RPC method,
+/// to be delivered to the transport layer.
+///
+/// See the [module documentation](self) for more information.
+///
+/// Although it implements `Serialize` (as every `RpcMethod::Output` must),
+/// a `StreamHandle` cannot actually be serialized:
+/// trying to do so always fails.
+/// Methods that yield a `StreamHandle` must therefore be run
+/// with [`invoke_stream_method`](crate::invoke_stream_method).
+pub struct StreamHandle {
+    /// The stream itself.
+    stream: Pin<Box<dyn DataStream>>,
+}
+
+impl StreamHandle {
+    /// Construct a new `StreamHandle` to deliver `stream`.
+    pub fn new<S: DataStream>(stream: S) -> Self {
+        Self {
+            stream: Box::pin(stream),
+        }
+    }
+
+    /// Consume this handle, and return the stream it carries.
+    pub fn into_stream(self) -> Pin<Box<dyn DataStream>> {
+        self.stream
+    }
+}
+
+impl fmt::Debug for StreamHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamHandle").finish_non_exhaustive()
+    }
+}
+
+impl serde::Serialize for StreamHandle {
+    fn serialize<S: serde::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom(
+            "Tried to serialize a StreamHandle; this method can only be invoked as a stream method",
+        ))
+    }
+}
+
+/// A boxed future holding the result of a method that yields a stream.
+pub type StreamResultFuture = BoxFuture<'static, Result<StreamHandle, RpcError>>;
+
+/// Return true if `method` yields a [`StreamHandle`],
+/// and must therefore be run with [`invoke_stream_method`](crate::invoke_stream_method).
+///
+/// Returns false for methods that can't be invoked over RPC.
+pub fn yields_stream(method: &dyn DynMethod) -> bool {
+    // As in `RpcInvocable::invoke_stream`, we compare TypeIds, not type names.
+    method_info_by_typeid(method.type_id())
+        .is_some_and(|info| (info.output_typeid)() == TypeId::of::<StreamHandle>())
+}
+
+#[cfg(test)]
+mod test {
+    // @@ begin test lint list maintained by maint/add_warning @@
+    #![allow(clippy::bool_assert_comparison)]
+    #![allow(clippy::clone_on_copy)]
+    #![allow(clippy::dbg_macro)]
+    #![allow(clippy::mixed_attributes_style)]
+    #![allow(clippy::print_stderr)]
+    #![allow(clippy::print_stdout)]
+    #![allow(clippy::single_char_pattern)]
+    #![allow(clippy::unwrap_used)]
+    #![allow(clippy::unchecked_duration_subtraction)]
+    #![allow(clippy::useless_vec)]
+    #![allow(clippy::needless_pass_by_value)]
+    //! <!-- @@ end test lint list maintained by maint/add_warning @@ -->
+
+    use super::*;
+    use crate::dispatch::test::{Ctx, GetName, Swan};
+    use crate::{templates::*, DispatchTable, InvokeError, NoUpdates, ObjectId, RpcMethod};
+    use derive_deftly::Deftly;
+    use futures::io::Cursor;
+    use futures::{AsyncReadExt as _, SinkExt as _};
+    use futures_await_test::async_test;
+    use std::sync::Arc;
+
+    /// A method that opens a stream of water.
+    #[derive(Debug, serde::Deserialize, Deftly)]
+    #[derive_deftly(DynMethod)]
+    #[deftly(rpc(method_name = "x-test:open_tap"))]
+    struct OpenTap;
+
+    impl RpcMethod for OpenTap {
+        type Output = StreamHandle;
+        type Update = NoUpdates;
+    }
+
+    async fn open_tap_swan(
+        _obj: Arc<Swan>,
+        _method: Box<OpenTap>,
+        _ctx: Arc<dyn crate::Context>,
+    ) -> Result<StreamHandle, crate::RpcError> {
+        Ok(StreamHandle::new(Cursor::new(b"splash".to_vec())))
+    }
+
+    crate::static_rpc_invoke_fn! {
+        open_tap_swan;
+    }
+
+    #[async_test]
+    async fn stream_method() {
+        assert!(yields_stream(&OpenTap));
+        assert!(!yields_stream(&GetName));
+
+        let ctx = Arc::new(Ctx::from(DispatchTable::from_inventory()));
+        let discard = || Box::pin(futures::sink::drain().sink_err_into());
+        let handle =
+            crate::invoke_stream_method(ctx.clone(), Arc::new(Swan), Box::new(OpenTap), discard())
+                .unwrap()
+                .await
+                .unwrap();
+        let mut data = String::new();
+        handle
+            .into_stream()
+            .read_to_string(&mut data)
+            .await
+            .unwrap();
+        assert_eq!(data, "splash");
+
+        // Regular methods can't be invoked as stream methods.
+        let r =
+            crate::invoke_stream_method(ctx.clone(), Arc::new(Swan), Box::new(GetName), discard());
+        assert!(matches!(r, Err(InvokeError::Bug(_))));
+
+        // And the handle can't be serialized as a regular result.
+        let r = crate::invoke_rpc_method(
+            ctx,
+            &ObjectId::from("Odile"),
+            Arc::new(Swan),
+            Box::new(OpenTap),
+            discard(),
+        )
+        .unwrap()
+        .await
+        .unwrap();
+        assert!(serde_json::to_string(&r).is_err());
+    }
+}
@@ -19,15 +19,123 @@ pub use general::{AddrParseError, SocketAddr};
 trait ReadAndWrite: AsyncRead + AsyncWrite + StreamOps + Send + Sync {}
 impl<T> ReadAndWrite for T where T: AsyncRead + AsyncWrite + StreamOps + Send + Sync {}
 
+/// The kind of transport underlying a [`Stream`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, derive_more::Display)]
+#[non_exhaustive]
+pub enum TransportKind {
+    /// A TCP connection.
+    #[display("tcp")]
+    Tcp,
+    /// An AF_UNIX stream connection.
+    ///
+    /// Such a connection can only come from a process on the same host.
+    #[display("unix")]
+    Unix,
+}
+
 /// A stream returned by a `NetStreamProvider<GeneralizedAddr>`
-pub struct Stream(Pin<Box<dyn ReadAndWrite>>);
+pub struct Stream {
+    /// The underlying type-erased stream.
+    inner: Pin<Box<dyn ReadAndWrite>>,
+    /// The address of the peer, if it has a meaningful one.
+    peer_addr: Option<general::SocketAddr>,
+    /// The kind of transport that `inner` uses.
+    transport_kind: TransportKind,
+}
+
+impl Stream {
+    /// Wrap `stream`, which is connected to `peer_addr` over `transport_kind`, as a `Stream`.
+    fn new<S: ReadAndWrite + 'static>(
+        stream: S,
+        peer_addr: general::SocketAddr,
+        transport_kind: TransportKind,
+    ) -> Self {
+        let peer_addr = match &peer_addr {
+            general::SocketAddr::Unix(unix_addr) if unix_addr.is_unnamed() => None,
+            _ => Some(peer_addr),
+        };
+
+        Stream {
+            inner: Box::pin(stream),
+            peer_addr,
+            transport_kind,
+        }
+    }
+
+    /// Return the kind of transport underlying this stream.
+    ///
+    /// This is known even when the [`peer_addr`](Stream::peer_addr) is not.
+    /// It can be used to decide how much to trust the peer:
+    /// for example, only a local process can connect over [`TransportKind::Unix`].
+    pub fn transport_kind(&self) -> TransportKind {
+        self.transport_kind
+    }
+
+    /// Return the address of the peer at the other end of this stream.
+    ///
+    /// For outbound streams, this is the address we connected to;
+    /// for inbound streams, it is the address reported when the connection was accepted.
+    ///
+    /// Returns `None` if the peer has no meaningful address.
+    /// This is usually the case for the peers of inbound AF_UNIX connections,
+    /// which are typically unnamed sockets.
+    pub fn peer_addr(&self) -> Option<&general::SocketAddr> {
+        self.peer_addr.as_ref()
+    }
+
+    /// Split this stream into an owned read half and an owned write half.
+    ///
+    /// After splitting, reads and writes proceed independently:
+    /// each half can be moved to (and used from) a separate task.
+    /// The stream is closed once both halves are dropped,
+    /// or when [`poll_close`](AsyncWrite::poll_close) is called on the write half.
+    pub fn split(self) -> (ReadHalf, WriteHalf) {
+        let (r, w) = futures::AsyncReadExt::split(self);
+        (ReadHalf(r), WriteHalf(w))
+    }
+}
+
+/// The read half of a [`Stream`], as returned by [`Stream::split`].
+pub struct ReadHalf(futures::io::ReadHalf<Stream>);
+
+/// The write half of a [`Stream`], as returned by [`Stream::split`].
+pub struct WriteHalf(futures::io::WriteHalf<Stream>);
+
+impl AsyncRead for ReadHalf {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<IoResult<usize>> {
+        Pin::new(&mut self.0).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for WriteHalf {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<IoResult<usize>> {
+        Pin::new(&mut self.0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        Pin::new(&mut self.0).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        Pin::new(&mut self.0).poll_close(cx)
+    }
+}
+
 impl AsyncRead for Stream {
     fn poll_read(
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
         buf: &mut [u8],
     ) -> Poll<IoResult<usize>> {
-        self.0.as_mut().poll_read(cx, buf)
+        self.inner.as_mut().poll_read(cx, buf)
     }
 }
 impl AsyncWrite for Stream {
@@ -36,25 +144,25 @@ impl AsyncWrite for Stream {
         cx: &mut Context<'_>,
         buf: &[u8],
     ) -> Poll<IoResult<usize>> {
-        self.0.as_mut().poll_write(cx, buf)
+        self.inner.as_mut().poll_write(cx, buf)
     }
 
     fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
-        self.0.as_mut().poll_flush(cx)
+        self.inner.as_mut().poll_flush(cx)
     }
 
     fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
-        self.0.as_mut().poll_close(cx)
+        self.inner.as_mut().poll_close(cx)
     }
 }
 
 impl StreamOps for Stream {
     fn set_tcp_notsent_lowat(&self, notsent_lowat: u32) -> IoResult<()> {
-        self.0.set_tcp_notsent_lowat(notsent_lowat)
+        self.inner.set_tcp_notsent_lowat(notsent_lowat)
     }
 
     fn new_handle(&self) -> Box<dyn StreamOps + Send + Unpin> {
-        self.0.new_handle()
+        self.inner.new_handle()
     }
 }
 
@@ -80,6 +188,27 @@ pub struct Listener {
     local_addr: general::SocketAddr,
 }
 
+impl Listener {
+    /// Wait for the next incoming connection on this listener.
+    ///
+    /// Returns the stream for that connection, along with the address of its peer.
+    ///
+    /// This is an alternative to [`incoming`](NetStreamListener::incoming)
+    /// for code that accepts connections one at a time in an explicit loop:
+    /// it does not consume the listener.
+    ///
+    /// If the listener has stopped accepting connections,
+    /// returns an error of kind [`UnexpectedEof`](IoErrorKind::UnexpectedEof).
+    pub async fn accept(&mut self) -> IoResult<(Stream, general::SocketAddr)> {
+        self.streams.next().await.unwrap_or_else(|| {
+            Err(IoError::new(
+                IoErrorKind::UnexpectedEof,
+                "listener is no longer accepting connections",
+            ))
+        })
+    }
+}
+
 impl NetStreamListener<general::SocketAddr> for Listener {
     type Stream = Stream;
     type Incoming = IncomingStreams;
@@ -95,15 +224,24 @@ impl NetStreamListener<general::SocketAddr> for Listener {
 
 /// Use `provider` to launch a `NetStreamListener` at `address`, and wrap that listener
 /// as a `Listener`.
-async fn abstract_listener_on<ADDR, P>(provider: &P, address: &ADDR) -> IoResult<Listener>
+///
+/// Every stream that the listener accepts will report `kind` as its [`TransportKind`].
+async fn abstract_listener_on<ADDR, P>(
+    provider: &P,
+    address: &ADDR,
+    kind: TransportKind,
+) -> IoResult<Listener>
 where
     P: NetStreamProvider<ADDR>,
     general::SocketAddr: From<ADDR>,
 {
     let lis = provider.listen(address).await?;
     let local_addr = general::SocketAddr::from(lis.local_addr()?);
-    let streams = lis.incoming().map(|result| {
-        result.map(|(socket, addr)| (Stream(Box::pin(socket)), general::SocketAddr::from(addr)))
+    let streams = lis.incoming().map(move |result| {
+        result.map(|(socket, addr)| {
+            let addr = general::SocketAddr::from(addr);
+            (Stream::new(socket, addr.clone(), kind), addr)
+        })
     });
     let streams = IncomingStreams(Box::pin(streams));
     Ok(Listener {
@@ -123,8 +261,16 @@ where
     async fn connect(&self, addr: &general::SocketAddr) -> IoResult<Stream> {
         use general::SocketAddr as G;
         match addr {
-            G::Inet(a) => Ok(Stream(Box::pin(self.connect(a).await?))),
-            G::Unix(a) => Ok(Stream(Box::pin(self.connect(a).await?))),
+            G::Inet(a) => Ok(Stream::new(
+                self.connect(a).await?,
+                G::Inet(*a),
+                TransportKind::Tcp,
+            )),
+            G::Unix(a) => Ok(Stream::new(
+                self.connect(a).await?,
+                G::Unix(a.clone()),
+                TransportKind::Unix,
+            )),
             other => Err(IoError::new(
                 IoErrorKind::InvalidInput,
                 UnsupportedAddress(other.clone()),
@@ -134,8 +280,8 @@ where
     async fn listen(&self, addr: &general::SocketAddr) -> IoResult<Listener> {
         use general::SocketAddr as G;
         match addr {
-            G::Inet(a) => abstract_listener_on(self, a).await,
-            G::Unix(a) => abstract_listener_on(self, a).await,
+            G::Inet(a) => abstract_listener_on(self, a, TransportKind::Tcp).await,
+            G::Unix(a) => abstract_listener_on(self, a, TransportKind::Unix).await,
             other => Err(IoError::new(
                 IoErrorKind::InvalidInput,
                 UnsupportedAddress(other.clone()),
@@ -144,6 +290,52 @@ where
     }
 }
 
+/// Use `provider` to listen on a fresh address derived from `base`,
+/// and return the listener along with the concrete address it is bound to.
+///
+/// For an inet address, the port in `base` is ignored,
+/// and the operating system picks an unused one.
+///
+/// For a unix address, `base` must be a pathname naming an existing directory;
+/// we bind to a newly generated socket path within that directory.
+/// (We don't remove that socket file when the listener is dropped.)
+pub async fn listen_ephemeral<P>(
+    provider: &P,
+    base: &general::SocketAddr,
+) -> IoResult<(Listener, general::SocketAddr)>
+where
+    P: NetStreamProvider<net::SocketAddr> + NetStreamProvider<unix::SocketAddr>,
+{
+    use general::SocketAddr as G;
+    let addr = match base {
+        G::Inet(a) => {
+            let mut a = *a;
+            a.set_port(0);
+            G::Inet(a)
+        }
+        G::Unix(a) => {
+            /// Counter used to make socket names unique within this process.
+            static NEXT_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+            let dir = a.as_pathname().ok_or_else(|| {
+                IoError::new(IoErrorKind::InvalidInput, UnsupportedAddress(base.clone()))
+            })?;
+            let id = NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            let name = format!("ephemeral-{}-{}.sock", std::process::id(), id);
+            G::Unix(unix::SocketAddr::from_pathname(dir.join(name))?)
+        }
+        other => {
+            return Err(IoError::new(
+                IoErrorKind::InvalidInput,
+                UnsupportedAddress(other.clone()),
+            ))
+        }
+    };
+    let listener: Listener = provider.listen(&addr).await?;
+    let local_addr = listener.local_addr()?;
+    Ok((listener, local_addr))
+}
+
 /// Tried to use a [`general::SocketAddr`] that `tor-rtcompat` didn't understand.
 #[derive(Clone, Debug, thiserror::Error)]
 #[error("Socket address {0:?} is not supported by tor-rtcompat")]
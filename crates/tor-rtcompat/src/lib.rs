@@ -473,6 +473,205 @@ mod test {
         })
     }
 
+    // Connect to ourself using a general::SocketAddr, and check that the
+    // resulting stream remembers its peer.
+    //
+    // NOTE: requires Ipv4 localhost.
+    fn self_connect_general_peer_addr<R: ToplevelRuntime>(runtime: &R) -> IoResult<()> {
+        use crate::general;
+
+        let localhost = SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0);
+        let rt1 = runtime.clone();
+
+        let listener = runtime
+            .block_on(rt1.listen(&general::SocketAddr::from(SocketAddr::from(localhost))))?;
+        let addr = listener.local_addr()?;
+
+        runtime.block_on(async {
+            let task1 = async {
+                let (con, addr) = listener.incoming().next().await.expect("closed?")?;
+                IoResult::Ok((con.peer_addr().cloned(), addr))
+            };
+            let task2 = async {
+                let con = rt1.connect(&addr).await?;
+                IoResult::Ok(con.peer_addr().cloned())
+            };
+
+            let (accepted, connected) = futures::join!(task1, task2);
+            let (accepted_peer, accepted_addr) = accepted?;
+
+            assert_eq!(connected?, Some(addr));
+            assert_eq!(accepted_peer, Some(accepted_addr));
+
+            Ok(())
+        })
+    }
+
+    // Connect to ourself using a general::SocketAddr, split both ends of
+    // the connection, and send data through the halves from separate tasks.
+    //
+    // NOTE: requires Ipv4 localhost.
+    fn self_connect_general_split<R: ToplevelRuntime>(runtime: &R) -> IoResult<()> {
+        use crate::general;
+        use futures::task::SpawnExt as _;
+
+        let localhost = SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0);
+        let rt1 = runtime.clone();
+
+        let listener = runtime
+            .block_on(rt1.listen(&general::SocketAddr::from(SocketAddr::from(localhost))))?;
+        let addr = listener.local_addr()?;
+
+        runtime.block_on(async {
+            // Echo back everything we receive, reading and writing from separate tasks.
+            let server = async {
+                let (con, _addr) = listener.incoming().next().await.expect("closed?")?;
+                let (mut r, mut w) = con.split();
+                let (tx, mut rx) = futures::channel::mpsc::unbounded::<Vec<u8>>();
+                let reader = rt1
+                    .spawn_with_handle(async move {
+                        let mut buf = vec![0_u8; 11];
+                        r.read_exact(&mut buf[..]).await?;
+                        tx.unbounded_send(buf).expect("writer task gone");
+                        IoResult::Ok(())
+                    })
+                    .expect("spawn failed");
+                let writer = rt1
+                    .spawn_with_handle(async move {
+                        let buf = rx.next().await.expect("reader task gone");
+                        w.write_all(&buf).await?;
+                        w.flush().await?;
+                        IoResult::Ok(())
+                    })
+                    .expect("spawn failed");
+                reader.await?;
+                writer.await
+            };
+            let client = async {
+                let con = rt1.connect(&addr).await?;
+                let (mut r, mut w) = con.split();
+                let writer = rt1
+                    .spawn_with_handle(async move {
+                        w.write_all(b"Hello world").await?;
+                        w.flush().await?;
+                        IoResult::Ok(w)
+                    })
+                    .expect("spawn failed");
+                let mut buf = vec![0_u8; 11];
+                r.read_exact(&mut buf[..]).await?;
+                // Keep the write half alive until we are done reading.
+                let _w = writer.await?;
+                IoResult::Ok(buf)
+            };
+
+            let (server_r, data) = futures::join!(server, client);
+            server_r?;
+            assert_eq!(&data?[..], b"Hello world");
+
+            Ok(())
+        })
+    }
+
+    // Listen on an ephemeral TCP port, and make sure we learn which one.
+    //
+    // NOTE: requires Ipv4 localhost.
+    fn listen_ephemeral_tcp<R: ToplevelRuntime>(runtime: &R) -> IoResult<()> {
+        use crate::general;
+
+        let base = general::SocketAddr::from(SocketAddr::from(SocketAddrV4::new(
+            Ipv4Addr::LOCALHOST,
+            9999,
+        )));
+        let (listener, addr) = runtime.block_on(general::listen_ephemeral(runtime, &base))?;
+
+        let general::SocketAddr::Inet(inet) = &addr else {
+            panic!("not an inet address: {:?}", addr);
+        };
+        assert!(inet.ip().is_loopback());
+        assert_ne!(inet.port(), 0);
+        assert_eq!(listener.local_addr()?, addr);
+
+        Ok(())
+    }
+
+    // Connect to ourself over TCP, and (where supported) over AF_UNIX,
+    // and check that both ends report the right kind of transport.
+    //
+    // NOTE: requires Ipv4 localhost.
+    fn general_transport_kind<R: ToplevelRuntime>(runtime: &R) -> IoResult<()> {
+        use crate::general::{self, TransportKind};
+
+        let localhost = SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0);
+        let mut bases = vec![(
+            general::SocketAddr::from(SocketAddr::from(localhost)),
+            TransportKind::Tcp,
+        )];
+        if cfg!(unix) {
+            bases.push((
+                general::SocketAddr::unix_pathname(std::env::temp_dir()).unwrap(),
+                TransportKind::Unix,
+            ));
+        }
+
+        for (base, kind) in bases {
+            let (mut listener, addr) =
+                runtime.block_on(general::listen_ephemeral(runtime, &base))?;
+
+            let (accepted, connected) = runtime
+                .block_on(async { futures::join!(listener.accept(), runtime.connect(&addr)) });
+            if let Some(path) = addr.as_pathname() {
+                let _ = std::fs::remove_file(path);
+            }
+
+            assert_eq!(accepted?.0.transport_kind(), kind);
+            assert_eq!(connected?.transport_kind(), kind);
+        }
+
+        Ok(())
+    }
+
+    // Accept a connection on a general::Listener with `accept`,
+    // rather than with its `incoming` stream.
+    //
+    // NOTE: requires Ipv4 localhost.
+    fn general_listener_accept<R: ToplevelRuntime>(runtime: &R) -> IoResult<()> {
+        use crate::general;
+
+        let localhost = SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0);
+        let rt1 = runtime.clone();
+
+        let mut listener = runtime
+            .block_on(rt1.listen(&general::SocketAddr::from(SocketAddr::from(localhost))))?;
+        let addr = listener.local_addr()?;
+
+        runtime.block_on(async {
+            let task1 = async {
+                let (mut con, peer) = listener.accept().await?;
+                let mut buf = vec![0_u8; 11];
+                con.read_exact(&mut buf[..]).await?;
+                IoResult::Ok((buf, con.peer_addr().cloned(), peer))
+            };
+            let task2 = async {
+                let mut con = rt1.connect(&addr).await?;
+                con.write_all(b"Hello world").await?;
+                con.flush().await?;
+                IoResult::Ok(con)
+            };
+
+            let (accepted, connected) = futures::join!(task1, task2);
+            let _con = connected?;
+            let (buf, accepted_peer, peer) = accepted?;
+            assert_eq!(&buf[..], b"Hello world");
+            assert_eq!(accepted_peer, Some(peer.clone()));
+            let general::SocketAddr::Inet(peer) = peer else {
+                panic!("not an inet address: {:?}", peer);
+            };
+            assert!(peer.ip().is_loopback());
+
+            Ok(())
+        })
+    }
+
     // Try connecting to ourself and sending a little data.
     //
     // NOTE: requires Ipv4 localhost.
@@ -680,6 +879,11 @@ mod test {
         tiny_wallclock,
         self_connect_tcp,
         self_connect_udp,
+        self_connect_general_peer_addr,
+        self_connect_general_split,
+        listen_ephemeral_tcp,
+        general_listener_accept,
+        general_transport_kind,
         listener_stream,
     }
 
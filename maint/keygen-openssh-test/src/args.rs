@@ -1,8 +1,12 @@
+use std::path::PathBuf;
+
 use clap::{Parser, ValueEnum};
 
 /// Generate an OpenSSH keypair.
 ///
-/// Outputs the keys to `<name>.public` and `<name>.private`.
+/// Outputs the keys to `<name>.public` and `<name>.private`,
+/// or, if `--keystore-layout` is specified,
+/// writes the private key where the Arti keystore rooted at that directory expects to find it.
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub(crate) struct Args {
@@ -25,9 +29,22 @@ pub(crate) struct Args {
     pub(crate) comment: Option<String>,
 
     /// The output file name.
+    ///
+    /// If `--keystore-layout` is specified, this is the `ArtiPath` of the key instead
+    /// (for example, `hss/allium-cepa/ks_hs_id`).
     #[arg(long)]
     pub(crate) name: String,
 
+    /// Write the private key into the Arti keystore rooted at this directory.
+    ///
+    /// The key is written to `<dir>/<name>.<ext>`,
+    /// where `<name>` is the `ArtiPath` given by `--name`,
+    /// and `<ext>` is the extension the Arti keystore uses for this type of key
+    /// (for example, `ed25519_expanded_private`).
+    /// Any missing directories are created.
+    #[arg(long, value_name = "DIR", conflicts_with = "public")]
+    pub(crate) keystore_layout: Option<PathBuf>,
+
     /// Whether to output a public key file.
     #[arg(long)]
     pub(crate) public: bool,
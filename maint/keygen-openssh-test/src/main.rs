@@ -5,6 +5,7 @@ use tor_llcrypto::pk::ed25519::Ed25519PublicKey as _;
 use tor_llcrypto::util::rng::RngCompat;
 
 use std::fs;
+use std::path::{Path, PathBuf};
 
 use clap::Parser;
 
@@ -12,6 +13,7 @@ use ssh_key::private::{DsaKeypair, Ed25519Keypair, Ed25519PrivateKey, OpaqueKeyp
 use ssh_key::public::{DsaPublicKey, Ed25519PublicKey, OpaquePublicKey};
 use ssh_key::{self, Algorithm, AlgorithmName, PrivateKey, PublicKey};
 use tor_basic_utils::test_rng::testing_rng;
+use tor_keymgr::{ArtiPath, KeyType as ArtiKeyType};
 use tor_llcrypto::pk::{curve25519, ed25519};
 
 /// A helper for creating a ([`PrivateKey`], [`PublicKey`]) pair.
@@ -103,6 +105,62 @@ fn generate_x25519(args: &Args) -> (PrivateKey, PublicKey) {
     make_openssh_key!(Other, args, keypair, public)
 }
 
+/// Return the path of the private key with the specified `ArtiPath`
+/// in the Arti keystore rooted at `keystore_dir`.
+///
+/// This mirrors the layout of the Arti native keystore:
+/// each `/`-separated component of the `ArtiPath` is a directory,
+/// except for the last one, which is the file name,
+/// with an extension given by the key type.
+fn keystore_path(keystore_dir: &Path, arti_path: &str, key_type: KeyType) -> PathBuf {
+    let arti_path = ArtiPath::new(arti_path.into()).unwrap();
+    let key_type = match key_type {
+        KeyType::Ed25519 => ArtiKeyType::Ed25519Keypair,
+        KeyType::ExpandedEd25519 => ArtiKeyType::Ed25519ExpandedKeypair,
+        KeyType::X25519 => ArtiKeyType::X25519StaticKeypair,
+        KeyType::Dsa => panic!("DSA keys can't be stored in an Arti keystore"),
+    };
+
+    let mut path = keystore_dir.join(String::from(arti_path));
+    path.set_extension(key_type.arti_extension());
+    path
+}
+
+/// Write the private key `private` to `path`, creating any missing directories.
+///
+/// Like the Arti keystore, this creates directories and files
+/// that can only be accessed by the current user.
+fn write_to_keystore(path: &Path, private: &str) {
+    let parent = path.parent().unwrap();
+
+    #[cfg(unix)]
+    {
+        use std::io::Write as _;
+        use std::os::unix::fs::{DirBuilderExt as _, OpenOptionsExt as _};
+
+        fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(parent)
+            .unwrap();
+        fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)
+            .unwrap()
+            .write_all(private.as_bytes())
+            .unwrap();
+    }
+
+    #[cfg(not(unix))]
+    {
+        fs::create_dir_all(parent).unwrap();
+        fs::write(path, private).unwrap();
+    }
+}
+
 fn main() {
     let args = Args::parse();
 
@@ -128,6 +186,13 @@ fn main() {
         .unwrap()
         .to_string();
 
+    if let Some(keystore_dir) = &args.keystore_layout {
+        let path = keystore_path(keystore_dir, &args.name, args.key_type);
+        write_to_keystore(&path, &private);
+        println!("created {}", path.display());
+        return;
+    }
+
     let pub_file = format!("{}.public", args.name);
     let priv_file = format!("{}.private", args.name);
 
@@ -127,6 +127,13 @@ def _annotate_library(lib: ctypes.CDLL):
     ]
     lib.arti_rpc_conn_cancel_handle.restype = _ArtiRpcStatus
 
+    lib.arti_rpc_request_cancel.argtypes = [
+        POINTER(ArtiRpcConn),
+        c_char_p,
+        _ErrorOut,
+    ]
+    lib.arti_rpc_request_cancel.restype = _ArtiRpcStatus
+
     lib.arti_rpc_conn_builder_new.argtypes = [_BuilderOut, _ErrorOut]
     lib.arti_rpc_conn_builder_new.restype = _ArtiRpcStatus
 
@@ -409,6 +409,8 @@ class ArtiRpcErrorStatus(Enum):
     ALL_CONNECT_ATTEMPTS_FAILED = 13
     CONNECT_POINT_NOT_USABLE = 14
     BAD_CONNECT_POINT_PATH = 15
+    CONNECTION_RESET = 16
+    TIMEOUT = 17
 
 
 def _error_status_from_int(status: int) -> Union[ArtiRpcErrorStatus, int]: